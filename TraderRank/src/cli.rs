@@ -0,0 +1,463 @@
+//! Command-line arguments: an optional subcommand and the options shared by all of them.
+
+use crate::report::{ReportFormat, ReportTable};
+use anyhow::{bail, Result};
+use chrono::NaiveDate;
+use std::path::PathBuf;
+use std::time::Duration;
+use trader_rank_core::exchange_time::DateRange;
+use trader_rank_core::storage::StoreKind;
+use trader_rank_core::trade_matcher::LotMethod;
+
+pub const USAGE: &str = "\
+usage: trader_rank [COMMAND] [OPTIONS]
+
+Commands:
+  (none)                   Import new and changed files, then show the report
+  import                   Import new and changed files from the source directory
+  report                   Show the performance report
+  tui                      Browse days, weeks and months full-screen, down to each day's round trips
+  trades                   List round trips (--fills: the imported fills)
+  export                   Write round trips as CSV (--fills: the fills; --tax-year YEAR: Form 8949 rows)
+  rebuild                  Re-import every source file from scratch
+  watch                    Import new and changed files as they arrive and print what changed
+  sources                  List imported source files
+  sources forget FILE      Drop a source file's fills from the ledger
+  sources reprocess FILE   Forget a source file and import it again
+  reset                    Delete imported trades and cached analysis; annotations and source files stay
+
+Options:
+  --data-dir DIR           Data directory holding Source/ (default: $TRADERRANK_DATA_DIR, else ../Data)
+  --store json|sqlite      Storage backend (default: SQLite when the data directory has a database)
+  --from YYYY-MM-DD        First trading day to report
+  --to YYYY-MM-DD          Last trading day to report
+  --symbol SYMBOL          Only this symbol
+  --account ID             Only this account
+  --swing                  Carry positions across days (swing trades)
+  --lots METHOD            Lot matching: fifo, lifo, average or specific-id
+  --format FORMAT          report: text (default), json, csv or md
+  --html FILE              report: write a self-contained HTML report with charts to FILE
+  --table TABLE            report: only overall, daily, weekly, monthly or periods (csv: default daily)
+  --fills                  trades, export: fills instead of round trips
+  --tax-year YEAR          export: Form 8949 rows for that year (default file Tax/form_8949_YEAR.csv)
+  --output FILE            export, report --format: write to FILE instead of standard output
+  --imports-dir DIR        import, watch: also read trade files from DIR (e.g. the desktop app's imports)
+  --interval SECS          watch: seconds between checks (default 2)
+  --yes                    reset: do not ask for confirmation
+  -h, --help               Show this help";
+
+/// How often `watch` checks the source directories unless `--interval` says otherwise.
+const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// What to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// No subcommand: import, then report
+    Run,
+    Import,
+    Report,
+    /// Full-screen browser
+    Tui,
+    Trades,
+    Export,
+    Rebuild,
+    /// Import files as they arrive
+    Watch,
+    Sources(SourcesAction),
+    Reset,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourcesAction {
+    List,
+    Forget(String),
+    Reprocess(String),
+}
+
+impl Command {
+    fn name(&self) -> &'static str {
+        match self {
+            Command::Run => "the default run",
+            Command::Import => "import",
+            Command::Report => "report",
+            Command::Tui => "tui",
+            Command::Trades => "trades",
+            Command::Export => "export",
+            Command::Rebuild => "rebuild",
+            Command::Watch => "watch",
+            Command::Sources(_) => "sources",
+            Command::Reset => "reset",
+        }
+    }
+
+    /// Commands that report on trades, to which --from, --to, --symbol and --account apply.
+    fn reports(&self) -> bool {
+        matches!(self, Command::Run | Command::Report | Command::Tui | Command::Trades | Command::Export)
+    }
+}
+
+/// Command-line options.
+pub struct CliArgs {
+    pub command: Command,
+    /// `--data-dir DIR`: the data directory, if given
+    pub data_dir: Option<PathBuf>,
+    /// `--store json|sqlite`: storage backend; by default SQLite when a database exists
+    pub store: Option<StoreKind>,
+    /// The account named by `--account ID` (or `--account=ID`), if any
+    pub account: Option<String>,
+    /// `--symbol SYMBOL`: only this symbol's trades
+    pub symbol: Option<String>,
+    /// `--from` and `--to`: trading days reported, both included
+    pub range: DateRange,
+    /// `--swing`: carry positions across days instead of analyzing day trades only
+    pub carry_positions: bool,
+    /// `--lots METHOD`: which open lots closing fills are matched against
+    pub lot_method: LotMethod,
+    /// `--format FORMAT`: how `report` writes its output
+    pub format: ReportFormat,
+    /// `--html FILE`: where `report` writes its HTML report
+    pub html: Option<PathBuf>,
+    /// `--table TABLE`: the one report table to write
+    pub table: Option<ReportTable>,
+    /// `--fills`: list or export fills rather than round trips
+    pub fills: bool,
+    /// `--tax-year YEAR`: export the tax lot report for that year
+    pub tax_year: Option<i32>,
+    /// `--output FILE`: where export writes
+    pub output: Option<PathBuf>,
+    /// `--imports-dir DIR`: a second directory of trade files to import
+    pub imports_dir: Option<PathBuf>,
+    /// `--interval SECS`: how often `watch` checks for files, if given
+    pub interval: Option<Duration>,
+    /// `--yes`: reset without asking
+    pub yes: bool,
+    /// `-h` or `--help`
+    pub help: bool,
+}
+
+impl CliArgs {
+    /// Commands whose standard output is data for scripts; their progress
+    /// messages go to standard error.
+    pub fn prints_data(&self) -> bool {
+        matches!(self.command, Command::Trades | Command::Export) || self.format != ReportFormat::Text
+    }
+
+    /// How often `watch` checks the source directories.
+    pub fn watch_interval(&self) -> Duration {
+        self.interval.unwrap_or(DEFAULT_WATCH_INTERVAL)
+    }
+
+    pub fn parse() -> Result<Self> {
+        Self::parse_from(std::env::args().skip(1))
+    }
+
+    fn parse_from(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut args = args.into_iter();
+        let mut positional = Vec::new();
+        let mut parsed = CliArgs {
+            command: Command::Run,
+            data_dir: None,
+            store: None,
+            account: None,
+            symbol: None,
+            range: DateRange::default(),
+            carry_positions: false,
+            lot_method: LotMethod::default(),
+            format: ReportFormat::default(),
+            html: None,
+            table: None,
+            fills: false,
+            tax_year: None,
+            output: None,
+            imports_dir: None,
+            interval: None,
+            yes: false,
+            help: false,
+        };
+
+        while let Some(arg) = args.next() {
+            if !arg.starts_with('-') {
+                positional.push(arg);
+                continue;
+            }
+
+            // Options take their value as `--name=value` or `--name value`
+            let (name, mut inline) = match arg.split_once('=') {
+                Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                None => (arg.clone(), None),
+            };
+            let mut value = |hint: &str| match inline.take().or_else(|| args.next()) {
+                Some(value) => Ok(value),
+                None => bail!("{} needs {}", name, hint),
+            };
+
+            match name.as_str() {
+                "--data-dir" => parsed.data_dir = Some(PathBuf::from(value("a directory, e.g. --data-dir ~/TraderRank/Data")?)),
+                "--store" => parsed.store = Some(parse_store_kind(&value("a backend: json or sqlite")?)?),
+                "--account" => parsed.account = Some(value("an account ID, e.g. --account U1234567")?),
+                "--symbol" => parsed.symbol = Some(value("a symbol, e.g. --symbol AAPL")?),
+                "--from" => parsed.range.from = Some(parse_date(&name, &value("a date, e.g. --from 2024-01-01")?)?),
+                "--to" => parsed.range.to = Some(parse_date(&name, &value("a date, e.g. --to 2024-12-31")?)?),
+                "--lots" => parsed.lot_method = parse_lot_method(&value("a method: fifo, lifo, average or specific-id")?)?,
+                "--format" => parsed.format = parse_format(&value("a format: text, json, csv or md")?)?,
+                "--html" => parsed.html = Some(PathBuf::from(value("a file name, e.g. --html report.html")?)),
+                "--table" => parsed.table = Some(parse_table(&value("a table: overall, daily, weekly, monthly or periods")?)?),
+                "--tax-year" => parsed.tax_year = Some(parse_tax_year(&value("a year, e.g. --tax-year 2024")?)?),
+                "--output" => parsed.output = Some(PathBuf::from(value("a file name, e.g. --output trades.csv")?)),
+                "--imports-dir" => parsed.imports_dir = Some(PathBuf::from(value("a directory, e.g. --imports-dir ~/Downloads/Flex")?)),
+                "--interval" => parsed.interval = Some(parse_interval(&value("a number of seconds, e.g. --interval 5")?)?),
+                "--swing" | "--fills" | "--yes" | "--help" | "-h" => {
+                    if inline.is_some() {
+                        bail!("{} does not take a value", name);
+                    }
+                    match name.as_str() {
+                        "--swing" => parsed.carry_positions = true,
+                        "--fills" => parsed.fills = true,
+                        "--yes" => parsed.yes = true,
+                        _ => parsed.help = true,
+                    }
+                }
+                _ => bail!("Unknown option '{}' (see --help)", arg),
+            }
+        }
+
+        parsed.command = parse_command(positional)?;
+        parsed.account = parsed.account.filter(|a| !a.trim().is_empty());
+        parsed.symbol = parsed.symbol.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+        parsed.validate()?;
+        Ok(parsed)
+    }
+
+    /// Reject options that do nothing for the command, so a mistyped script fails loudly.
+    fn validate(&self) -> Result<()> {
+        let command = &self.command;
+        let filters = [
+            ("--from", self.range.from.is_some()),
+            ("--to", self.range.to.is_some()),
+            ("--symbol", self.symbol.is_some()),
+            ("--account", self.account.is_some()),
+        ];
+        for (option, given) in filters {
+            if given && !command.reports() {
+                bail!("{} does not apply to {}; it filters report, tui, trades and export", option, command.name());
+            }
+        }
+        if let (Some(from), Some(to)) = (self.range.from, self.range.to) {
+            if from > to {
+                bail!("--from {} is after --to {}", from, to);
+            }
+        }
+
+        let only = [
+            ("--fills", self.fills, matches!(command, Command::Trades | Command::Export)),
+            ("--tax-year", self.tax_year.is_some(), *command == Command::Export),
+            ("--format", self.format != ReportFormat::Text, *command == Command::Report),
+            ("--html", self.html.is_some(), *command == Command::Report),
+            ("--table", self.table.is_some(), *command == Command::Report),
+            ("--output", self.output.is_some(), matches!(command, Command::Export | Command::Report)),
+            ("--imports-dir", self.imports_dir.is_some(), matches!(command, Command::Import | Command::Watch)),
+            ("--interval", self.interval.is_some(), *command == Command::Watch),
+            ("--yes", self.yes, *command == Command::Reset),
+        ];
+        for (option, given, applies) in only {
+            if given && !applies {
+                bail!("{} does not apply to {}", option, command.name());
+            }
+        }
+        if self.html.is_some() && self.format != ReportFormat::Text {
+            bail!("--html and --format cannot be combined; the HTML report is written on its own");
+        }
+        if *command == Command::Report && self.format == ReportFormat::Text {
+            for (option, given) in [("--table", self.table.is_some()), ("--output", self.output.is_some())] {
+                if given {
+                    bail!("{} needs --format json, csv or md", option);
+                }
+            }
+        }
+        if self.tax_year.is_some() {
+            for (option, given) in [("--fills", self.fills), ("--from", self.range.from.is_some()), ("--to", self.range.to.is_some()), ("--symbol", self.symbol.is_some())] {
+                if given {
+                    bail!("{} does not apply to --tax-year, which reports every lot sold that year", option);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_command(positional: Vec<String>) -> Result<Command> {
+    let mut words = positional.into_iter();
+    let Some(word) = words.next() else {
+        return Ok(Command::Run);
+    };
+
+    let command = match word.as_str() {
+        "import" => Command::Import,
+        "report" => Command::Report,
+        "tui" => Command::Tui,
+        "trades" => Command::Trades,
+        "export" => Command::Export,
+        "rebuild" => Command::Rebuild,
+        "watch" => Command::Watch,
+        "reset" => Command::Reset,
+        "sources" => {
+            let action = match (words.next().as_deref(), words.next()) {
+                (None, _) | (Some("list"), None) => SourcesAction::List,
+                (Some("forget"), Some(name)) => SourcesAction::Forget(name),
+                (Some("reprocess"), Some(name)) => SourcesAction::Reprocess(name),
+                (Some(action @ ("forget" | "reprocess")), None) => bail!("sources {} needs a source file name (see `trader_rank sources`)", action),
+                (Some(action), _) => bail!("Unknown sources action '{}' (expected forget or reprocess)", action),
+            };
+            Command::Sources(action)
+        }
+        _ => bail!("Unknown command '{}' (see --help)", word),
+    };
+
+    if let Some(extra) = words.next() {
+        bail!("Unexpected argument '{}' after {}", extra, command.name());
+    }
+    Ok(command)
+}
+
+fn parse_date(option: &str, value: &str) -> Result<NaiveDate> {
+    match NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d") {
+        Ok(date) => Ok(date),
+        Err(_) => bail!("Invalid date '{}' for {} (expected YYYY-MM-DD)", value, option),
+    }
+}
+
+fn parse_format(name: &str) -> Result<ReportFormat> {
+    match ReportFormat::from_name(name) {
+        Some(format) => Ok(format),
+        None => bail!("Unknown format '{}' (expected text, json, csv or md)", name),
+    }
+}
+
+fn parse_table(name: &str) -> Result<ReportTable> {
+    match ReportTable::from_name(name) {
+        Some(table) => Ok(table),
+        None => bail!("Unknown table '{}' (expected overall, daily, weekly, monthly or periods)", name),
+    }
+}
+
+fn parse_lot_method(name: &str) -> Result<LotMethod> {
+    match LotMethod::from_name(name) {
+        Some(method) => Ok(method),
+        None => bail!("Unknown lot method '{}' (expected fifo, lifo, average or specific-id)", name),
+    }
+}
+
+fn parse_interval(value: &str) -> Result<Duration> {
+    match value.trim().parse::<u64>() {
+        Ok(secs) if (1..=3600).contains(&secs) => Ok(Duration::from_secs(secs)),
+        _ => bail!("Invalid interval '{}' (expected 1 to 3600 seconds)", value),
+    }
+}
+
+fn parse_store_kind(name: &str) -> Result<StoreKind> {
+    match StoreKind::from_name(name) {
+        Some(kind) => Ok(kind),
+        None => bail!("Unknown store '{}' (expected json or sqlite)", name),
+    }
+}
+
+fn parse_tax_year(value: &str) -> Result<i32> {
+    match value.trim().parse::<i32>() {
+        Ok(year) if (1900..=9999).contains(&year) => Ok(year),
+        _ => bail!("Invalid tax year '{}' (expected a year such as 2024)", value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<CliArgs> {
+        CliArgs::parse_from(args.split_whitespace().map(str::to_string))
+    }
+
+    fn error(args: &str) -> String {
+        match parse(args) {
+            Ok(_) => panic!("`{}` was accepted", args),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn subcommands() {
+        assert_eq!(parse("").unwrap().command, Command::Run);
+        assert_eq!(parse("report").unwrap().command, Command::Report);
+        assert_eq!(parse("tui").unwrap().command, Command::Tui);
+        assert_eq!(parse("sources").unwrap().command, Command::Sources(SourcesAction::List));
+        assert_eq!(parse("sources list").unwrap().command, Command::Sources(SourcesAction::List));
+        assert_eq!(parse("sources forget a.csv").unwrap().command, Command::Sources(SourcesAction::Forget("a.csv".to_string())));
+        assert_eq!(parse("sources reprocess a.csv").unwrap().command, Command::Sources(SourcesAction::Reprocess("a.csv".to_string())));
+
+        assert!(error("sources forget").contains("needs a source file name"));
+        assert!(error("sources drop a.csv").contains("Unknown sources action 'drop'"));
+        assert!(error("import now").contains("Unexpected argument 'now' after import"));
+        assert!(error("show").contains("Unknown command 'show'"));
+        assert!(error("report --verbose").contains("Unknown option '--verbose'"));
+    }
+
+    #[test]
+    fn filters_and_matching_options() {
+        let args = parse("trades --account U1 --symbol=aapl --lots fifo --swing --fills").unwrap();
+        assert_eq!(args.command, Command::Trades);
+        assert_eq!(args.account.as_deref(), Some("U1"));
+        assert_eq!(args.symbol.as_deref(), Some("aapl"));
+        assert_eq!(args.lot_method, LotMethod::Fifo);
+        assert!(args.carry_positions && args.fills);
+        assert!(args.prints_data());
+
+        assert!(error("import --symbol AAPL").contains("--symbol does not apply to import"));
+        assert!(error("sources --account U1").contains("--account does not apply to sources"));
+        assert!(error("report --lots newest").contains("Unknown lot method 'newest'"));
+        assert!(error("report --account").contains("--account needs an account ID"));
+        assert!(error("report --swing=yes").contains("--swing does not take a value"));
+    }
+
+    #[test]
+    fn date_ranges() {
+        let args = parse("report --from 2024-01-02 --to=2024-01-31").unwrap();
+        assert_eq!(args.range.from, NaiveDate::from_ymd_opt(2024, 1, 2));
+        assert_eq!(args.range.to, NaiveDate::from_ymd_opt(2024, 1, 31));
+        assert!(parse("report --to 2024-01-31").unwrap().range.from.is_none());
+
+        assert!(error("report --from 2024-02-01 --to 2024-01-01").contains("--from 2024-02-01 is after --to 2024-01-01"));
+        assert!(error("report --from 2024-13-01").contains("Invalid date '2024-13-01' for --from"));
+        assert!(error("rebuild --from 2024-01-01").contains("--from does not apply to rebuild"));
+    }
+
+    #[test]
+    fn watch_interval() {
+        let args = parse("watch").unwrap();
+        assert_eq!(args.interval, None);
+        assert_eq!(args.watch_interval(), DEFAULT_WATCH_INTERVAL);
+        assert_eq!(parse("watch --interval 5").unwrap().watch_interval(), Duration::from_secs(5));
+
+        // The default value still only applies to watch
+        assert!(error("report --interval 2").contains("--interval does not apply to report"));
+        assert!(error("watch --interval 0").contains("Invalid interval '0'"));
+        assert!(error("watch --interval 3601").contains("Invalid interval '3601'"));
+    }
+
+    #[test]
+    fn options_that_cannot_be_combined() {
+        let args = parse("report --format csv --table weekly --output weeks.csv").unwrap();
+        assert_eq!(args.format, ReportFormat::Csv);
+        assert_eq!(args.table, Some(ReportTable::Weekly));
+        assert!(args.prints_data());
+        assert_eq!(parse("export --tax-year 2024 --account U1").unwrap().tax_year, Some(2024));
+
+        assert!(error("report --html r.html --format json").contains("--html and --format cannot be combined"));
+        assert!(error("report --table daily").contains("--table needs --format"));
+        assert!(error("report --output r.txt").contains("--output needs --format"));
+        assert!(error("trades --format json").contains("--format does not apply to trades"));
+        assert!(error("export --tax-year 2024 --fills").contains("--fills does not apply to --tax-year"));
+        assert!(error("export --tax-year 2024 --symbol AAPL").contains("--symbol does not apply to --tax-year"));
+        assert!(error("report --tax-year 2024").contains("--tax-year does not apply to report"));
+        assert!(error("import --yes").contains("--yes does not apply to import"));
+        assert!(error("report --imports-dir x").contains("--imports-dir does not apply to report"));
+    }
+}
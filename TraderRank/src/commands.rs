@@ -0,0 +1,709 @@
+//! What each subcommand does, against the store and source directory of one data directory.
+
+use crate::report::{self, Report, ReportFormat, ReportTable, Scope};
+use crate::tui;
+use crate::visualization::{CalendarRenderer, ChartRenderer, TableRenderer, WeeklyRenderer};
+use anyhow::{bail, Result};
+use colored::*;
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use trader_rank_core::analytics::{TimePatternAnalyzer, TradingAnalytics};
+use trader_rank_core::dedup::{dedupe_sources, DedupOutcome};
+use trader_rank_core::exchange_time::{DateRange, DEFAULT_EXCHANGE_TZ};
+use trader_rank_core::export;
+use trader_rank_core::models::{Trade, TradingSummary};
+use trader_rank_core::parser::{CsvParser, ParseReport};
+use trader_rank_core::storage::{self, ProcessedData, SourceFile, SourceScan, SourceStatus, Store};
+use trader_rank_core::tax::TaxReport;
+use trader_rank_core::trade_matcher::{match_trades_with, MatchOptions, OpenPosition};
+
+/// Everything a command works with.
+pub struct App {
+    pub data_dir: PathBuf,
+    pub source_dir: PathBuf,
+    /// `--imports-dir`: a second directory of trade files, such as the desktop app's imports
+    pub imports_dir: Option<PathBuf>,
+    pub store: Box<dyn Store>,
+    pub parser: CsvParser,
+    pub match_options: MatchOptions,
+    /// `--account`: only this account's trades are reported
+    pub account: Option<String>,
+    /// `--symbol`: only this symbol's trades are reported
+    pub symbol: Option<String>,
+    /// `--from`/`--to`: trading days reported
+    pub range: DateRange,
+    /// Standard output is data: progress messages go to standard error
+    pub quiet: bool,
+}
+
+impl App {
+    /// Merge new and changed source files into the trade ledger and re-analyze
+    /// it. `reprocess` names a forgotten file to import under its own name
+    /// even when another file has the same contents.
+    pub fn import(&self, reprocess: Option<&str>) -> Result<()> {
+        self.require_source_dir()?;
+        println!("{}", "📂 Checking for new trade data...".yellow());
+
+        let mut scans = self.store.scan_source_files(self.source_paths()?)?;
+        if scans.is_empty() {
+            println!("{}", format!("⚠️  No trade files in {}", self.source_dir.display()).yellow());
+        }
+
+        if let Some(name) = reprocess {
+            if let Some(hash) = scans.iter().find(|scan| scan.file.name == name).map(|scan| scan.file.hash.clone()) {
+                for scan in &mut scans {
+                    if scan.file.name == name {
+                        scan.status = SourceStatus::New;
+                    } else if scan.status == SourceStatus::New && scan.file.hash == hash {
+                        scan.status = SourceStatus::Copy(name.to_string());
+                    }
+                }
+            }
+        }
+
+        // Renamed copies of imported files are recorded, so they are not hashed again, but not imported
+        let mut copies = Vec::new();
+        for scan in &scans {
+            if let SourceStatus::Copy(original) = &scan.status {
+                println!("{}", format!("  📑 {} has the same contents as {}; skipped", scan.file.name, original).yellow());
+                copies.push(scan.file.clone());
+            }
+        }
+
+        let new_files: Vec<&SourceScan> = scans.iter().filter(|scan| scan.needs_import()).collect();
+        if new_files.is_empty() {
+            if !scans.is_empty() {
+                println!("{}", "✅ All files already processed.".green());
+            }
+            if let Some(mut processed_data) = self.store.load_processed_data()?.filter(|_| !copies.is_empty()) {
+                processed_data.sources.extend(copies);
+                self.store.save_processed_data(&processed_data)?;
+            }
+            return Ok(());
+        }
+
+        let changed = new_files.iter().filter(|scan| scan.status == SourceStatus::Changed).count();
+        match changed {
+            0 => println!("{}", format!("🔍 Found {} new file(s) to process", new_files.len()).green()),
+            _ => println!("{}", format!("🔍 Found {} new or changed file(s) to process ({} changed)", new_files.len(), changed).green()),
+        }
+
+        // Fills from earlier runs are merged with the new files' fills, so the
+        // analysis covers the whole history and re-imported fills are dropped
+        let ledger = self.store.load_trades()?.unwrap_or_default();
+        if !ledger.is_empty() {
+            println!("{}", format!("📥 Loading {} trades from the trade ledger...", ledger.len()).cyan());
+        } else if self.store.load_processed_data()?.is_some() {
+            println!("{}", "📚 No trade ledger yet; reading every source file to build it".yellow());
+        }
+
+        let mut dropped_lines = 0;
+        let mut file_trades = vec![ledger];
+        let mut file_names = vec!["trade ledger".to_string()];
+
+        for scan in &new_files {
+            let file_name = scan.file.name.clone();
+            match scan.status {
+                SourceStatus::Changed => println!("{}", format!("  📄 Re-importing changed file: {}", file_name).white()),
+                _ => println!("{}", format!("  📄 Processing: {}", file_name).white()),
+            }
+            let mut report = self.parser.parse_file(&scan.path)?;
+            print_parse_report(&report);
+            dropped_lines += report.diagnostics.len();
+            for trade in &mut report.trades {
+                trade.source = Some(file_name.clone());
+            }
+            file_trades.push(report.trades);
+            file_names.push(file_name);
+        }
+
+        if dropped_lines > 0 {
+            println!("{}", format!("⚠️  {} line(s) could not be imported (see above)", dropped_lines).yellow());
+        }
+
+        // Fills a changed file shares with its earlier import are already in the ledger;
+        // only its new fills are merged
+        let mut dedup = dedupe_sources(file_trades);
+        let already_imported = dedup.duplicates.len();
+        dedup.duplicates.retain(|d| d.kept_source != 0 || d.kept.source != d.dropped.source);
+        let already_imported = already_imported - dedup.duplicates.len();
+        if already_imported > 0 {
+            println!("{}", format!("📎 {} fill(s) of changed files were already in the ledger", already_imported).dimmed());
+        }
+
+        let duplicate_count = dedup.duplicates.len();
+        if duplicate_count > 0 {
+            println!("{}", format!("⚠️  Found {} duplicate trade(s), filtering them out", duplicate_count).yellow());
+            print_fuzzy_duplicates(&dedup, &file_names);
+        }
+
+        let all_trades = dedup.trades;
+        println!("{}", format!("✅ Processing {} unique trades (filtered {} duplicates)", all_trades.len(), duplicate_count).green());
+
+        let imported = new_files.iter()
+            .map(|scan| SourceFile {
+                trades: all_trades.iter().filter(|t| t.source.as_deref() == Some(scan.file.name.as_str())).count(),
+                ..scan.file.clone()
+            })
+            .chain(copies)
+            .collect();
+        self.save_analysis(&all_trades, imported)
+    }
+
+    /// Show the report for the selected account, symbol and days: the console
+    /// report, or with another `format` the report tables written to `output`
+    /// or standard output, or with `html` an HTML report written to that file.
+    ///
+    /// The cached analysis is used when it covers exactly what was asked for;
+    /// otherwise the trade ledger is analyzed again.
+    pub fn report(&self, format: ReportFormat, table: Option<ReportTable>, output: Option<&Path>, html: Option<&Path>) -> Result<()> {
+        let processed = self.store.load_processed_data()?;
+        let ledger = self.store.load_trades()?;
+        if processed.is_none() && ledger.is_none() {
+            bail!("No trades imported yet; run `trader_rank import` first");
+        }
+
+        let filtered = self.symbol.is_some() || !self.range.is_all();
+        let fills = ledger.map(|ledger| self.select(ledger)).transpose()?;
+        let summary = match (processed, &fills) {
+            (Some(data), _) if !filtered && self.is_cached(&data) => {
+                self.notice("📊 Loading cached analysis...".cyan());
+                self.select_account_summary(data.summary, data.account_summaries)?
+            }
+            (data, Some(fills)) => {
+                if data.is_some() && !filtered {
+                    self.notice("🔄 The cached analysis used other settings; analyzing the trade ledger".cyan());
+                }
+                self.print_scope(&TradingAnalytics::accounts(fills));
+                let (summary, open) = TradingAnalytics::analyze_positions_in(fills, self.match_options, self.range);
+                warn_open_positions(&open, self.match_options);
+                summary
+            }
+            (Some(data), None) => {
+                if filtered {
+                    bail!("--from, --to and --symbol need the trade ledger; run `trader_rank rebuild` to build it");
+                }
+                self.warn_stale(&data);
+                self.select_account_summary(data.summary, data.account_summaries)?
+            }
+            (None, None) => unreachable!(),
+        };
+
+        if summary.daily_summaries.is_empty() {
+            if format != ReportFormat::Text {
+                bail!("No round trips were closed on the selected days");
+            }
+            println!("{}", "⚠️  No round trips were closed on the selected days".yellow());
+            return Ok(());
+        }
+
+        let periods = match &fills {
+            Some(fills) => TimePatternAnalyzer::identify_best_trading_periods_in(fills, self.match_options, self.range),
+            None => Vec::new(),
+        };
+
+        let scope = || Scope::new(self.account.clone(), self.symbol.clone(), self.range, self.match_options.exchange_tz);
+        if let Some(path) = html {
+            let mut matched = match &fills {
+                Some(fills) => match_trades_with(fills, self.match_options),
+                None => Vec::new(),
+            };
+            matched.retain(|mt| self.range.contains(mt.exit_date(self.match_options.exchange_tz)));
+            let report = Report::build(&summary, &periods, scope());
+            report::write_html(&report, &summary, &matched, File::create(path)?)?;
+            println!("{}", format!("💾 HTML report written to {}", path.display()).green());
+            return Ok(());
+        }
+
+        if format != ReportFormat::Text {
+            let report = Report::build(&summary, &periods, scope());
+            match output {
+                Some(path) => {
+                    report::write_report(&report, format, table, File::create(path)?)?;
+                    eprintln!("{}", format!("💾 Report written to {}", path.display()).green());
+                }
+                None => report::write_report(&report, format, table, std::io::stdout().lock())?,
+            }
+            return Ok(());
+        }
+
+        println!("{}", "📊 Generating reports...".cyan());
+        TableRenderer::render_summary(&summary, 10);
+        ChartRenderer::render_pnl_chart(&summary.daily_summaries);
+
+        // Add daily win rate chart - right after P&L charts for visibility
+        ChartRenderer::render_daily_winrate_chart(&summary.daily_summaries);
+
+        // Add win rate progression chart
+        ChartRenderer::render_winrate_progression(&summary.daily_summaries);
+
+        if let Some(last_day) = summary.daily_summaries.last() {
+            ChartRenderer::render_hourly_distribution(last_day);
+        }
+
+        // Add weekly analysis
+        WeeklyRenderer::render_weekly_analysis(&summary);
+
+        // Add 6-month summary for longer-term perspective
+        WeeklyRenderer::render_six_month_summary(&summary);
+
+        // Add calendar views (last 4 weeks)
+        CalendarRenderer::render_combined_calendars(&summary);
+
+        if fills.is_some() {
+            println!("\n{}", "🎯 Best Trading Periods Analysis".bold().cyan());
+            for (i, period) in periods.iter().take(3).enumerate() {
+                let medal = match i {
+                    0 => "🥇",
+                    1 => "🥈",
+                    2 => "🥉",
+                    _ => "  ",
+                };
+                println!("{} {} ({:02}:00-{:02}:00): ${:.2} | Win Rate: {:.1}%",
+                    medal,
+                    period.name.bold(),
+                    period.start_hour,
+                    period.end_hour,
+                    period.total_pnl,
+                    period.win_rate
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Browse the selected account's round trips full-screen. Positions are
+    /// matched over the whole ledger, as in the report.
+    pub fn browse(&self) -> Result<()> {
+        let fills = self.select(self.ledger()?)?;
+        let mut scope = Vec::new();
+        if let Some(account) = &self.account {
+            scope.push(format!("account {}", account));
+        }
+        match (self.range.from, self.range.to) {
+            (Some(from), Some(to)) => scope.push(format!("{} to {}", from, to)),
+            (Some(from), None) => scope.push(format!("from {}", from)),
+            (None, Some(to)) => scope.push(format!("through {}", to)),
+            (None, None) => {}
+        }
+        scope.push(format!("days in {}", self.match_options.exchange_tz));
+        println!("{}", "🧮 Analyzing the trade ledger...".cyan());
+        tui::run(&fills, self.match_options, self.range, scope.join(" · "))
+    }
+
+    /// Print the round trips closed on the selected days, or with `fills` the
+    /// fills made on them, oldest first.
+    pub fn list_trades(&self, fills: bool) -> Result<()> {
+        let selected = self.select(self.ledger()?)?;
+        let tz = self.match_options.exchange_tz;
+
+        if fills {
+            let fills: Vec<&Trade> = selected.iter().filter(|t| self.range.contains(t.trading_date(tz))).collect();
+            println!("{:<20} {:<8} {:<5} {:>10} {:>12} {:>10}  {:<10} Source",
+                "Time", "Symbol", "Side", "Qty", "Price", "Comm", "Account");
+            println!("{}", "-".repeat(100));
+            for t in &fills {
+                println!("{:<20} {:<8} {:<5} {:>10} {:>12} {:>10.2}  {:<10} {}",
+                    t.time.with_timezone(&tz).format("%Y-%m-%d %H:%M:%S"),
+                    t.symbol,
+                    t.side,
+                    t.quantity.normalize(),
+                    t.fill_price.normalize(),
+                    t.commission,
+                    t.account.as_deref().unwrap_or("-"),
+                    t.source.as_deref().unwrap_or("-"),
+                );
+            }
+            eprintln!("{}", format!("{} fill(s)", fills.len()).dimmed());
+            return Ok(());
+        }
+
+        let mut matched = match_trades_with(&selected, self.match_options);
+        matched.retain(|mt| self.range.contains(mt.exit_date(tz)));
+        println!("{:<17} {:<8} {:<5} {:>10} {:>12} {:>12} {:>12}  {:<8} Account",
+            "Closed", "Symbol", "Side", "Qty", "Entry", "Exit", "Net P&L", "Holding");
+        println!("{}", "-".repeat(100));
+        for mt in &matched {
+            let pnl = format!("{:>12}", format!("{:.2}", mt.net_pnl));
+            println!("{:<17} {:<8} {:<5} {:>10} {:>12} {:>12} {}  {:<8} {}",
+                mt.exit_time.with_timezone(&tz).format("%Y-%m-%d %H:%M"),
+                mt.symbol,
+                mt.side,
+                mt.quantity.normalize(),
+                mt.entry_price.round_dp(4).normalize(),
+                mt.exit_price.normalize(),
+                if mt.net_pnl < Decimal::ZERO { pnl.red() } else { pnl.green() },
+                if mt.is_swing() { "swing" } else { "intraday" },
+                mt.account.as_deref().unwrap_or("-"),
+            );
+        }
+        let total: Decimal = matched.iter().map(|mt| mt.net_pnl).sum();
+        eprintln!("{}", format!("{} round trip(s), net P&L ${:.2}", matched.len(), total).dimmed());
+        Ok(())
+    }
+
+    /// Write the selected round trips, or fills, as CSV to `output` or standard output.
+    pub fn export(&self, fills: bool, output: Option<&Path>) -> Result<()> {
+        let selected = self.select(self.ledger()?)?;
+        let tz = self.match_options.exchange_tz;
+        let writer: Box<dyn Write> = match output {
+            Some(path) => Box::new(File::create(path)?),
+            None => Box::new(std::io::stdout().lock()),
+        };
+
+        let rows = if fills {
+            let fills: Vec<Trade> = selected.into_iter().filter(|t| self.range.contains(t.trading_date(tz))).collect();
+            export::write_fills_csv(&fills, tz, writer)?;
+            fills.len()
+        } else {
+            let mut matched = match_trades_with(&selected, self.match_options);
+            matched.retain(|mt| self.range.contains(mt.exit_date(tz)));
+            export::write_round_trips_csv(&matched, tz, writer)?;
+            matched.len()
+        };
+
+        if let Some(path) = output {
+            let what = if fills { "fill" } else { "round trip" };
+            eprintln!("{}", format!("💾 {} {}(s) written to {}", rows, what, path.display()).green());
+        }
+        Ok(())
+    }
+
+    /// Build the tax lot report from the trade ledger, print the year's totals and
+    /// write its Form 8949 CSV to `output`, by default in `Tax/`.
+    ///
+    /// Lots have to be matched across the whole history, so the cached analysis is not used.
+    /// Wash sales are found across all accounts; `--account` only limits the lots reported.
+    pub fn export_tax_report(&self, year: i32, output: Option<&Path>) -> Result<()> {
+        eprintln!("{}", format!("🧾 Building tax lot report for {}...", year).cyan());
+
+        let trades = self.ledger()?;
+        let mut report = TaxReport::build(&trades, self.match_options);
+        if let Some(account) = &self.account {
+            eprintln!("{}", format!("👤 Account: {}", account).cyan());
+            report.lots.retain(|lot| lot.account.as_deref() == Some(account.as_str()));
+        }
+        if self.match_options.lot_method != report.lot_method {
+            eprintln!("{}", format!("🧾 Lots matched by {}; {} is not a tax lot method", report.lot_method, self.match_options.lot_method).yellow());
+        }
+
+        let totals = report.totals(year);
+        if totals.lots == 0 {
+            let years: Vec<String> = report.tax_years().iter().map(i32::to_string).collect();
+            if years.is_empty() {
+                bail!("No closed lots to report");
+            }
+            bail!("No lots were sold in {} (years with sales: {})", year, years.join(", "));
+        }
+
+        let gain = |amount: Decimal| {
+            let text = format!("${:.2}", amount);
+            if amount < Decimal::ZERO { text.red() } else { text.green() }
+        };
+        eprintln!("{}", format!("   Closed lots:      {}", totals.lots).white());
+        eprintln!("   Short-term gain:  {}", gain(totals.short_term_gain));
+        eprintln!("   Long-term gain:   {}", gain(totals.long_term_gain));
+        if totals.wash_sales > 0 {
+            eprintln!("{}", format!("   Wash sales:       {} (${:.2} loss disallowed)", totals.wash_sales, totals.disallowed_loss).yellow());
+        }
+
+        let path = match output {
+            Some(path) => path.to_path_buf(),
+            None => {
+                let tax_dir = self.data_dir.join("Tax");
+                std::fs::create_dir_all(&tax_dir)?;
+                tax_dir.join(format!("form_8949_{}.csv", year))
+            }
+        };
+        report.write_form_8949(year, File::create(&path)?)?;
+        eprintln!("{}", format!("💾 Form 8949 rows written to {}", path.display()).green());
+        Ok(())
+    }
+
+    /// Clear the ledger and import every source file again.
+    pub fn rebuild(&self) -> Result<()> {
+        self.require_source_dir()?;
+        self.store.clear()?;
+        println!("{}", "🧹 Cleared the trade ledger and cached analysis; re-importing every source file".yellow());
+        self.import(None)
+    }
+
+    /// List the imported source files and how the source directory compares with them.
+    pub fn list_sources(&self) -> Result<()> {
+        let scans = match self.source_dir.is_dir() {
+            true => self.store.scan_sources(&self.source_dir)?,
+            false => Vec::new(),
+        };
+        print_sources(&self.store.load_sources()?, &scans);
+        Ok(())
+    }
+
+    /// Drop a source file's fills from the ledger and re-analyze what is left.
+    pub fn forget_source(&self, name: &str) -> Result<()> {
+        self.forget(name)?;
+        if storage::source_files(&self.source_dir).unwrap_or_default().iter().any(|path| storage::file_name(path) == name) {
+            println!("{}", format!("⚠️  {} is still in {} and will be imported again next run", name, self.source_dir.display()).yellow());
+        }
+        let ledger = self.store.load_trades()?.unwrap_or_default();
+        self.save_analysis(&ledger, Vec::new())
+    }
+
+    /// Forget a source file and import it again.
+    pub fn reprocess_source(&self, name: &str) -> Result<()> {
+        self.require_source_dir()?;
+        if !storage::source_files(&self.source_dir)?.iter().any(|path| storage::file_name(path) == name) {
+            bail!("{} is not in {}, so it cannot be re-processed", name, self.source_dir.display());
+        }
+        self.forget(name)?;
+        self.import(Some(name))
+    }
+
+    /// Delete the ledger, source records and cached analysis, after asking unless `yes`.
+    pub fn reset(&self, yes: bool) -> Result<()> {
+        if !yes {
+            print!("This deletes the trade ledger, source file records and cached analysis in {}.\nSource files and annotations are kept. Type 'yes' to continue: ", self.data_dir.display());
+            std::io::stdout().flush()?;
+            let mut answer = String::new();
+            std::io::stdin().lock().read_line(&mut answer)?;
+            if answer.trim() != "yes" {
+                println!("{}", "Reset cancelled.".yellow());
+                return Ok(());
+            }
+        }
+        self.store.clear()?;
+        println!("{}", "🧹 Trade ledger and cached analysis deleted; run `trader_rank import` to import the source files again".green());
+        Ok(())
+    }
+
+    fn forget(&self, name: &str) -> Result<()> {
+        match self.store.forget_source(name)? {
+            Some(source) => {
+                println!("{}", format!("🗑️  Forgot {} ({} trade(s) removed from the ledger)", source.name, source.trades).yellow());
+                Ok(())
+            }
+            None => bail!("No imported source file is named '{}' (see `trader_rank sources`)", name),
+        }
+    }
+
+    /// Trade files in the source directory and the imports directory, if given.
+    pub fn source_paths(&self) -> Result<Vec<PathBuf>> {
+        let mut paths = storage::source_files(&self.source_dir)?;
+        if let Some(dir) = &self.imports_dir {
+            paths.extend(storage::source_files(dir)?);
+        }
+        Ok(paths)
+    }
+
+    /// Print a progress message, on standard error when standard output is data.
+    fn notice(&self, text: ColoredString) {
+        if self.quiet {
+            eprintln!("{}", text);
+        } else {
+            println!("{}", text);
+        }
+    }
+
+    /// Save the ledger and its analysis, recording `imported` files.
+    fn save_analysis(&self, trades: &[Trade], imported: Vec<SourceFile>) -> Result<()> {
+        println!("{}", "🧮 Analyzing trading performance...".cyan());
+        let (combined, open) = TradingAnalytics::analyze_positions_in(trades, self.match_options, DateRange::default());
+        warn_open_positions(&open, self.match_options);
+        let account_summaries = TradingAnalytics::analyze_by_account_with(trades, self.match_options);
+
+        println!("{}", "💾 Saving analysis results...".yellow());
+        // The ledger is saved first: if marking the files fails, they are merged again next run
+        self.store.save_trades(trades)?;
+        self.store.mark_files_processed(imported, combined.clone(), account_summaries, self.match_options)?;
+        self.store.save_matched_trades(&match_trades_with(trades, self.match_options))?;
+        self.store.save_daily_summary(&combined)?;
+        Ok(())
+    }
+
+    pub fn require_source_dir(&self) -> Result<()> {
+        if !self.source_dir.is_dir() {
+            bail!("{} not found; put your CSV, Excel or Flex files there or pass --data-dir", self.source_dir.display());
+        }
+        Ok(())
+    }
+
+    fn ledger(&self) -> Result<Vec<Trade>> {
+        match self.store.load_trades()? {
+            Some(ledger) => Ok(ledger),
+            None => bail!("No trade ledger yet; run `trader_rank import` (or `rebuild` after upgrading) first"),
+        }
+    }
+
+    /// The ledger fills of the selected account and symbol. Positions are kept
+    /// per account and symbol, so these fills match the same round trips.
+    fn select(&self, mut fills: Vec<Trade>) -> Result<Vec<Trade>> {
+        if let Some(account) = &self.account {
+            let accounts = TradingAnalytics::accounts(&fills);
+            if !accounts.contains(account) {
+                if accounts.is_empty() {
+                    bail!("No trades are recorded with an account, so --account {} matches nothing", account);
+                }
+                bail!("No trades for account {} (known accounts: {})", account, accounts.join(", "));
+            }
+            fills.retain(|t| t.in_account(account));
+        }
+        if let Some(symbol) = &self.symbol {
+            fills.retain(|t| t.symbol.eq_ignore_ascii_case(symbol));
+            if fills.is_empty() {
+                bail!("No trades for symbol {}", symbol);
+            }
+        }
+        Ok(fills)
+    }
+
+    /// True when the cached analysis was made with the options asked for now.
+    fn is_cached(&self, data: &ProcessedData) -> bool {
+        data.exchange_timezone.unwrap_or(DEFAULT_EXCHANGE_TZ) == self.match_options.exchange_tz
+            && data.carry_positions == self.match_options.carry_positions
+            && data.lot_method == self.match_options.lot_method
+    }
+
+    /// Point out cached settings that differ from the options, when there is no ledger to re-analyze.
+    fn warn_stale(&self, data: &ProcessedData) {
+        let options = self.match_options;
+        let cached_tz = data.exchange_timezone.unwrap_or(DEFAULT_EXCHANGE_TZ);
+        if cached_tz != options.exchange_tz {
+            self.notice(format!("⚠️  Cached analysis is bucketed in {}, not {}; run `trader_rank rebuild` to re-analyze", cached_tz, options.exchange_tz).yellow());
+        }
+        if data.carry_positions != options.carry_positions {
+            let cached = if data.carry_positions { "includes" } else { "excludes" };
+            self.notice(format!("⚠️  Cached analysis {} swing trades; run `trader_rank rebuild` to re-analyze", cached).yellow());
+        }
+        if data.lot_method != options.lot_method {
+            self.notice(format!("⚠️  Cached analysis matched lots by {}, not {}; run `trader_rank rebuild` to re-analyze", data.lot_method, options.lot_method).yellow());
+        }
+    }
+
+    /// The summary to report: one account's, when filtering, or all accounts combined.
+    fn select_account_summary(
+        &self,
+        combined: TradingSummary,
+        mut account_summaries: BTreeMap<String, TradingSummary>,
+    ) -> Result<TradingSummary> {
+        let Some(account) = &self.account else {
+            self.print_scope(&combined.accounts);
+            return Ok(combined);
+        };
+
+        match account_summaries.remove(account) {
+            Some(summary) => {
+                self.print_scope(&combined.accounts);
+                Ok(summary)
+            }
+            None if combined.accounts.is_empty() => bail!("No trades are recorded with an account, so --account {} matches nothing", account),
+            None => bail!("No trades for account {} (known accounts: {})", account, combined.accounts.join(", ")),
+        }
+    }
+
+    /// Say which account, symbol and days the report covers.
+    fn print_scope(&self, accounts: &[String]) {
+        match &self.account {
+            Some(account) => self.notice(format!("👤 Account: {}", account).cyan()),
+            None if accounts.len() > 1 => {
+                self.notice(format!("👤 All accounts combined: {} (use --account ID for one)", accounts.join(", ")).cyan());
+            }
+            None => {}
+        }
+        if let Some(symbol) = &self.symbol {
+            self.notice(format!("🔎 Symbol: {}", symbol).cyan());
+        }
+        match (self.range.from, self.range.to) {
+            (Some(from), Some(to)) => self.notice(format!("📅 {} to {}", from, to).cyan()),
+            (Some(from), None) => self.notice(format!("📅 From {}", from).cyan()),
+            (None, Some(to)) => self.notice(format!("📅 Through {}", to).cyan()),
+            (None, None) => {}
+        }
+    }
+}
+
+/// List the imported source files and how each file in the source directory
+/// compares with them.
+fn print_sources(sources: &[SourceFile], scans: &[SourceScan]) {
+    println!("\n{}", "📚 Imported source files".bold().cyan());
+    if sources.is_empty() {
+        println!("{}", "   None yet".dimmed());
+    }
+    for source in sources {
+        let status = match scans.iter().find(|scan| scan.file.name == source.name).map(|scan| &scan.status) {
+            Some(SourceStatus::Unchanged) => "unchanged".green(),
+            Some(SourceStatus::Changed) => "changed, re-imported next run".yellow(),
+            Some(_) | None => "no longer in the source directory".dimmed(),
+        };
+        println!("   {:<40} {:>6} trades  {}  {}  {}",
+            source.name,
+            source.trades,
+            source.processed_at.format("%Y-%m-%d %H:%M"),
+            source.hash.get(..12).unwrap_or(&source.hash).dimmed(),
+            status,
+        );
+    }
+
+    let pending: Vec<&SourceScan> = scans.iter()
+        .filter(|scan| !sources.iter().any(|s| s.name == scan.file.name))
+        .collect();
+    if !pending.is_empty() {
+        println!("\n{}", "📂 Not imported yet".bold().cyan());
+        for scan in pending {
+            match &scan.status {
+                SourceStatus::Copy(original) => println!("   {:<40} {}", scan.file.name, format!("same contents as {}", original).dimmed()),
+                _ => println!("   {:<40} {}", scan.file.name, "new".yellow()),
+            }
+        }
+    }
+}
+
+/// Warn about positions left open at the end of a trading day, on standard
+/// error. Overnight positions are expected when they are carried.
+pub fn warn_open_positions(open: &[OpenPosition], options: MatchOptions) {
+    if options.carry_positions {
+        return;
+    }
+    for position in open {
+        eprintln!("{}", format!("⚠️  Warning: {} at end of day", position).yellow());
+    }
+}
+
+/// Most dropped lines listed per file; the rest are counted.
+const MAX_DIAGNOSTICS_SHOWN: usize = 10;
+
+/// List duplicates that were matched on their fields alone, so a wrongly
+/// merged pair of fills can be spotted. Duplicates with matching execution IDs are only counted.
+fn print_fuzzy_duplicates(dedup: &DedupOutcome, file_names: &[String]) {
+    let fuzzy: Vec<_> = dedup.fuzzy_duplicates().collect();
+    if fuzzy.is_empty() {
+        return;
+    }
+
+    println!("{}", format!("     {} matched without an execution ID:", fuzzy.len()).yellow());
+    for duplicate in fuzzy.iter().take(MAX_DIAGNOSTICS_SHOWN) {
+        println!("{}", format!("        ↳ {}", duplicate).yellow());
+        println!("{}", format!("          {} duplicates {}", file_names[duplicate.dropped_source], file_names[duplicate.kept_source]).dimmed());
+    }
+    if fuzzy.len() > MAX_DIAGNOSTICS_SHOWN {
+        println!("{}", format!("        … and {} more", fuzzy.len() - MAX_DIAGNOSTICS_SHOWN).yellow());
+    }
+}
+
+/// Print what a file yielded: trade count and format, or why it was skipped, and any dropped lines.
+fn print_parse_report(report: &ParseReport) {
+    if report.is_skipped() {
+        println!("{}", format!("     └─ {}", report.summary()).yellow());
+        return;
+    }
+
+    println!("{}", format!("     └─ {}", report.summary()).dimmed());
+    for diagnostic in report.diagnostics.iter().take(MAX_DIAGNOSTICS_SHOWN) {
+        println!("{}", format!("        ⚠️  {}", diagnostic).yellow());
+        println!("{}", format!("           {}", diagnostic.raw).dimmed());
+    }
+    if report.diagnostics.len() > MAX_DIAGNOSTICS_SHOWN {
+        println!("{}", format!("        … and {} more", report.diagnostics.len() - MAX_DIAGNOSTICS_SHOWN).yellow());
+    }
+}
@@ -1,165 +1,134 @@
-mod models;
-mod parser;
-mod analytics;
-mod persistence;
+mod cli;
+mod commands;
+mod report;
+mod tui;
 mod visualization;
+mod watch;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
+use cli::{CliArgs, Command, SourcesAction, USAGE};
 use colored::*;
-use parser::CsvParser;
-use analytics::{TradingAnalytics, TimePatternAnalyzer};
-use persistence::JsonStore;
-use visualization::{TableRenderer, ChartRenderer, CalendarRenderer, WeeklyRenderer};
-use std::collections::HashSet;
+use commands::App;
+use report::ReportFormat;
+use std::path::PathBuf;
+use trader_rank_core::exchange_time::{self, Tz, DEFAULT_EXCHANGE_TZ};
+use trader_rank_core::parser::{CsvParser, ImportProfile};
+use trader_rank_core::storage::StoreKind;
+use trader_rank_core::trade_matcher::{LotMethod, MatchOptions};
 
 fn main() -> Result<()> {
-    println!("{}", "\n🚀 TraderRank Analytics Engine Starting...".bold().cyan());
-
-    let project_root = std::env::current_dir()?;
-    let data_dir = project_root.parent().unwrap_or(&project_root).join("Data");
-    let source_dir = data_dir.join("Source");
-
-    if !source_dir.exists() {
-        eprintln!("{}", "❌ Error: Data/Source directory not found!".red().bold());
-        eprintln!("Please ensure the Data/Source directory exists with CSV files.");
-        std::process::exit(1);
+    let args = CliArgs::parse()?;
+    if args.help {
+        println!("{}", USAGE);
+        return Ok(());
     }
 
-    let store = JsonStore::new(data_dir.clone())?;
-
-    println!("{}", "📂 Checking for new trade data...".yellow());
-
-    let new_files = store.get_new_files(&source_dir)?;
-
-    if new_files.is_empty() {
-        println!("{}", "✅ All files already processed.".green());
-
-        if let Some(mut processed_data) = store.load_processed_data()? {
-            println!("{}", "📊 Loading cached analysis...".cyan());
-
-            // Regenerate monthly summaries if missing (backward compatibility with old cached data)
-            if processed_data.summary.monthly_summaries.is_empty() && !processed_data.summary.daily_summaries.is_empty() {
-                println!("{}", "📈 Regenerating monthly summaries...".yellow());
-                processed_data.summary.monthly_summaries = TradingAnalytics::regenerate_monthly_summaries(&processed_data.summary.daily_summaries);
-
-                // Find best/worst months
-                processed_data.summary.best_month = processed_data.summary.monthly_summaries
-                    .iter()
-                    .max_by_key(|m| m.realized_pnl)
-                    .map(|m| ((m.year, m.month), m.realized_pnl));
-                processed_data.summary.worst_month = processed_data.summary.monthly_summaries
-                    .iter()
-                    .min_by_key(|m| m.realized_pnl)
-                    .map(|m| ((m.year, m.month), m.realized_pnl));
-            }
-
-            TableRenderer::render_summary(&processed_data.summary, 10);
-            ChartRenderer::render_pnl_chart(&processed_data.summary.daily_summaries);
-
-            // Add daily win rate chart
-            ChartRenderer::render_daily_winrate_chart(&processed_data.summary.daily_summaries);
-
-            // Add win rate progression chart
-            ChartRenderer::render_winrate_progression(&processed_data.summary.daily_summaries);
+    // Data commands keep standard output for their data
+    let quiet = args.prints_data();
+    let notice = |text: ColoredString| if quiet { eprintln!("{}", text) } else { println!("{}", text) };
+    notice("\n🚀 TraderRank Analytics Engine Starting...".bold().cyan());
 
-            if let Some(last_day) = processed_data.summary.daily_summaries.last() {
-                ChartRenderer::render_hourly_distribution(last_day);
-            }
-
-            // Add weekly analysis
-            WeeklyRenderer::render_weekly_analysis(&processed_data.summary);
-
-            // Add 6-month summary for longer-term perspective
-            WeeklyRenderer::render_six_month_summary(&processed_data.summary);
-
-            // Add calendar views (last 4 weeks)
-            CalendarRenderer::render_combined_calendars(&processed_data.summary);
-        } else {
-            println!("{}", "⚠️  No processed data found.".yellow());
-        }
-    } else {
-        println!("{}", format!("🔍 Found {} new file(s) to process", new_files.len()).green());
+    let data_dir = data_dir(args.data_dir.clone())?;
+    if !data_dir.is_dir() {
+        bail!("Data directory {} not found (pass --data-dir DIR or set TRADERRANK_DATA_DIR)", data_dir.display());
+    }
 
-        let mut unique_trades = HashSet::new();
-        let mut duplicate_count = 0;
+    if let Some(dir) = args.imports_dir.as_deref().filter(|dir| !dir.is_dir()) {
+        bail!("Imports directory {} not found", dir.display());
+    }
 
-        if let Some(_processed_data) = store.load_processed_data()? {
-            println!("{}", "📥 Loading existing trade history...".cyan());
-            // Note: In a real implementation, we'd need to reconstruct trades from summaries
-            // or store trades separately
-        }
+    // An existing database is used unless --store says otherwise
+    let store_kind = args.store.unwrap_or_else(|| StoreKind::detect(&data_dir));
+    let store = store_kind.open(&data_dir)?;
+    if store_kind == StoreKind::Sqlite {
+        notice(format!("🗄️  Storage: {}", store_kind).cyan());
+    }
 
-        for file in &new_files {
-            println!("{}", format!("  📄 Processing: {}", file.file_name().unwrap().to_string_lossy()).white());
-            let trades = CsvParser::parse_file(file)?;
-            let file_trade_count = trades.len();
+    // User-defined import profiles for other brokers' exports
+    let profiles = ImportProfile::load_dir(&data_dir.join("Profiles"))?;
+    if !profiles.is_empty() {
+        let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+        notice(format!("🧩 Loaded {} import profile(s): {}", profiles.len(), names.join(", ")).cyan());
+    }
 
-            for trade in trades {
-                if !unique_trades.insert(trade) {
-                    duplicate_count += 1;
-                }
-            }
+    // Trading days and hours are bucketed in the exchange's timezone; built-in formats'
+    // timestamps are read in the source timezone, which defaults to the exchange's
+    let exchange_tz = timezone_from_env("TRADERRANK_EXCHANGE_TZ")?.unwrap_or(DEFAULT_EXCHANGE_TZ);
+    let source_tz = timezone_from_env("TRADERRANK_SOURCE_TZ")?.unwrap_or(exchange_tz);
+    if exchange_tz != DEFAULT_EXCHANGE_TZ || source_tz != exchange_tz {
+        notice(format!("🕐 Exchange timezone: {}, source timezone: {}", exchange_tz, source_tz).cyan());
+    }
+    let match_options = MatchOptions { exchange_tz, carry_positions: args.carry_positions, lot_method: args.lot_method };
+    if match_options.carry_positions {
+        notice("🌙 Carrying positions overnight: swing trades are included".cyan());
+    }
+    if match_options.lot_method != LotMethod::default() {
+        notice(format!("🧾 Lot matching: {}", match_options.lot_method).cyan());
+    }
 
-            println!("{}", format!("     └─ {} trades found", file_trade_count).dimmed());
+    let interval = args.watch_interval();
+    let app = App {
+        source_dir: data_dir.join("Source"),
+        imports_dir: args.imports_dir,
+        data_dir,
+        store,
+        parser: CsvParser::with_profiles(profiles).with_timezone(source_tz),
+        match_options,
+        account: args.account,
+        symbol: args.symbol,
+        range: args.range,
+        quiet,
+    };
+
+    match args.command {
+        Command::Run => {
+            app.import(None)?;
+            app.report(ReportFormat::Text, None, None, None)?;
+            println!("\n{}", "✨ Analysis complete!".green().bold());
         }
-
-        if duplicate_count > 0 {
-            println!("{}", format!("⚠️  Found {} duplicate trade(s), filtering them out", duplicate_count).yellow());
+        Command::Import => {
+            app.import(None)?;
+            println!("\n{}", "✨ Import complete!".green().bold());
         }
-
-        let all_trades: Vec<_> = unique_trades.into_iter().collect();
-        println!("{}", format!("✅ Processing {} unique trades (filtered {} duplicates)", all_trades.len(), duplicate_count).green());
-
-        println!("{}", "🧮 Analyzing trading performance...".cyan());
-        let summary = TradingAnalytics::analyze_trades(&all_trades);
-
-        println!("{}", "💾 Saving analysis results...".yellow());
-        store.mark_files_processed(new_files, summary.clone())?;
-        store.save_daily_summary(&summary)?;
-
-        println!("{}", "📊 Generating reports...".cyan());
-        TableRenderer::render_summary(&summary, 10);
-        ChartRenderer::render_pnl_chart(&summary.daily_summaries);
-
-        // Add daily win rate chart - right after P&L charts for visibility
-        ChartRenderer::render_daily_winrate_chart(&summary.daily_summaries);
-
-        // Add win rate progression chart
-        ChartRenderer::render_winrate_progression(&summary.daily_summaries);
-
-        if let Some(last_day) = summary.daily_summaries.last() {
-            ChartRenderer::render_hourly_distribution(last_day);
+        Command::Report => app.report(args.format, args.table, args.output.as_deref(), args.html.as_deref())?,
+        Command::Tui => app.browse()?,
+        Command::Trades => app.list_trades(args.fills)?,
+        Command::Export => match args.tax_year {
+            Some(year) => app.export_tax_report(year, args.output.as_deref())?,
+            None => app.export(args.fills, args.output.as_deref())?,
+        },
+        Command::Rebuild => {
+            app.rebuild()?;
+            println!("\n{}", "✨ Rebuild complete!".green().bold());
         }
+        Command::Watch => watch::watch(&app, interval)?,
+        Command::Sources(SourcesAction::List) => app.list_sources()?,
+        Command::Sources(SourcesAction::Forget(name)) => app.forget_source(&name)?,
+        Command::Sources(SourcesAction::Reprocess(name)) => app.reprocess_source(&name)?,
+        Command::Reset => app.reset(args.yes)?,
+    }
+    Ok(())
+}
 
-        // Add weekly analysis
-        WeeklyRenderer::render_weekly_analysis(&summary);
-
-        // Add 6-month summary for longer-term perspective
-        WeeklyRenderer::render_six_month_summary(&summary);
-
-        // Add calendar views (last 4 weeks)
-        CalendarRenderer::render_combined_calendars(&summary);
-
-        let periods = TimePatternAnalyzer::identify_best_trading_periods(&all_trades);
-        println!("\n{}", "🎯 Best Trading Periods Analysis".bold().cyan());
-        for (i, period) in periods.iter().take(3).enumerate() {
-            let medal = match i {
-                0 => "🥇",
-                1 => "🥈",
-                2 => "🥉",
-                _ => "  ",
-            };
-            println!("{} {} ({:02}:00-{:02}:00): ${:.2} | Win Rate: {:.1}%",
-                medal,
-                period.name.bold(),
-                period.start_hour,
-                period.end_hour,
-                period.total_pnl,
-                period.win_rate
-            );
+/// `--data-dir`, else `TRADERRANK_DATA_DIR`, else `Data` next to the working
+/// directory (the layout of a checkout run from `TraderRank/`).
+fn data_dir(option: Option<PathBuf>) -> Result<PathBuf> {
+    if let Some(dir) = option {
+        return Ok(dir);
+    }
+    match std::env::var("TRADERRANK_DATA_DIR") {
+        Ok(dir) if !dir.trim().is_empty() => Ok(PathBuf::from(dir)),
+        _ => {
+            let project_root = std::env::current_dir()?;
+            Ok(project_root.parent().unwrap_or(&project_root).join("Data"))
         }
     }
+}
 
-    println!("\n{}", "✨ Analysis complete!".green().bold());
-    Ok(())
+/// IANA timezone named by an environment variable, if it is set.
+fn timezone_from_env(var: &str) -> Result<Option<Tz>> {
+    match std::env::var(var) {
+        Ok(name) if !name.trim().is_empty() => Ok(Some(exchange_time::parse_tz(&name)?)),
+        _ => Ok(None),
+    }
 }
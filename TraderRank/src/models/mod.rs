@@ -1,5 +0,0 @@
-pub mod trade;
-pub mod summary;
-
-pub use trade::{Trade, Side};
-pub use summary::{DailySummary, WeeklySummary, MonthlySummary, TradingSummary, TimeSlotPerformance};
\ No newline at end of file
@@ -1,3 +0,0 @@
-pub mod csv_parser;
-
-pub use csv_parser::CsvParser;
\ No newline at end of file
@@ -1,3 +0,0 @@
-pub mod json_store;
-
-pub use json_store::JsonStore;
\ No newline at end of file
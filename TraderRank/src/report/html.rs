@@ -0,0 +1,480 @@
+//! A self-contained HTML report: stat cards, inline SVG charts and tables in one
+//! file that opens offline, coloured with the desktop palettes.
+
+use super::Report;
+use anyhow::Result;
+use chrono::{Datelike, NaiveDate};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::Write;
+use trader_rank_core::models::{MatchedTrade, TradingSummary};
+use trader_rank_core::theme::Palette;
+
+const CHART_WIDTH: f64 = 900.0;
+const CHART_HEIGHT: f64 = 260.0;
+/// Room left of a chart for the axis labels, and below it for the dates
+const MARGIN_LEFT: f64 = 70.0;
+const MARGIN_BOTTOM: f64 = 24.0;
+const MARGIN_TOP: f64 = 12.0;
+
+const STYLE: &str = "
+* { margin: 0; padding: 0; box-sizing: border-box; }
+body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', system-ui, sans-serif; background: var(--bg-primary); color: var(--text-primary); line-height: 1.5; padding: 32px; }
+main { max-width: 980px; margin: 0 auto; }
+header { display: flex; justify-content: space-between; align-items: baseline; margin-bottom: 24px; }
+h1 { color: var(--text-heading); font-size: 24px; }
+h2 { color: var(--text-heading); font-size: 16px; margin-bottom: 12px; }
+.scope { color: var(--text-secondary); font-size: 13px; }
+button { background: var(--bg-card); color: var(--text-secondary); border: 1px solid var(--border-card); border-radius: 8px; padding: 4px 12px; cursor: pointer; }
+.cards { display: grid; grid-template-columns: repeat(auto-fit, minmax(150px, 1fr)); gap: 12px; margin-bottom: 20px; }
+.card, section { background: var(--bg-card); border: 1px solid var(--border-card); border-radius: 12px; padding: 16px 20px; }
+section { margin-bottom: 20px; }
+.label { color: var(--text-secondary); font-size: 12px; }
+.value { color: var(--text-heading); font-size: 20px; font-weight: 600; }
+.profit { color: var(--accent-green); }
+.loss { color: var(--accent-red); }
+.muted { color: var(--text-muted); font-size: 13px; }
+svg { display: block; width: 100%; height: auto; }
+svg text { fill: var(--text-secondary); font-size: 11px; }
+svg .grid { stroke: var(--border-color); }
+svg .zero { stroke: var(--text-muted); }
+svg .equity { stroke: var(--accent-primary); fill: none; stroke-width: 2; }
+svg .equity-area { fill: var(--accent-primary); opacity: 0.12; }
+svg .pos { fill: var(--accent-green); }
+svg .neg { fill: var(--accent-red); }
+svg .none { fill: var(--bg-table-header); }
+svg .day { font-size: 9px; fill: var(--text-primary); }
+.months { display: flex; flex-wrap: wrap; gap: 20px; }
+.months svg { width: 180px; }
+td svg { height: 8px; }
+table { width: 100%; border-collapse: collapse; font-size: 13px; }
+th { background: var(--bg-table-header); color: var(--text-secondary); font-weight: 500; }
+th, td { padding: 6px 10px; text-align: right; border-bottom: 1px solid var(--border-color); }
+th:first-child, td:first-child { text-align: left; }
+tr:nth-child(even) td { background: var(--bg-table-row-alt); }
+";
+
+/// Follow the reader's light/dark preference, and let the button switch it.
+const SCRIPT: &str = "
+const root = document.documentElement;
+if (window.matchMedia && matchMedia('(prefers-color-scheme: light)').matches) root.dataset.theme = 'light';
+document.getElementById('theme').onclick = () => root.dataset.theme = root.dataset.theme === 'light' ? 'dark' : 'light';
+";
+
+/// Write the HTML report for `report`, drawn from its summary and the round trips it covers.
+pub fn write_html<W: Write>(report: &Report, summary: &TradingSummary, matched: &[MatchedTrade], mut writer: W) -> Result<()> {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\" data-theme=\"dark\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    let _ = writeln!(html, "<title>TraderRank Report {} to {}</title>", report.overall.start_date, report.overall.end_date);
+    let _ = writeln!(html, "<style>\n{}{}</style>\n</head>\n<body>\n<main>", Palette::theme_css(), STYLE);
+
+    let _ = writeln!(html, "<header><div><h1>TraderRank Report</h1><div class=\"scope\">{}</div></div><button id=\"theme\">Light / Dark</button></header>", escape(&scope_line(report)));
+    cards(&mut html, report);
+
+    section(&mut html, "Equity Curve", &equity_curve(summary));
+    section(&mut html, "Daily P&L", &daily_bars(summary));
+    section(&mut html, "Monthly Calendar", &calendars(summary));
+    section(&mut html, "Weekly Performance", &weekly_table(report));
+    section(&mut html, "Hourly Distribution", &hourly_bars(summary));
+    section(&mut html, "Symbol Breakdown", &symbol_breakdown(matched));
+
+    let _ = writeln!(html, "</main>\n<script>{}</script>\n</body>\n</html>", SCRIPT);
+    writer.write_all(html.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn scope_line(report: &Report) -> String {
+    let scope = &report.scope;
+    let mut parts = vec![format!("{} to {}", report.overall.start_date, report.overall.end_date)];
+    if let Some(account) = &scope.account {
+        parts.push(format!("account {}", account));
+    }
+    if let Some(symbol) = &scope.symbol {
+        parts.push(format!("symbol {}", symbol));
+    }
+    parts.push(format!("days in {}", scope.exchange_timezone));
+    parts.join(" · ")
+}
+
+fn cards(html: &mut String, report: &Report) {
+    let o = &report.overall;
+    let mut cards = vec![
+        ("Net P&L", money(o.net_pnl), pnl_class(o.net_pnl)),
+        ("Trades", o.total_trades.to_string(), ""),
+        ("Win Rate", format!("{:.1}%", o.win_rate), ""),
+        ("Trading Days", format!("{} ({} profitable)", o.trading_days, o.profitable_days), ""),
+        ("Volume", money(o.total_volume), ""),
+    ];
+    if let Some(day) = &o.best_day {
+        cards.push(("Best Day", format!("{} {}", day.date.format("%b %d"), money(day.net_pnl)), pnl_class(day.net_pnl)));
+    }
+    if let Some(day) = &o.worst_day {
+        cards.push(("Worst Day", format!("{} {}", day.date.format("%b %d"), money(day.net_pnl)), pnl_class(day.net_pnl)));
+    }
+
+    html.push_str("<div class=\"cards\">\n");
+    for (label, value, class) in cards {
+        let _ = writeln!(html, "<div class=\"card\"><div class=\"label\">{}</div><div class=\"value {}\">{}</div></div>", escape(label), class, escape(&value));
+    }
+    html.push_str("</div>\n");
+}
+
+fn section(html: &mut String, title: &str, body: &str) {
+    let _ = writeln!(html, "<section>\n<h2>{}</h2>\n{}</section>", escape(title), body);
+}
+
+/// Cumulative net P&L at the close of each trading day.
+fn equity_curve(summary: &TradingSummary) -> String {
+    let mut equity = Decimal::ZERO;
+    let points: Vec<(NaiveDate, f64)> = summary.daily_summaries.iter()
+        .map(|d| {
+            equity += d.realized_pnl;
+            (d.date.date_naive(), to_f64(equity))
+        })
+        .collect();
+    if points.len() < 2 {
+        return "<p class=\"muted\">Needs at least two trading days.</p>\n".to_string();
+    }
+
+    let values: Vec<f64> = points.iter().map(|(_, v)| *v).collect();
+    let axis = Axis::new(&values);
+    let step = (CHART_WIDTH - MARGIN_LEFT) / (points.len() - 1) as f64;
+    let coords: Vec<(f64, f64)> = values.iter().enumerate()
+        .map(|(i, v)| (MARGIN_LEFT + i as f64 * step, axis.y(*v)))
+        .collect();
+
+    let mut svg = svg_open();
+    axis.draw(&mut svg);
+    let line: Vec<String> = coords.iter().map(|(x, y)| format!("{:.1},{:.1}", x, y)).collect();
+    let zero = axis.y(0.0);
+    let _ = writeln!(svg, "<polygon class=\"equity-area\" points=\"{:.1},{:.1} {} {:.1},{:.1}\"/>", coords[0].0, zero, line.join(" "), coords[coords.len() - 1].0, zero);
+    let _ = writeln!(svg, "<polyline class=\"equity\" points=\"{}\"/>", line.join(" "));
+    date_labels(&mut svg, points.first().map(|(d, _)| *d), points.last().map(|(d, _)| *d));
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// One bar per trading day.
+fn daily_bars(summary: &TradingSummary) -> String {
+    if summary.daily_summaries.is_empty() {
+        return "<p class=\"muted\">No trading days.</p>\n".to_string();
+    }
+    let bars: Vec<(String, Decimal)> = summary.daily_summaries.iter()
+        .map(|d| (d.date.format("%Y-%m-%d").to_string(), d.realized_pnl))
+        .collect();
+    let mut svg = bar_chart(&bars);
+    let days = &summary.daily_summaries;
+    date_labels(&mut svg, days.first().map(|d| d.date.date_naive()), days.last().map(|d| d.date.date_naive()));
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Net P&L by the hour round trips were closed, over every day in the report.
+fn hourly_bars(summary: &TradingSummary) -> String {
+    let mut hours: BTreeMap<u32, (Decimal, u32)> = BTreeMap::new();
+    for slot in summary.daily_summaries.iter().flat_map(|d| &d.time_slot_performance) {
+        let entry = hours.entry(slot.hour).or_insert((Decimal::ZERO, 0));
+        entry.0 += slot.pnl;
+        entry.1 += slot.trades;
+    }
+    let (Some(&first), Some(&last)) = (hours.keys().next(), hours.keys().next_back()) else {
+        return "<p class=\"muted\">No hourly data.</p>\n".to_string();
+    };
+
+    let bars: Vec<(String, Decimal)> = (first..=last)
+        .map(|hour| {
+            let (pnl, trades) = hours.get(&hour).copied().unwrap_or_default();
+            (format!("{:02}:00 · {} trade(s)", hour, trades), pnl)
+        })
+        .collect();
+    let mut svg = bar_chart(&bars);
+    let width = (CHART_WIDTH - MARGIN_LEFT) / bars.len() as f64;
+    for (i, hour) in (first..=last).enumerate() {
+        let x = MARGIN_LEFT + (i as f64 + 0.5) * width;
+        let _ = writeln!(svg, "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{:02}</text>", x, CHART_HEIGHT - 6.0, hour);
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// A bar chart of labelled amounts, left open for axis labels along the bottom.
+fn bar_chart(bars: &[(String, Decimal)]) -> String {
+    let mut values: Vec<f64> = bars.iter().map(|(_, v)| to_f64(*v)).collect();
+    values.push(0.0);
+    let axis = Axis::new(&values);
+    let width = (CHART_WIDTH - MARGIN_LEFT) / bars.len().max(1) as f64;
+    let gap = if width > 4.0 { width * 0.2 } else { 0.0 };
+
+    let mut svg = svg_open();
+    axis.draw(&mut svg);
+    for (i, (label, pnl)) in bars.iter().enumerate() {
+        let value = to_f64(*pnl);
+        let (top, bottom) = (axis.y(value.max(0.0)), axis.y(value.min(0.0)));
+        let _ = writeln!(svg, "<rect class=\"{}\" x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\"><title>{}: {}</title></rect>",
+            if *pnl < Decimal::ZERO { "neg" } else { "pos" },
+            MARGIN_LEFT + i as f64 * width + gap / 2.0,
+            top,
+            (width - gap).max(1.0),
+            (bottom - top).max(0.5),
+            escape(label),
+            money(*pnl),
+        );
+    }
+    svg
+}
+
+/// One heatmap per month, weeks as rows from Monday, shaded by the day's net P&L.
+fn calendars(summary: &TradingSummary) -> String {
+    const CELL: f64 = 22.0;
+    const GAP: f64 = 3.0;
+    const HEADER: f64 = 30.0;
+
+    let days: BTreeMap<NaiveDate, Decimal> = summary.daily_summaries.iter()
+        .map(|d| (d.date.date_naive(), d.realized_pnl))
+        .collect();
+    let largest = days.values().map(|pnl| pnl.abs()).max().unwrap_or_default();
+
+    if summary.monthly_summaries.is_empty() {
+        return "<p class=\"muted\">No trading days.</p>\n".to_string();
+    }
+    let mut html = String::from("<div class=\"months\">\n");
+    for month in &summary.monthly_summaries {
+        let Some(first) = NaiveDate::from_ymd_opt(month.year, month.month, 1) else {
+            continue;
+        };
+        let offset = first.weekday().num_days_from_monday();
+        let length = days_in_month(month.year, month.month);
+        let rows = (offset + length).div_ceil(7);
+        let height = HEADER + rows as f64 * (CELL + GAP);
+
+        let _ = writeln!(html, "<svg viewBox=\"0 0 {:.0} {:.0}\" role=\"img\">", 7.0 * (CELL + GAP), height);
+        let _ = writeln!(html, "<text x=\"0\" y=\"12\">{} {} · {}</text>", escape(&month.month_name), month.year, money(month.realized_pnl));
+        for (i, weekday) in ["M", "T", "W", "T", "F", "S", "S"].iter().enumerate() {
+            let _ = writeln!(html, "<text x=\"{:.1}\" y=\"26\" text-anchor=\"middle\">{}</text>", i as f64 * (CELL + GAP) + CELL / 2.0, weekday);
+        }
+        for day in 1..=length {
+            let date = NaiveDate::from_ymd_opt(month.year, month.month, day).unwrap_or(first);
+            let cell = offset + day - 1;
+            let (x, y) = ((cell % 7) as f64 * (CELL + GAP), HEADER + (cell / 7) as f64 * (CELL + GAP));
+            match days.get(&date) {
+                Some(pnl) => {
+                    // Stronger shading for larger days, never fainter than a quarter
+                    let strength = match largest.is_zero() {
+                        true => 1.0,
+                        false => 0.25 + 0.75 * to_f64(pnl.abs() / largest),
+                    };
+                    let _ = writeln!(html, "<rect class=\"{}\" x=\"{:.1}\" y=\"{:.1}\" width=\"{}\" height=\"{}\" rx=\"3\" fill-opacity=\"{:.2}\"><title>{}: {}</title></rect>",
+                        if *pnl < Decimal::ZERO { "neg" } else { "pos" }, x, y, CELL, CELL, strength, date, money(*pnl));
+                }
+                None => {
+                    let _ = writeln!(html, "<rect class=\"none\" x=\"{:.1}\" y=\"{:.1}\" width=\"{}\" height=\"{}\" rx=\"3\"/>", x, y, CELL, CELL);
+                }
+            }
+            let _ = writeln!(html, "<text class=\"day\" x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>", x + CELL / 2.0, y + CELL / 2.0 + 3.0, day);
+        }
+        html.push_str("</svg>\n");
+    }
+    html.push_str("</div>\n");
+    html
+}
+
+fn weekly_table(report: &Report) -> String {
+    if report.weekly.is_empty() {
+        return "<p class=\"muted\">No trading days.</p>\n".to_string();
+    }
+    let mut html = String::from("<table>\n<tr><th>Week</th><th>Dates</th><th>Days</th><th>Trades</th><th>Win Rate</th><th>Gross P&amp;L</th><th>Commission</th><th>Net P&amp;L</th></tr>\n");
+    for w in report.weekly.iter().rev() {
+        let _ = writeln!(html, "<tr><td>{}-W{:02}</td><td>{} – {}</td><td>{}</td><td>{}</td><td>{:.1}%</td><td>{}</td><td>{}</td><td class=\"{}\">{}</td></tr>",
+            w.year, w.week,
+            w.start_date.format("%b %d"), w.end_date.format("%b %d"),
+            w.trading_days, w.trades, w.win_rate,
+            money(w.gross_pnl), money(w.commission),
+            pnl_class(w.net_pnl), money(w.net_pnl),
+        );
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+/// Net P&L, round trips and win rate per symbol, best first, with a bar for each.
+fn symbol_breakdown(matched: &[MatchedTrade]) -> String {
+    if matched.is_empty() {
+        return "<p class=\"muted\">Needs the trade ledger; run <code>trader_rank rebuild</code> to build it.</p>\n".to_string();
+    }
+
+    let mut symbols: BTreeMap<&str, (Decimal, u32, u32)> = BTreeMap::new();
+    for mt in matched {
+        let entry = symbols.entry(mt.symbol.as_str()).or_insert((Decimal::ZERO, 0, 0));
+        entry.0 += mt.net_pnl;
+        entry.1 += 1;
+        if mt.net_pnl > Decimal::ZERO {
+            entry.2 += 1;
+        }
+    }
+    let mut rows: Vec<_> = symbols.into_iter().collect();
+    rows.sort_by_key(|(_, (pnl, _, _))| std::cmp::Reverse(*pnl));
+    let largest = rows.iter().map(|(_, (pnl, _, _))| pnl.abs()).max().unwrap_or_default();
+
+    let mut html = String::from("<table>\n<tr><th>Symbol</th><th>Trades</th><th>Win Rate</th><th>Net P&amp;L</th><th style=\"width:40%\"></th></tr>\n");
+    for (symbol, (pnl, trades, wins)) in rows {
+        let share = match largest.is_zero() {
+            true => 0.0,
+            false => to_f64(pnl.abs() / largest) * 100.0,
+        };
+        let _ = writeln!(html, "<tr><td>{}</td><td>{}</td><td>{:.1}%</td><td class=\"{}\">{}</td><td><svg viewBox=\"0 0 100 8\" preserveAspectRatio=\"none\"><rect class=\"{}\" width=\"{:.1}\" height=\"8\" rx=\"2\"/></svg></td></tr>",
+            escape(symbol), trades, wins as f64 / trades as f64 * 100.0,
+            pnl_class(pnl), money(pnl),
+            if pnl < Decimal::ZERO { "neg" } else { "pos" }, share,
+        );
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+/// The vertical scale of a chart, from its lowest to its highest value.
+struct Axis {
+    min: f64,
+    max: f64,
+}
+
+impl Axis {
+    fn new(values: &[f64]) -> Self {
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min).min(0.0);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max).max(0.0);
+        match (max - min).abs() < f64::EPSILON {
+            true => Axis { min: min - 1.0, max: max + 1.0 },
+            false => Axis { min, max },
+        }
+    }
+
+    fn y(&self, value: f64) -> f64 {
+        let plot = CHART_HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+        MARGIN_TOP + (self.max - value) / (self.max - self.min) * plot
+    }
+
+    /// Gridlines and labels at the top, bottom and zero.
+    fn draw(&self, svg: &mut String) {
+        for value in [self.max, self.min] {
+            let y = self.y(value);
+            let _ = writeln!(svg, "<line class=\"grid\" x1=\"{}\" x2=\"{}\" y1=\"{:.1}\" y2=\"{:.1}\"/>", MARGIN_LEFT, CHART_WIDTH, y, y);
+            let _ = writeln!(svg, "<text x=\"{}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>", MARGIN_LEFT - 6.0, y + 4.0, money_f64(value));
+        }
+        let zero = self.y(0.0);
+        let _ = writeln!(svg, "<line class=\"zero\" x1=\"{}\" x2=\"{}\" y1=\"{:.1}\" y2=\"{:.1}\"/>", MARGIN_LEFT, CHART_WIDTH, zero, zero);
+        if self.min < 0.0 && self.max > 0.0 {
+            let _ = writeln!(svg, "<text x=\"{}\" y=\"{:.1}\" text-anchor=\"end\">$0</text>", MARGIN_LEFT - 6.0, zero + 4.0);
+        }
+    }
+}
+
+fn svg_open() -> String {
+    format!("<svg viewBox=\"0 0 {} {}\" role=\"img\">\n", CHART_WIDTH, CHART_HEIGHT)
+}
+
+fn date_labels(svg: &mut String, first: Option<NaiveDate>, last: Option<NaiveDate>) {
+    if let Some(first) = first {
+        let _ = writeln!(svg, "<text x=\"{}\" y=\"{}\">{}</text>", MARGIN_LEFT, CHART_HEIGHT - 6.0, first);
+    }
+    if let Some(last) = last {
+        let _ = writeln!(svg, "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>", CHART_WIDTH, CHART_HEIGHT - 6.0, last);
+    }
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let next = match month {
+        12 => NaiveDate::from_ymd_opt(year + 1, 1, 1),
+        _ => NaiveDate::from_ymd_opt(year, month + 1, 1),
+    };
+    next.and_then(|next| next.pred_opt()).map(|last| last.day()).unwrap_or(31)
+}
+
+fn pnl_class(amount: Decimal) -> &'static str {
+    match amount {
+        a if a > Decimal::ZERO => "profit",
+        a if a < Decimal::ZERO => "loss",
+        _ => "",
+    }
+}
+
+fn money(amount: Decimal) -> String {
+    if amount >= Decimal::ZERO {
+        format!("${:.2}", amount)
+    } else {
+        format!("-${:.2}", amount.abs())
+    }
+}
+
+fn money_f64(amount: f64) -> String {
+    if amount >= 0.0 {
+        format!("${:.0}", amount)
+    } else {
+        format!("-${:.0}", -amount)
+    }
+}
+
+fn to_f64(amount: Decimal) -> f64 {
+    amount.to_f64().unwrap_or_default()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{sample, Scope};
+    use trader_rank_core::exchange_time::DateRange;
+    use trader_rank_core::{MatchOptions, TradingAnalytics};
+
+    fn written(report: &Report, summary: &TradingSummary, matched: &[MatchedTrade]) -> String {
+        let mut out = Vec::new();
+        write_html(report, summary, matched, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    /// Opens offline: nothing is loaded from another file or site.
+    fn assert_self_contained(html: &str) {
+        for reference in ["src=", "href=", "url(", "@import", "http:", "https:"] {
+            assert!(!html.contains(reference), "{} in {}", reference, html);
+        }
+        assert!(html.starts_with("<!DOCTYPE html>") && html.trim_end().ends_with("</html>"));
+    }
+
+    #[test]
+    fn tiny_report_draws_every_chart_inline() {
+        let (report, summary, matched) = sample();
+        let html = written(&report, &summary, &matched);
+        assert_self_contained(&html);
+
+        let sections: Vec<&str> = html.match_indices("<h2>").map(|(i, _)| html[i + 4..].split('<').next().unwrap()).collect();
+        assert_eq!(sections, ["Equity Curve", "Daily P&amp;L", "Monthly Calendar", "Weekly Performance", "Hourly Distribution", "Symbol Breakdown"]);
+        assert!(html.contains("<style>") && html.contains("<script>"));
+        assert!(html.contains("<polyline class=\"equity\""));
+        assert!(html.contains("<title>2024-01-02: $73.00</title>"));
+        assert!(html.contains("<title>2024-01-03: -$77.00</title>"));
+        assert!(html.contains("January 2024 · -$4.00"));
+        assert!(html.contains("<div class=\"label\">Net P&amp;L</div><div class=\"value loss\">-$4.00</div>"));
+        assert!(html.contains("AAPL") && html.contains("MSFT"));
+        assert!(!html.contains("class=\"muted\""), "every chart has data");
+    }
+
+    #[test]
+    fn empty_report_says_what_is_missing() {
+        let options = MatchOptions::default();
+        let summary = TradingAnalytics::analyze_trades_with(&[], options);
+        let report = Report::build(&summary, &[], Scope::new(None, None, DateRange::default(), options.exchange_tz));
+        let html = written(&report, &summary, &[]);
+        assert_self_contained(&html);
+
+        assert!(!html.contains("<svg"));
+        assert!(html.contains("Needs at least two trading days."));
+        assert_eq!(html.matches("No trading days.").count(), 3);
+        assert!(html.contains("No hourly data."));
+        assert!(html.contains("<div class=\"value \">0 (0 profitable)</div>"));
+    }
+}
@@ -0,0 +1,336 @@
+//! The report as data: the overall summary, daily, weekly and monthly tables and
+//! the best-periods ranking, built once and written as JSON, CSV, Markdown or HTML.
+
+mod html;
+mod render;
+
+pub use html::write_html;
+pub use render::write_report;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::fmt;
+use trader_rank_core::analytics::patterns::TradingPeriod;
+use trader_rank_core::exchange_time::{DateRange, Tz};
+use trader_rank_core::models::TradingSummary;
+
+/// How `report` writes its output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// Colored tables and charts for the terminal
+    #[default]
+    Text,
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl ReportFormat {
+    /// Parse "text", "json", "csv" or "md" (case-insensitive).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "text" => Some(ReportFormat::Text),
+            "json" => Some(ReportFormat::Json),
+            "csv" => Some(ReportFormat::Csv),
+            "md" | "markdown" => Some(ReportFormat::Markdown),
+            _ => None,
+        }
+    }
+}
+
+/// One table of the report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportTable {
+    Overall,
+    Daily,
+    Weekly,
+    Monthly,
+    Periods,
+}
+
+impl ReportTable {
+    pub const ALL: [ReportTable; 5] = [
+        ReportTable::Overall,
+        ReportTable::Daily,
+        ReportTable::Weekly,
+        ReportTable::Monthly,
+        ReportTable::Periods,
+    ];
+
+    /// Parse "overall", "daily", "weekly", "monthly" or "periods" (case-insensitive).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "overall" => Some(ReportTable::Overall),
+            "daily" | "days" => Some(ReportTable::Daily),
+            "weekly" | "weeks" => Some(ReportTable::Weekly),
+            "monthly" | "months" => Some(ReportTable::Monthly),
+            "periods" => Some(ReportTable::Periods),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ReportTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ReportTable::Overall => "Overall Performance",
+            ReportTable::Daily => "Daily Performance",
+            ReportTable::Weekly => "Weekly Performance",
+            ReportTable::Monthly => "Monthly Performance",
+            ReportTable::Periods => "Best Trading Periods",
+        })
+    }
+}
+
+/// What the report covers.
+#[derive(Debug, Clone, Serialize)]
+pub struct Scope {
+    pub account: Option<String>,
+    pub symbol: Option<String>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub exchange_timezone: Tz,
+}
+
+/// Everything in the report. Amounts are rounded to cents; dates are exchange trading days.
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub scope: Scope,
+    pub overall: Overall,
+    pub daily: Vec<DayRow>,
+    pub weekly: Vec<WeekRow>,
+    pub monthly: Vec<MonthRow>,
+    /// Sessions ranked by net P&L; empty when there is no trade ledger to match
+    pub best_periods: Vec<PeriodRow>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Overall {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub trading_days: usize,
+    pub profitable_days: usize,
+    pub total_trades: u32,
+    pub win_rate: f64,
+    pub net_pnl: Decimal,
+    pub total_volume: Decimal,
+    pub swing_trades: u32,
+    pub swing_pnl: Decimal,
+    pub best_day: Option<DayPnl>,
+    pub worst_day: Option<DayPnl>,
+    pub best_hour: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DayPnl {
+    pub date: NaiveDate,
+    pub net_pnl: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DayRow {
+    pub date: NaiveDate,
+    pub trades: u32,
+    pub winning_trades: u32,
+    pub losing_trades: u32,
+    pub win_rate: f64,
+    pub gross_pnl: Decimal,
+    pub commission: Decimal,
+    pub net_pnl: Decimal,
+    pub volume: Decimal,
+    pub largest_win: Decimal,
+    pub largest_loss: Decimal,
+    pub swing_trades: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WeekRow {
+    pub year: i32,
+    pub week: u32,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub trading_days: u32,
+    pub profitable_days: u32,
+    pub trades: u32,
+    pub win_rate: f64,
+    pub gross_pnl: Decimal,
+    pub commission: Decimal,
+    pub net_pnl: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MonthRow {
+    pub year: i32,
+    pub month: u32,
+    pub month_name: String,
+    pub trading_days: u32,
+    pub profitable_days: u32,
+    pub trades: u32,
+    pub win_rate: f64,
+    pub gross_pnl: Decimal,
+    pub commission: Decimal,
+    pub net_pnl: Decimal,
+    pub avg_daily_pnl: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PeriodRow {
+    pub name: String,
+    pub start_hour: u32,
+    pub end_hour: u32,
+    pub trades: u32,
+    pub win_rate: f64,
+    pub net_pnl: Decimal,
+    pub avg_pnl_per_trade: Decimal,
+}
+
+impl Report {
+    pub fn build(summary: &TradingSummary, periods: &[TradingPeriod], scope: Scope) -> Self {
+        let day_pnl = |day: &Option<(chrono::DateTime<chrono::Utc>, Decimal)>| {
+            day.map(|(date, pnl)| DayPnl { date: date.date_naive(), net_pnl: cents(pnl) })
+        };
+        let days = &summary.daily_summaries;
+
+        let overall = Overall {
+            start_date: summary.start_date.date_naive(),
+            end_date: summary.end_date.date_naive(),
+            trading_days: days.len(),
+            profitable_days: days.iter().filter(|d| d.realized_pnl > Decimal::ZERO).count(),
+            total_trades: summary.total_trades,
+            win_rate: round_rate(summary.overall_win_rate),
+            net_pnl: cents(summary.total_pnl),
+            total_volume: cents(summary.total_volume),
+            swing_trades: days.iter().map(|d| d.swing_trades).sum(),
+            swing_pnl: cents(days.iter().map(|d| d.swing_pnl).sum()),
+            best_day: day_pnl(&summary.best_day),
+            worst_day: day_pnl(&summary.worst_day),
+            best_hour: summary.most_profitable_hour.map(|(hour, _)| hour),
+        };
+
+        let daily = days.iter()
+            .map(|d| DayRow {
+                date: d.date.date_naive(),
+                trades: d.total_trades,
+                winning_trades: d.winning_trades,
+                losing_trades: d.losing_trades,
+                win_rate: round_rate(d.win_rate),
+                gross_pnl: cents(d.gross_pnl),
+                commission: cents(d.total_commission),
+                net_pnl: cents(d.realized_pnl),
+                volume: cents(d.total_volume),
+                largest_win: cents(d.largest_win),
+                largest_loss: cents(Decimal::ZERO - d.largest_loss.abs()),
+                swing_trades: d.swing_trades,
+            })
+            .collect();
+
+        let weekly = summary.weekly_summaries.iter()
+            .map(|w| WeekRow {
+                year: w.year,
+                week: w.week_number,
+                start_date: w.start_date.date_naive(),
+                end_date: w.end_date.date_naive(),
+                trading_days: w.trading_days,
+                profitable_days: w.profitable_days,
+                trades: w.total_trades,
+                win_rate: round_rate(w.win_rate),
+                gross_pnl: cents(w.gross_pnl),
+                commission: cents(w.total_commission),
+                net_pnl: cents(w.realized_pnl),
+            })
+            .collect();
+
+        let monthly = summary.monthly_summaries.iter()
+            .map(|m| MonthRow {
+                year: m.year,
+                month: m.month,
+                month_name: m.month_name.clone(),
+                trading_days: m.trading_days,
+                profitable_days: m.profitable_days,
+                trades: m.total_trades,
+                win_rate: round_rate(m.win_rate),
+                gross_pnl: cents(m.gross_pnl),
+                commission: cents(m.total_commission),
+                net_pnl: cents(m.realized_pnl),
+                avg_daily_pnl: cents(m.avg_daily_pnl),
+            })
+            .collect();
+
+        let best_periods = periods.iter()
+            .filter(|p| p.total_trades > 0)
+            .map(|p| PeriodRow {
+                name: p.name.clone(),
+                start_hour: p.start_hour,
+                end_hour: p.end_hour,
+                trades: p.total_trades,
+                win_rate: round_rate(p.win_rate),
+                net_pnl: cents(p.total_pnl),
+                avg_pnl_per_trade: cents(p.avg_pnl_per_trade),
+            })
+            .collect();
+
+        Report { scope, overall, daily, weekly, monthly, best_periods }
+    }
+}
+
+impl Scope {
+    pub fn new(account: Option<String>, symbol: Option<String>, range: DateRange, exchange_timezone: Tz) -> Self {
+        Scope { account, symbol, from: range.from, to: range.to, exchange_timezone }
+    }
+}
+
+/// Amounts to exactly two decimal places, so "300" is written as "300.00".
+fn cents(amount: Decimal) -> Decimal {
+    let mut rounded = amount.round_dp(2);
+    rounded.rescale(2);
+    rounded
+}
+
+/// Win rates to one decimal place, as the console report shows them.
+fn round_rate(rate: f64) -> f64 {
+    (rate * 10.0).round() / 10.0
+}
+
+/// A stock fill with a $1 commission, for tests. `time` is RFC 3339 or a
+/// New York wall-clock "YYYY-MM-DD HH:MM:SS".
+#[cfg(test)]
+pub(crate) fn fill(symbol: &str, side: trader_rank_core::Side, quantity: i64, price: &str, time: &str) -> trader_rank_core::Trade {
+    use trader_rank_core::{Instrument, Trade, DEFAULT_EXCHANGE_TZ};
+
+    let fill_price: Decimal = price.parse().unwrap();
+    Trade {
+        symbol: symbol.to_string(),
+        side,
+        quantity: Decimal::from(quantity),
+        fill_price,
+        time: Trade::parse_time(time, DEFAULT_EXCHANGE_TZ).unwrap(),
+        net_amount: fill_price * Decimal::from(quantity),
+        commission: Decimal::ONE,
+        realized_pnl: None,
+        instrument: Instrument::stock(),
+        account: None,
+        execution_id: None,
+        closes_lot: None,
+        source: None,
+    }
+}
+
+/// Two days of round trips, an AAPL win and an MSFT loss, for the writers' tests.
+#[cfg(test)]
+pub(crate) fn sample() -> (Report, TradingSummary, Vec<trader_rank_core::MatchedTrade>) {
+    use trader_rank_core::{match_trades_with, MatchOptions, Side, TimePatternAnalyzer, TradingAnalytics};
+
+    let fills = vec![
+        fill("AAPL", Side::Buy, 100, "185.50", "2024-01-02 09:35:00"),
+        fill("AAPL", Side::Sell, 100, "186.25", "2024-01-02 10:15:00"),
+        fill("MSFT", Side::Buy, 50, "400.00", "2024-01-03 09:40:00"),
+        fill("MSFT", Side::Sell, 50, "398.50", "2024-01-03 11:00:00"),
+    ];
+
+    let options = MatchOptions::default();
+    let summary = TradingAnalytics::analyze_trades_with(&fills, options);
+    let periods = TimePatternAnalyzer::identify_best_trading_periods_with(&fills, options);
+    let scope = Scope::new(None, None, DateRange::default(), options.exchange_tz);
+    (Report::build(&summary, &periods, scope), summary, match_trades_with(&fills, options))
+}
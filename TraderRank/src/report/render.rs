@@ -0,0 +1,263 @@
+//! JSON, CSV and Markdown writers for a `Report`.
+
+use super::{Report, ReportFormat, ReportTable};
+use anyhow::Result;
+use std::io::Write;
+
+/// Write `report` as `format`: every table, or only `table` when one is chosen.
+/// CSV holds one table, so it writes the daily table unless another is chosen.
+pub fn write_report<W: Write>(report: &Report, format: ReportFormat, table: Option<ReportTable>, mut writer: W) -> Result<()> {
+    match format {
+        ReportFormat::Json => {
+            match table {
+                None => serde_json::to_writer_pretty(&mut writer, report)?,
+                Some(ReportTable::Overall) => serde_json::to_writer_pretty(&mut writer, &report.overall)?,
+                Some(ReportTable::Daily) => serde_json::to_writer_pretty(&mut writer, &report.daily)?,
+                Some(ReportTable::Weekly) => serde_json::to_writer_pretty(&mut writer, &report.weekly)?,
+                Some(ReportTable::Monthly) => serde_json::to_writer_pretty(&mut writer, &report.monthly)?,
+                Some(ReportTable::Periods) => serde_json::to_writer_pretty(&mut writer, &report.best_periods)?,
+            }
+            writeln!(writer)?;
+        }
+        ReportFormat::Csv => {
+            let (headers, rows) = table_rows(report, table.unwrap_or(ReportTable::Daily));
+            let mut csv = csv::Writer::from_writer(&mut writer);
+            csv.write_record(headers)?;
+            for row in rows {
+                csv.write_record(row)?;
+            }
+            csv.flush()?;
+        }
+        ReportFormat::Markdown => write_markdown(report, table, &mut writer)?,
+        ReportFormat::Text => unreachable!("the text report is drawn by the console renderers"),
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_markdown<W: Write>(report: &Report, table: Option<ReportTable>, writer: &mut W) -> Result<()> {
+    let scope = &report.scope;
+    writeln!(writer, "# TraderRank Report")?;
+    writeln!(writer)?;
+    let mut covers = vec![format!("{} to {}", report.overall.start_date, report.overall.end_date)];
+    if let Some(account) = &scope.account {
+        covers.push(format!("account {}", account));
+    }
+    if let Some(symbol) = &scope.symbol {
+        covers.push(format!("symbol {}", symbol));
+    }
+    covers.push(format!("days in {}", scope.exchange_timezone));
+    writeln!(writer, "{}", covers.join(" · "))?;
+
+    let tables = match table {
+        Some(table) => vec![table],
+        None => ReportTable::ALL.to_vec(),
+    };
+    for table in tables {
+        let (headers, rows) = table_rows(report, table);
+        writeln!(writer)?;
+        writeln!(writer, "## {}", table)?;
+        writeln!(writer)?;
+        if rows.is_empty() {
+            writeln!(writer, "_None_")?;
+            continue;
+        }
+
+        writeln!(writer, "| {} |", headers.join(" | "))?;
+        // Numeric columns are right-aligned
+        let align: Vec<&str> = (0..headers.len())
+            .map(|i| match rows.iter().all(|row| row[i].parse::<f64>().is_ok()) {
+                true => "---:",
+                false => "---",
+            })
+            .collect();
+        writeln!(writer, "| {} |", align.join(" | "))?;
+        for row in rows {
+            let cells: Vec<String> = row.iter().map(|cell| cell.replace('|', "\\|")).collect();
+            writeln!(writer, "| {} |", cells.join(" | "))?;
+        }
+    }
+    Ok(())
+}
+
+/// A table's column headers and rows, as text.
+fn table_rows(report: &Report, table: ReportTable) -> (Vec<&'static str>, Vec<Vec<String>>) {
+    let rate = |rate: f64| format!("{:.1}", rate);
+    match table {
+        ReportTable::Overall => {
+            let o = &report.overall;
+            let mut rows = vec![
+                ("Start Date", o.start_date.to_string()),
+                ("End Date", o.end_date.to_string()),
+                ("Trading Days", o.trading_days.to_string()),
+                ("Profitable Days", o.profitable_days.to_string()),
+                ("Trades", o.total_trades.to_string()),
+                ("Win Rate %", rate(o.win_rate)),
+                ("Net P&L", o.net_pnl.to_string()),
+                ("Volume", o.total_volume.to_string()),
+            ];
+            if o.swing_trades > 0 {
+                rows.push(("Swing Trades", o.swing_trades.to_string()));
+                rows.push(("Swing P&L", o.swing_pnl.to_string()));
+            }
+            if let Some(day) = &o.best_day {
+                rows.push(("Best Day", format!("{} ({})", day.date, day.net_pnl)));
+            }
+            if let Some(day) = &o.worst_day {
+                rows.push(("Worst Day", format!("{} ({})", day.date, day.net_pnl)));
+            }
+            if let Some(hour) = o.best_hour {
+                rows.push(("Best Hour", format!("{:02}:00", hour)));
+            }
+            let rows = rows.into_iter().map(|(metric, value)| vec![metric.to_string(), value]).collect();
+            (vec!["Metric", "Value"], rows)
+        }
+        ReportTable::Daily => {
+            let headers = vec!["Date", "Trades", "Wins", "Losses", "Win Rate %", "Gross P&L", "Commission", "Net P&L", "Volume", "Largest Win", "Largest Loss", "Swing Trades"];
+            let rows = report.daily.iter()
+                .map(|d| vec![
+                    d.date.to_string(),
+                    d.trades.to_string(),
+                    d.winning_trades.to_string(),
+                    d.losing_trades.to_string(),
+                    rate(d.win_rate),
+                    d.gross_pnl.to_string(),
+                    d.commission.to_string(),
+                    d.net_pnl.to_string(),
+                    d.volume.to_string(),
+                    d.largest_win.to_string(),
+                    d.largest_loss.to_string(),
+                    d.swing_trades.to_string(),
+                ])
+                .collect();
+            (headers, rows)
+        }
+        ReportTable::Weekly => {
+            let headers = vec!["Year", "Week", "Start Date", "End Date", "Trading Days", "Profitable Days", "Trades", "Win Rate %", "Gross P&L", "Commission", "Net P&L"];
+            let rows = report.weekly.iter()
+                .map(|w| vec![
+                    w.year.to_string(),
+                    w.week.to_string(),
+                    w.start_date.to_string(),
+                    w.end_date.to_string(),
+                    w.trading_days.to_string(),
+                    w.profitable_days.to_string(),
+                    w.trades.to_string(),
+                    rate(w.win_rate),
+                    w.gross_pnl.to_string(),
+                    w.commission.to_string(),
+                    w.net_pnl.to_string(),
+                ])
+                .collect();
+            (headers, rows)
+        }
+        ReportTable::Monthly => {
+            let headers = vec!["Year", "Month", "Trading Days", "Profitable Days", "Trades", "Win Rate %", "Gross P&L", "Commission", "Net P&L", "Avg Daily P&L"];
+            let rows = report.monthly.iter()
+                .map(|m| vec![
+                    m.year.to_string(),
+                    m.month_name.clone(),
+                    m.trading_days.to_string(),
+                    m.profitable_days.to_string(),
+                    m.trades.to_string(),
+                    rate(m.win_rate),
+                    m.gross_pnl.to_string(),
+                    m.commission.to_string(),
+                    m.net_pnl.to_string(),
+                    m.avg_daily_pnl.to_string(),
+                ])
+                .collect();
+            (headers, rows)
+        }
+        ReportTable::Periods => {
+            let headers = vec!["Rank", "Period", "Hours", "Trades", "Win Rate %", "Net P&L", "Avg P&L per Trade"];
+            let rows = report.best_periods.iter().enumerate()
+                .map(|(i, p)| vec![
+                    (i + 1).to_string(),
+                    p.name.clone(),
+                    format!("{:02}:00-{:02}:00", p.start_hour, p.end_hour),
+                    p.trades.to_string(),
+                    rate(p.win_rate),
+                    p.net_pnl.to_string(),
+                    p.avg_pnl_per_trade.to_string(),
+                ])
+                .collect();
+            (headers, rows)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::sample;
+    use serde_json::Value;
+
+    fn written(report: &Report, format: ReportFormat, table: Option<ReportTable>) -> String {
+        let mut out = Vec::new();
+        write_report(report, format, table, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn json_has_every_table_with_amounts_as_strings() {
+        let (report, _, _) = sample();
+        let text = written(&report, ReportFormat::Json, None);
+        let json: Value = serde_json::from_str(&text).unwrap();
+
+        // Top-level fields in the order the report shows them
+        let fields: Vec<&str> = text.lines()
+            .filter_map(|line| line.strip_prefix("  \"")?.split('"').next())
+            .collect();
+        assert_eq!(fields, ["scope", "overall", "daily", "weekly", "monthly", "best_periods"]);
+        assert_eq!(json["scope"]["exchange_timezone"], "America/New_York");
+        assert_eq!(json["overall"]["net_pnl"], "-4.00");
+        assert_eq!(json["overall"]["best_day"], serde_json::json!({ "date": "2024-01-02", "net_pnl": "73.00" }));
+        assert_eq!(json["daily"][1]["date"], "2024-01-03");
+        assert_eq!(json["daily"][1]["largest_loss"], "-75.00");
+        assert_eq!(json["daily"][1]["win_rate"], 0.0);
+        assert_eq!(json["monthly"][0]["month_name"], "January");
+
+        // One table is written on its own
+        let daily: Value = serde_json::from_str(&written(&report, ReportFormat::Json, Some(ReportTable::Daily))).unwrap();
+        assert_eq!(daily, json["daily"]);
+    }
+
+    #[test]
+    fn csv_writes_the_daily_table_and_quotes_cells() {
+        let (mut report, _, _) = sample();
+        assert_eq!(written(&report, ReportFormat::Csv, None), "\
+Date,Trades,Wins,Losses,Win Rate %,Gross P&L,Commission,Net P&L,Volume,Largest Win,Largest Loss,Swing Trades
+2024-01-02,1,1,0,100.0,75.00,2.00,73.00,37175.00,75.00,0.00,0
+2024-01-03,1,0,1,0.0,-75.00,2.00,-77.00,39925.00,0.00,-75.00,0
+");
+
+        report.best_periods[0].name = "Open, \"first\" hours".to_string();
+        let csv = written(&report, ReportFormat::Csv, Some(ReportTable::Periods));
+        assert_eq!(csv.lines().nth(1), Some("1,\"Open, \"\"first\"\" hours\",10:00-12:00,2,50.0,-4.00,-2.00"));
+    }
+
+    #[test]
+    fn markdown_tables_align_numbers_and_escape_pipes() {
+        let (mut report, _, _) = sample();
+        report.scope.symbol = Some("AAPL".to_string());
+        report.best_periods[0].name = "Open|Close".to_string();
+        report.weekly.clear();
+        let md = written(&report, ReportFormat::Markdown, None);
+
+        assert!(md.starts_with("# TraderRank Report\n\n2024-01-02 to 2024-01-03 · symbol AAPL · days in America/New_York\n"), "{}", md);
+        let headings: Vec<&str> = md.lines().filter(|line| line.starts_with("## ")).collect();
+        assert_eq!(headings, ["## Overall Performance", "## Daily Performance", "## Weekly Performance", "## Monthly Performance", "## Best Trading Periods"]);
+        assert!(md.contains("\
+| Date | Trades | Wins | Losses | Win Rate % | Gross P&L | Commission | Net P&L | Volume | Largest Win | Largest Loss | Swing Trades |
+| --- | ---: | ---: | ---: | ---: | ---: | ---: | ---: | ---: | ---: | ---: | ---: |
+| 2024-01-02 | 1 | 1 | 0 | 100.0 | 75.00 | 2.00 | 73.00 | 37175.00 | 75.00 | 0.00 | 0 |
+"), "{}", md);
+        assert!(md.contains("| Best Day | 2024-01-02 (73.00) |"));
+        assert!(md.contains("## Weekly Performance\n\n_None_\n"));
+        assert!(md.contains("| 1 | Open\\|Close | 10:00-12:00 |"), "{}", md);
+
+        let daily = written(&report, ReportFormat::Markdown, Some(ReportTable::Daily));
+        assert_eq!(daily.matches("## ").count(), 1);
+    }
+}
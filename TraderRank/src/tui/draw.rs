@@ -0,0 +1,371 @@
+//! Drawing the browser: a list of days, weeks or months (or one day's round
+//! trips) beside a month calendar or P&L bars, over the equity curve.
+
+use super::{Basis, Browser, Period};
+use chrono::{Datelike, Duration, NaiveDate};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::symbols::Marker;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Axis, Block, Cell, Chart, Dataset, GraphType, Paragraph, Row, Table, TableState};
+use ratatui::Frame;
+use rust_decimal::prelude::*;
+use std::collections::BTreeMap;
+use trader_rank_core::theme::Palette;
+
+/// Width of a calendar cell, in columns.
+const CELL_WIDTH: usize = 9;
+/// Width of the longest P&L bar, in columns.
+const BAR_WIDTH: usize = 30;
+
+pub fn draw(frame: &mut Frame, browser: &Browser) {
+    let [title, totals, body, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(1),
+        Constraint::Min(10),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    // A day's round trips need more columns than the lists
+    let list_width = if browser.detail { 58 } else { 45 };
+    let [list, side] = Layout::horizontal([Constraint::Percentage(list_width), Constraint::Percentage(100 - list_width)]).areas(body);
+    let [upper, equity] = Layout::vertical([Constraint::Min(8), Constraint::Length(12)]).areas(side);
+
+    draw_title(frame, browser, title);
+    draw_totals(frame, browser, totals);
+    if browser.detail {
+        draw_day_trades(frame, browser, list);
+        draw_hourly(frame, browser, upper);
+    } else {
+        match browser.period {
+            Period::Days => {
+                draw_days(frame, browser, list);
+                if let Some(date) = browser.selected_date() {
+                    draw_calendar(frame, browser, date.year(), date.month(), upper);
+                }
+            }
+            Period::Weeks => {
+                draw_weeks(frame, browser, list);
+                draw_week_bars(frame, browser, upper);
+            }
+            Period::Months => {
+                draw_months(frame, browser, list);
+                if let Some(month) = browser.months().get(browser.month) {
+                    draw_calendar(frame, browser, month.year, month.month, upper);
+                }
+            }
+        }
+    }
+    draw_equity(frame, browser, equity);
+    draw_footer(frame, browser, footer);
+}
+
+fn draw_title(frame: &mut Frame, browser: &Browser, area: Rect) {
+    let tab = |period: Period, label: &'static str| {
+        let span = Span::raw(label);
+        if period == browser.period && !browser.detail { span.reversed() } else { span.fg(color(Palette::DARK.text_secondary)) }
+    };
+    let symbol = browser.symbol.as_deref().unwrap_or("All symbols");
+    let line = Line::from(vec![
+        Span::raw(" TraderRank ").bold().fg(color(Palette::DARK.accent_cyan)),
+        tab(Period::Days, " 1 Days "),
+        tab(Period::Weeks, " 2 Weeks "),
+        tab(Period::Months, " 3 Months "),
+        Span::raw(format!("  {}  ", symbol)).bold(),
+        Span::raw(format!("{} P&L", browser.basis.name())).fg(color(Palette::DARK.accent_yellow)),
+        Span::raw(format!("  {}", browser.scope)).fg(color(Palette::DARK.text_secondary)),
+    ]);
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+fn draw_totals(frame: &mut Frame, browser: &Browser, area: Rect) {
+    let summary = browser.summary();
+    let days = browser.days();
+    let pnl: Decimal = days.iter().map(|d| browser.basis.day(d)).sum();
+    let profitable = days.iter().filter(|d| browser.basis.day(d) > Decimal::ZERO).count();
+    let streaks = summary.day_streaks();
+    let streak = match streaks.current {
+        0 => Span::raw("no streak"),
+        n if n > 0 => Span::raw(format!("{} winning day(s)", n)).fg(green()),
+        n => Span::raw(format!("{} losing day(s)", -n)).fg(red()),
+    };
+    let line = Line::from(vec![
+        Span::raw(format!(" {} to {}  ", summary.start_date.format("%Y-%m-%d"), summary.end_date.format("%Y-%m-%d"))),
+        Span::raw(format!("{} days ({} green)  {} trades  win {:.1}%  ", days.len(), profitable, summary.total_trades, summary.overall_win_rate)),
+        pnl_span(pnl),
+        Span::raw("  streak: "),
+        streak,
+        Span::raw(format!(" (longest {} won, {} lost)", streaks.longest_win, streaks.longest_loss)).fg(color(Palette::DARK.text_secondary)),
+    ]);
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+fn draw_days(frame: &mut Frame, browser: &Browser, area: Rect) {
+    let rows = browser.days().iter().map(|d| Row::new(vec![
+        Cell::from(d.date.format("%Y-%m-%d").to_string()),
+        Cell::from(d.date.format("%a").to_string()),
+        Cell::from(d.total_trades.to_string()),
+        Cell::from(format!("{:.1}%", d.win_rate)),
+        pnl_cell(browser.basis.day(d)),
+    ]));
+    let widths = [Constraint::Length(10), Constraint::Length(3), Constraint::Length(6), Constraint::Length(6), Constraint::Min(12)];
+    let header = ["Date", "Day", "Trades", "Win", "P&L"];
+    draw_list(frame, area, "Days (Enter: open the day)", header.to_vec(), rows.collect(), &widths, browser.day);
+}
+
+fn draw_weeks(frame: &mut Frame, browser: &Browser, area: Rect) {
+    let rows = browser.weeks().iter().map(|w| Row::new(vec![
+        Cell::from(format!("{}-W{:02}", w.year, w.week_number)),
+        Cell::from(w.start_date.format("%m/%d").to_string()),
+        Cell::from(w.trading_days.to_string()),
+        Cell::from(w.total_trades.to_string()),
+        Cell::from(format!("{:.1}%", w.win_rate)),
+        pnl_cell(browser.basis.week(w)),
+    ]));
+    let widths = [Constraint::Length(8), Constraint::Length(5), Constraint::Length(4), Constraint::Length(6), Constraint::Length(6), Constraint::Min(12)];
+    let header = ["Week", "From", "Days", "Trades", "Win", "P&L"];
+    draw_list(frame, area, "Weeks (Enter: its days)", header.to_vec(), rows.collect(), &widths, browser.week);
+}
+
+fn draw_months(frame: &mut Frame, browser: &Browser, area: Rect) {
+    let rows = browser.months().iter().map(|m| {
+        let pnl = browser.basis.month(m);
+        let per_day = if m.trading_days > 0 { pnl / Decimal::from(m.trading_days) } else { Decimal::ZERO };
+        Row::new(vec![
+            Cell::from(format!("{} {}", &m.month_name[..3.min(m.month_name.len())], m.year)),
+            Cell::from(m.trading_days.to_string()),
+            Cell::from(m.total_trades.to_string()),
+            Cell::from(format!("{:.1}%", m.win_rate)),
+            pnl_cell(pnl),
+            Cell::from(format!("{:.2}", per_day)),
+        ])
+    });
+    let widths = [Constraint::Length(8), Constraint::Length(4), Constraint::Length(6), Constraint::Length(6), Constraint::Length(12), Constraint::Min(10)];
+    let header = ["Month", "Days", "Trades", "Win", "P&L", "Per Day"];
+    draw_list(frame, area, "Months (Enter: its days)", header.to_vec(), rows.collect(), &widths, browser.month);
+}
+
+fn draw_day_trades(frame: &mut Frame, browser: &Browser, area: Rect) {
+    let tz = browser.tz;
+    let trades = browser.day_trades();
+    let rows = trades.iter().map(|mt| Row::new(vec![
+        Cell::from(mt.exit_time.with_timezone(&tz).format("%H:%M").to_string()),
+        Cell::from(mt.symbol.clone()),
+        Cell::from(mt.side.clone()),
+        Cell::from(mt.quantity.normalize().to_string()),
+        Cell::from(mt.entry_price.round_dp(4).normalize().to_string()),
+        Cell::from(mt.exit_price.normalize().to_string()),
+        pnl_cell(browser.basis.trade(mt)),
+        Cell::from(if mt.is_swing() { "swing" } else { "" }),
+    ]));
+    let widths = [
+        Constraint::Length(5),
+        Constraint::Length(8),
+        Constraint::Length(5),
+        Constraint::Length(6),
+        Constraint::Length(9),
+        Constraint::Length(9),
+        Constraint::Length(10),
+        Constraint::Min(5),
+    ];
+    let header = ["Time", "Symbol", "Side", "Qty", "Entry", "Exit", "P&L", ""];
+    let title = match browser.selected_date() {
+        Some(date) => format!("{} round trips (Esc: back)", date.format("%a %Y-%m-%d")),
+        None => "Round trips".to_string(),
+    };
+    draw_list(frame, area, &title, header.to_vec(), rows.collect(), &widths, browser.trade_offset);
+}
+
+/// A bordered table with `selected` highlighted and scrolled into view.
+fn draw_list(frame: &mut Frame, area: Rect, title: &str, header: Vec<&str>, rows: Vec<Row>, widths: &[Constraint], selected: usize) {
+    let table = Table::new(rows, widths)
+        .header(Row::new(header).bold().fg(color(Palette::DARK.text_secondary)))
+        .row_highlight_style(Style::new().reversed())
+        .block(Block::bordered().title(format!(" {} ", title)));
+    let mut state = TableState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+/// A month calendar, Sunday first, each trading day with its P&L; the selected day is highlighted.
+fn draw_calendar(frame: &mut Frame, browser: &Browser, year: i32, month: u32, area: Rect) {
+    let Some(first) = NaiveDate::from_ymd_opt(year, month, 1) else {
+        return;
+    };
+    let pnl: BTreeMap<NaiveDate, Decimal> = browser.days().iter()
+        .map(|d| (d.date.date_naive(), browser.basis.day(d)))
+        .collect();
+    let selected = browser.selected_date();
+
+    let mut lines = vec![Line::from(
+        ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"].iter()
+            .map(|day| Span::raw(format!("{:^width$}", day, width = CELL_WIDTH)).fg(color(Palette::DARK.text_secondary)))
+            .collect::<Vec<_>>(),
+    )];
+    let mut week_start = first - Duration::days(first.weekday().num_days_from_sunday() as i64);
+    while week_start.month() == month || week_start < first {
+        let mut days = Vec::new();
+        let mut amounts = Vec::new();
+        for offset in 0..7 {
+            let date = week_start + Duration::days(offset);
+            if date.month() != month {
+                days.push(Span::raw(" ".repeat(CELL_WIDTH)));
+                amounts.push(Span::raw(" ".repeat(CELL_WIDTH)));
+                continue;
+            }
+            let mut style = Style::new();
+            let amount = match pnl.get(&date) {
+                Some(amount) => {
+                    style = style.fg(if *amount < Decimal::ZERO { red() } else { green() });
+                    compact(*amount)
+                }
+                None => String::new(),
+            };
+            if Some(date) == selected {
+                style = style.add_modifier(Modifier::REVERSED | Modifier::BOLD);
+            }
+            days.push(Span::styled(format!("{:^width$}", date.day(), width = CELL_WIDTH), style));
+            amounts.push(Span::styled(format!("{:^width$}", amount, width = CELL_WIDTH), style));
+        }
+        lines.push(Line::from(days));
+        lines.push(Line::from(amounts));
+        week_start += Duration::days(7);
+    }
+
+    let month_pnl: Decimal = pnl.iter().filter(|(date, _)| date.year() == year && date.month() == month).map(|(_, pnl)| *pnl).sum();
+    let title = Line::from(vec![
+        Span::raw(format!(" {} ", first.format("%B %Y"))),
+        pnl_span(month_pnl),
+        Span::raw(" (←/→: a week) "),
+    ]);
+    frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(title)), area);
+}
+
+/// The selected week's days as P&L bars.
+fn draw_week_bars(frame: &mut Frame, browser: &Browser, area: Rect) {
+    let Some(week) = browser.weeks().get(browser.week) else {
+        return;
+    };
+    let bars = week.daily_summaries.iter()
+        .map(|d| (d.date.format("%a %m/%d").to_string(), browser.basis.day(d)))
+        .collect();
+    let title = format!(" Week {} of {}: {} to {} ", week.week_number, week.year, week.start_date.format("%m/%d"), week.end_date.format("%m/%d"));
+    draw_bars(frame, area, &title, bars);
+}
+
+/// The selected day's P&L by the hour its round trips closed.
+fn draw_hourly(frame: &mut Frame, browser: &Browser, area: Rect) {
+    let mut hours: BTreeMap<u32, Decimal> = BTreeMap::new();
+    for mt in browser.day_trades() {
+        *hours.entry(mt.exit_hour(browser.tz)).or_default() += browser.basis.trade(mt);
+    }
+    let bars = hours.into_iter().map(|(hour, pnl)| (format!("{:02}:00", hour), pnl)).collect();
+    draw_bars(frame, area, " Hourly P&L ", bars);
+}
+
+/// Labelled horizontal bars, green for gains and red for losses, scaled to the largest.
+fn draw_bars(frame: &mut Frame, area: Rect, title: &str, bars: Vec<(String, Decimal)>) {
+    let largest = bars.iter().map(|(_, pnl)| pnl.abs()).max().unwrap_or(Decimal::ZERO);
+    let lines: Vec<Line> = bars.into_iter()
+        .map(|(label, pnl)| {
+            let width = match largest.is_zero() {
+                true => 0,
+                false => (pnl.abs() / largest * Decimal::from(BAR_WIDTH)).to_usize().unwrap_or(0),
+            };
+            let bar_color = if pnl < Decimal::ZERO { red() } else { green() };
+            Line::from(vec![
+                Span::raw(format!("{} ", label)),
+                Span::raw(format!("{:<width$}", "█".repeat(width), width = BAR_WIDTH)).fg(bar_color),
+                Span::raw(" "),
+                pnl_span(pnl),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(title.to_string())), area);
+}
+
+/// Cumulative P&L over every day shown, with the selected day marked.
+fn draw_equity(frame: &mut Frame, browser: &Browser, area: Rect) {
+    let days = browser.days();
+    let mut total = 0.0;
+    let points: Vec<(f64, f64)> = days.iter().enumerate()
+        .map(|(i, d)| {
+            total += browser.basis.day(d).to_f64().unwrap_or(0.0);
+            (i as f64, total)
+        })
+        .collect();
+    let selected: Vec<(f64, f64)> = points.get(browser.day).copied().into_iter().collect();
+    let (low, high) = points.iter().fold((0.0f64, 0.0f64), |(low, high), (_, y)| (low.min(*y), high.max(*y)));
+    let high = if (high - low).abs() < f64::EPSILON { low + 1.0 } else { high };
+    let last_x = (points.len().max(2) - 1) as f64;
+
+    let first_date = days.first().map(|d| d.date.format("%Y-%m-%d").to_string()).unwrap_or_default();
+    let last_date = days.last().map(|d| d.date.format("%Y-%m-%d").to_string()).unwrap_or_default();
+    let chart = Chart::new(vec![
+        Dataset::default()
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::new().fg(color(Palette::DARK.accent_cyan)))
+            .data(&points),
+        Dataset::default()
+            .marker(Marker::Dot)
+            .graph_type(GraphType::Scatter)
+            .style(Style::new().fg(color(Palette::DARK.accent_yellow)).bold())
+            .data(&selected),
+    ])
+    .block(Block::bordered().title(format!(" Equity Curve ({}) ", browser.basis.name())))
+    .x_axis(Axis::default().bounds([0.0, last_x]).labels([first_date, last_date]))
+    .y_axis(Axis::default().bounds([low, high]).labels([format!("{:.0}", low), format!("{:.0}", high)]));
+    frame.render_widget(chart, area);
+}
+
+fn draw_footer(frame: &mut Frame, browser: &Browser, area: Rect) {
+    let line = if let Some(input) = &browser.prompt {
+        Line::from(vec![
+            Span::raw(" Symbol (empty for all, Esc to cancel): ").fg(color(Palette::DARK.accent_yellow)),
+            Span::raw(format!("{}█", input)),
+        ])
+    } else if let Some(status) = &browser.status {
+        Line::from(Span::raw(format!(" {}", status)).fg(color(Palette::DARK.accent_orange)))
+    } else {
+        let keys = match browser.basis {
+            Basis::Net => "g: gross",
+            Basis::Gross => "g: net",
+        };
+        Line::from(Span::raw(format!(
+            " ↑/↓ scroll  PgUp/PgDn  Enter open  Esc back  Tab/1-3 view  {}  s/S next symbol  / type symbol  q quit",
+            keys,
+        )).fg(color(Palette::DARK.text_secondary)))
+    };
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+fn pnl_span(pnl: Decimal) -> Span<'static> {
+    Span::raw(format!("${:.2}", pnl)).fg(if pnl < Decimal::ZERO { red() } else { green() })
+}
+
+fn pnl_cell(pnl: Decimal) -> Cell<'static> {
+    Cell::from(pnl_span(pnl))
+}
+
+/// A calendar cell's P&L: "+850", "-1.2k".
+fn compact(pnl: Decimal) -> String {
+    let amount = pnl.to_f64().unwrap_or(0.0);
+    if amount.abs() >= 1000.0 {
+        format!("{:+.1}k", amount / 1000.0)
+    } else {
+        format!("{:+.0}", amount)
+    }
+}
+
+fn green() -> Color {
+    color(Palette::DARK.accent_green)
+}
+
+fn red() -> Color {
+    color(Palette::DARK.accent_red)
+}
+
+/// A palette colour; the `rgba(...)` ones are not used here.
+fn color(hex: &str) -> Color {
+    hex.parse().unwrap_or(Color::Reset)
+}
@@ -0,0 +1,533 @@
+//! `tui`: a full-screen browser over the whole history. Days, weeks and months
+//! scroll with the keyboard, a day opens into its round trips and hourly P&L,
+//! and P&L switches between net and gross.
+
+mod draw;
+
+use crate::commands::warn_open_positions;
+use anyhow::{bail, Result};
+use chrono::{Datelike, Duration, NaiveDate};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::IsTerminal;
+use trader_rank_core::exchange_time::{DateRange, Tz};
+use trader_rank_core::models::{DailySummary, MatchedTrade, MonthlySummary, Trade, TradingSummary, WeeklySummary};
+use trader_rank_core::trade_matcher::{match_trades_with, MatchOptions};
+use trader_rank_core::TradingAnalytics;
+
+/// The list being browsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Days,
+    Weeks,
+    Months,
+}
+
+/// Which P&L is shown: after or before commissions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Basis {
+    Net,
+    Gross,
+}
+
+impl Basis {
+    fn day(self, day: &DailySummary) -> Decimal {
+        match self {
+            Basis::Net => day.realized_pnl,
+            Basis::Gross => day.gross_pnl,
+        }
+    }
+
+    fn week(self, week: &WeeklySummary) -> Decimal {
+        match self {
+            Basis::Net => week.realized_pnl,
+            Basis::Gross => week.gross_pnl,
+        }
+    }
+
+    fn month(self, month: &MonthlySummary) -> Decimal {
+        match self {
+            Basis::Net => month.realized_pnl,
+            Basis::Gross => month.gross_pnl,
+        }
+    }
+
+    fn trade(self, trade: &MatchedTrade) -> Decimal {
+        match self {
+            Basis::Net => trade.net_pnl,
+            Basis::Gross => trade.gross_pnl,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Basis::Net => "Net",
+            Basis::Gross => "Gross",
+        }
+    }
+}
+
+/// The analysis of one symbol, or of every symbol.
+struct Slice {
+    summary: TradingSummary,
+    /// Round trips closed on the selected days, oldest first
+    matched: Vec<MatchedTrade>,
+}
+
+/// What the browser shows and where it is.
+pub struct Browser {
+    /// Keyed by symbol; `None` holds every symbol
+    slices: BTreeMap<Option<String>, Slice>,
+    symbols: Vec<String>,
+    symbol: Option<String>,
+    tz: Tz,
+    /// The account, days and timezone covered, for the title
+    scope: String,
+    period: Period,
+    basis: Basis,
+    /// Selected row of each list
+    day: usize,
+    week: usize,
+    month: usize,
+    /// The selected day is opened into its round trips
+    detail: bool,
+    /// First round trip shown in the day's list
+    trade_offset: usize,
+    /// Symbol being typed after `/`
+    prompt: Option<String>,
+    /// One-line message shown until the next key
+    status: Option<String>,
+}
+
+/// Browse `fills` until the user quits. The fills are analyzed once up front and
+/// each symbol's view is cut from that, so no analysis runs while the screen is
+/// drawn and its warnings print once.
+pub fn run(fills: &[Trade], options: MatchOptions, range: DateRange, scope: String) -> Result<()> {
+    if !std::io::stdout().is_terminal() {
+        bail!("tui needs a terminal; use `report` or `trades` to write to a file or pipe");
+    }
+
+    let browser = Browser::new(fills, options, range, scope);
+    if browser.days().is_empty() {
+        bail!("No round trips were closed on the selected days");
+    }
+
+    let mut terminal = ratatui::init();
+    let result = browser.event_loop(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl Browser {
+    fn new(fills: &[Trade], options: MatchOptions, range: DateRange, scope: String) -> Self {
+        let all = Slice::all(fills, options, range);
+        // Symbols with round trips closed on the selected days
+        let symbols: Vec<String> = all.matched.iter()
+            .map(|mt| mt.symbol.clone())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let mut slices: BTreeMap<_, _> = symbols.iter()
+            .map(|symbol| (Some(symbol.clone()), all.symbol(fills, symbol, options.exchange_tz, range)))
+            .collect();
+        slices.insert(None, all);
+
+        let mut browser = Browser {
+            slices,
+            symbols,
+            symbol: None,
+            tz: options.exchange_tz,
+            scope,
+            period: Period::Days,
+            basis: Basis::Net,
+            day: 0,
+            week: 0,
+            month: 0,
+            detail: false,
+            trade_offset: 0,
+            prompt: None,
+            status: None,
+        };
+        browser.day = browser.days().len().saturating_sub(1);
+        browser.sync_from_day();
+        browser
+    }
+
+    fn event_loop(mut self, terminal: &mut ratatui::DefaultTerminal) -> Result<()> {
+        loop {
+            terminal.draw(|frame| draw::draw(frame, &self))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !self.handle_key(key) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    fn slice(&self) -> &Slice {
+        &self.slices[&self.symbol]
+    }
+
+    fn summary(&self) -> &TradingSummary {
+        &self.slice().summary
+    }
+
+    fn days(&self) -> &[DailySummary] {
+        &self.summary().daily_summaries
+    }
+
+    fn weeks(&self) -> &[WeeklySummary] {
+        &self.summary().weekly_summaries
+    }
+
+    fn months(&self) -> &[MonthlySummary] {
+        &self.summary().monthly_summaries
+    }
+
+    fn selected_day(&self) -> Option<&DailySummary> {
+        self.days().get(self.day)
+    }
+
+    fn selected_date(&self) -> Option<NaiveDate> {
+        self.selected_day().map(|d| d.date.date_naive())
+    }
+
+    /// Round trips closed on the selected day.
+    fn day_trades(&self) -> Vec<&MatchedTrade> {
+        let Some(date) = self.selected_date() else {
+            return Vec::new();
+        };
+        self.slice().matched.iter().filter(|mt| mt.exit_date(self.tz) == date).collect()
+    }
+
+    /// Handle a key press; false when the browser should close.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        self.status = None;
+        if let Some(input) = &mut self.prompt {
+            match key.code {
+                KeyCode::Enter => {
+                    let symbol = input.trim().to_string();
+                    self.prompt = None;
+                    self.filter_symbol(&symbol);
+                }
+                KeyCode::Esc => self.prompt = None,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+            return true;
+        }
+
+        match key.code {
+            KeyCode::Char('q') => return false,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Esc | KeyCode::Backspace if self.detail => self.detail = false,
+            KeyCode::Esc => return false,
+            KeyCode::Char('1') => self.show(Period::Days),
+            KeyCode::Char('2') => self.show(Period::Weeks),
+            KeyCode::Char('3') => self.show(Period::Months),
+            KeyCode::Tab => self.show(match self.period {
+                Period::Days => Period::Weeks,
+                Period::Weeks => Period::Months,
+                Period::Months => Period::Days,
+            }),
+            KeyCode::Char('g') | KeyCode::Char('n') => {
+                self.basis = match self.basis {
+                    Basis::Net => Basis::Gross,
+                    Basis::Gross => Basis::Net,
+                };
+            }
+            KeyCode::Char('/') => self.prompt = Some(String::new()),
+            KeyCode::Char('s') => self.cycle_symbol(1),
+            KeyCode::Char('S') => self.cycle_symbol(-1),
+            KeyCode::Enter => self.open(),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll(1),
+            KeyCode::PageUp => self.scroll(-10),
+            KeyCode::PageDown => self.scroll(10),
+            KeyCode::Home => self.scroll(isize::MIN / 2),
+            KeyCode::End => self.scroll(isize::MAX / 2),
+            // In the calendar, left and right move a week
+            KeyCode::Left | KeyCode::Char('h') if self.period == Period::Days && !self.detail => self.move_days(-7),
+            KeyCode::Right | KeyCode::Char('l') if self.period == Period::Days && !self.detail => self.move_days(7),
+            KeyCode::Left | KeyCode::Char('h') => self.scroll(-1),
+            KeyCode::Right | KeyCode::Char('l') => self.scroll(1),
+            _ => {}
+        }
+        true
+    }
+
+    fn show(&mut self, period: Period) {
+        self.detail = false;
+        self.period = period;
+    }
+
+    /// Move the selection by `rows`: round trips in a day, else the list's rows.
+    fn scroll(&mut self, rows: isize) {
+        let step = |index: usize, len: usize| (index as isize + rows).clamp(0, len.saturating_sub(1) as isize) as usize;
+        if self.detail {
+            self.trade_offset = step(self.trade_offset, self.day_trades().len());
+            return;
+        }
+        match self.period {
+            Period::Days => {
+                self.day = step(self.day, self.days().len());
+                self.sync_from_day();
+            }
+            Period::Weeks => self.week = step(self.week, self.weeks().len()),
+            Period::Months => self.month = step(self.month, self.months().len()),
+        }
+    }
+
+    /// Select the trading day `days` calendar days from the selected one, or the
+    /// nearest trading day before it.
+    fn move_days(&mut self, days: i64) {
+        if let Some(date) = self.selected_date() {
+            self.select_date(date + Duration::days(days));
+        }
+    }
+
+    /// Enter: a week or month opens on its last trading day; a day opens into its trades.
+    fn open(&mut self) {
+        let last = match self.period {
+            Period::Days => {
+                self.detail = self.selected_day().is_some();
+                self.trade_offset = 0;
+                return;
+            }
+            Period::Weeks => self.weeks().get(self.week).map(|w| w.end_date.date_naive()),
+            Period::Months => self.months().get(self.month).and_then(|m| last_of_month(m.year, m.month)),
+        };
+        if let Some(date) = last {
+            self.select_date(date);
+            self.period = Period::Days;
+        }
+    }
+
+    /// Select the last trading day on or before `date`, else the first one.
+    fn select_date(&mut self, date: NaiveDate) {
+        self.day = self.days().iter()
+            .rposition(|d| d.date.date_naive() <= date)
+            .unwrap_or(0);
+        self.sync_from_day();
+    }
+
+    /// Keep the week and month lists on the selected day.
+    fn sync_from_day(&mut self) {
+        let Some(date) = self.selected_date() else {
+            return;
+        };
+        if let Some(week) = self.weeks().iter().position(|w| w.start_date.date_naive() <= date && date <= w.end_date.date_naive()) {
+            self.week = week;
+        }
+        if let Some(month) = self.months().iter().position(|m| m.year == date.year() && m.month == date.month()) {
+            self.month = month;
+        }
+    }
+
+    fn cycle_symbol(&mut self, step: isize) {
+        // Position 0 is every symbol
+        let count = self.symbols.len() as isize + 1;
+        let current = match &self.symbol {
+            Some(symbol) => self.symbols.iter().position(|s| s == symbol).map_or(0, |i| i as isize + 1),
+            None => 0,
+        };
+        let next = (current + step).rem_euclid(count) as usize;
+        let symbol = match next {
+            0 => String::new(),
+            i => self.symbols[i - 1].clone(),
+        };
+        self.filter_symbol(&symbol);
+    }
+
+    /// Show one symbol, or every symbol when `symbol` is empty, staying near the selected day.
+    fn filter_symbol(&mut self, symbol: &str) {
+        let symbol = match self.symbols.iter().find(|s| s.eq_ignore_ascii_case(symbol)) {
+            _ if symbol.is_empty() => None,
+            Some(known) => Some(known.clone()),
+            None => {
+                self.status = Some(format!("No round trips for symbol {}", symbol));
+                return;
+            }
+        };
+        let date = self.selected_date();
+        self.symbol = symbol;
+        self.detail = false;
+        self.day = self.days().len().saturating_sub(1);
+        match date {
+            Some(date) => self.select_date(date),
+            None => self.sync_from_day(),
+        }
+        if self.days().is_empty() {
+            self.status = Some("No round trips were closed on the selected days".to_string());
+        }
+    }
+}
+
+impl Slice {
+    /// Every symbol, warning about positions left open.
+    fn all(fills: &[Trade], options: MatchOptions, range: DateRange) -> Self {
+        let (summary, open) = TradingAnalytics::analyze_positions_in(fills, options, range);
+        warn_open_positions(&open, options);
+        let mut matched = match_trades_with(fills, options);
+        matched.retain(|mt| range.contains(mt.exit_date(options.exchange_tz)));
+        matched.sort_by_key(|mt| mt.exit_time);
+        Slice { summary, matched }
+    }
+
+    /// One symbol's round trips out of every symbol's. Positions are matched per
+    /// symbol, so they are the same as `symbol`'s fills matched on their own.
+    fn symbol(&self, fills: &[Trade], symbol: &str, tz: Tz, range: DateRange) -> Self {
+        let fills: Vec<Trade> = fills.iter().filter(|t| t.symbol == symbol).cloned().collect();
+        let matched: Vec<MatchedTrade> = self.matched.iter().filter(|mt| mt.symbol == symbol).cloned().collect();
+        let summary = TradingAnalytics::summarize_matched_in(&fills, matched.clone(), tz, range);
+        Slice { summary, matched }
+    }
+}
+
+fn last_of_month(year: i32, month: u32) -> Option<NaiveDate> {
+    let (year, month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    NaiveDate::from_ymd_opt(year, month, 1).map(|first| first - Duration::days(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::fill;
+    use trader_rank_core::Side;
+
+    /// A round trip bought at 10:00 and sold at 11:00 for `exit` against an entry of 100.
+    fn round_trip(symbol: &str, date: &str, exit: &str) -> [Trade; 2] {
+        [
+            fill(symbol, Side::Buy, 100, "100", &format!("{} 10:00:00", date)),
+            fill(symbol, Side::Sell, 100, exit, &format!("{} 11:00:00", date)),
+        ]
+    }
+
+    /// AAPL on Tue 2 Jan, Fri 5 Jan and Mon 8 Jan; MSFT on Wed 3 Jan and Thu 1 Feb.
+    fn browser() -> Browser {
+        let fills: Vec<Trade> = [
+            round_trip("AAPL", "2024-01-02", "101"),
+            round_trip("MSFT", "2024-01-03", "99"),
+            round_trip("AAPL", "2024-01-05", "102"),
+            round_trip("AAPL", "2024-01-08", "98"),
+            round_trip("MSFT", "2024-02-01", "103"),
+        ].concat();
+        Browser::new(&fills, MatchOptions::default(), DateRange::default(), String::new())
+    }
+
+    fn date(text: &str) -> NaiveDate {
+        text.parse().unwrap()
+    }
+
+    fn press(browser: &mut Browser, code: KeyCode) -> bool {
+        browser.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn opens_on_the_last_trading_day() {
+        let browser = browser();
+        assert_eq!(browser.symbols, ["AAPL", "MSFT"]);
+        assert_eq!(browser.selected_date(), Some(date("2024-02-01")));
+        assert_eq!((browser.week, browser.month), (browser.weeks().len() - 1, 1));
+        assert_eq!(browser.day_trades().len(), 1);
+    }
+
+    #[test]
+    fn select_date_takes_the_trading_day_on_or_before() {
+        let mut browser = browser();
+        browser.select_date(date("2024-01-07"));
+        assert_eq!(browser.selected_date(), Some(date("2024-01-05")));
+        assert_eq!(browser.weeks()[browser.week].start_date.date_naive(), date("2024-01-01"));
+        assert_eq!(browser.month, 0);
+
+        browser.select_date(date("2024-01-08"));
+        assert_eq!(browser.selected_date(), Some(date("2024-01-08")));
+        assert_eq!(browser.weeks()[browser.week].start_date.date_naive(), date("2024-01-08"));
+
+        // Before the first trading day, the first one
+        browser.select_date(date("2023-12-29"));
+        assert_eq!(browser.selected_date(), Some(date("2024-01-02")));
+
+        browser.select_date(date("2024-12-31"));
+        assert_eq!(browser.selected_date(), Some(date("2024-02-01")));
+        assert_eq!(browser.month, 1);
+    }
+
+    #[test]
+    fn keys_move_switch_and_open() {
+        let mut browser = browser();
+        assert!(press(&mut browser, KeyCode::Up));
+        assert_eq!(browser.selected_date(), Some(date("2024-01-08")));
+        // Left and right move a calendar week
+        press(&mut browser, KeyCode::Left);
+        assert_eq!(browser.selected_date(), Some(date("2024-01-02")));
+        press(&mut browser, KeyCode::Right);
+        assert_eq!(browser.selected_date(), Some(date("2024-01-08")));
+        press(&mut browser, KeyCode::Home);
+        assert_eq!(browser.day, 0);
+        press(&mut browser, KeyCode::End);
+        assert_eq!(browser.selected_date(), Some(date("2024-02-01")));
+
+        press(&mut browser, KeyCode::Char('g'));
+        assert_eq!(browser.basis, Basis::Gross);
+        press(&mut browser, KeyCode::Char('n'));
+        assert_eq!(browser.basis, Basis::Net);
+
+        // A month opens on its last trading day
+        press(&mut browser, KeyCode::Tab);
+        assert_eq!(browser.period, Period::Weeks);
+        press(&mut browser, KeyCode::Char('3'));
+        assert_eq!(browser.period, Period::Months);
+        press(&mut browser, KeyCode::Up);
+        press(&mut browser, KeyCode::Enter);
+        assert_eq!(browser.period, Period::Days);
+        assert_eq!(browser.selected_date(), Some(date("2024-01-08")));
+
+        // A day opens into its trades; Esc closes it, then the browser
+        press(&mut browser, KeyCode::Enter);
+        assert!(browser.detail);
+        assert!(press(&mut browser, KeyCode::Esc));
+        assert!(!browser.detail);
+        assert!(!press(&mut browser, KeyCode::Esc));
+        assert!(!press(&mut browser, KeyCode::Char('q')));
+        assert!(!browser.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)));
+    }
+
+    #[test]
+    fn symbols_are_filtered_from_one_analysis() {
+        let mut browser = browser();
+        let all = browser.summary().total_pnl;
+        let by_symbol: Decimal = browser.slices.iter()
+            .filter(|(symbol, _)| symbol.is_some())
+            .map(|(_, slice)| slice.summary.total_pnl)
+            .sum();
+        assert_eq!(by_symbol, all);
+
+        // Typed after `/`, in any case, staying near the selected day
+        browser.select_date(date("2024-01-05"));
+        for code in [KeyCode::Char('/'), KeyCode::Char('m'), KeyCode::Char('s'), KeyCode::Char('x'), KeyCode::Backspace, KeyCode::Char('f'), KeyCode::Char('t'), KeyCode::Enter] {
+            assert!(press(&mut browser, code));
+        }
+        assert_eq!(browser.symbol.as_deref(), Some("MSFT"));
+        assert_eq!(browser.days().len(), 2);
+        assert_eq!(browser.selected_date(), Some(date("2024-01-03")));
+        assert!(browser.slice().matched.iter().all(|mt| mt.symbol == "MSFT"));
+
+        browser.filter_symbol("TSLA");
+        assert_eq!(browser.status.as_deref(), Some("No round trips for symbol TSLA"));
+        assert_eq!(browser.symbol.as_deref(), Some("MSFT"));
+
+        // `s` cycles through the symbols and back to all of them
+        press(&mut browser, KeyCode::Char('s'));
+        assert_eq!(browser.symbol, None);
+        assert_eq!(browser.days().len(), 5);
+        press(&mut browser, KeyCode::Char('s'));
+        assert_eq!(browser.symbol.as_deref(), Some("AAPL"));
+        press(&mut browser, KeyCode::Char('S'));
+        assert_eq!(browser.symbol, None);
+    }
+}
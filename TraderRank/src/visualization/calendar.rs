@@ -1,9 +1,12 @@
-use crate::models::{DailySummary, TradingSummary};
+use trader_rank_core::models::{DailySummary, TradingSummary};
 use chrono::{Datelike, Duration, NaiveDate, Utc, Weekday};
 use colored::*;
 use rust_decimal::Decimal;
 use std::collections::HashMap;
 
+/// Calendar grid: weeks of seven cells, each `Some((day_of_month, pnl))` or `None` for padding
+type CalendarWeeks = Vec<Vec<Option<(u32, Option<Decimal>)>>>;
+
 pub struct CalendarRenderer;
 
 impl CalendarRenderer {
@@ -86,7 +89,7 @@ impl CalendarRenderer {
             let mut week: Vec<NaiveDate> = Vec::new();
             for _ in 0..7 {
                 week.push(current_date);
-                current_date = current_date + Duration::days(1);
+                current_date += Duration::days(1);
             }
             weeks.push(week);
         }
@@ -314,7 +317,7 @@ impl CalendarRenderer {
         };
 
         // Build calendar in memory first for better formatting
-        let mut weeks: Vec<Vec<Option<(u32, Option<Decimal>)>>> = Vec::new();
+        let mut weeks: CalendarWeeks = Vec::new();
         let mut current_week: Vec<Option<(u32, Option<Decimal>)>> = Vec::new();
 
         // Add padding for first week
@@ -345,7 +348,7 @@ impl CalendarRenderer {
         // Print calendar weeks
         for week in weeks {
             // Print day numbers
-            for (_idx, day_opt) in week.iter().enumerate() {
+            for day_opt in week.iter() {
                 match day_opt {
                     Some((day, _)) => {
                         let date = NaiveDate::from_ymd_opt(year, month, *day).unwrap();
@@ -554,7 +557,7 @@ impl CalendarRenderer {
         };
 
         // Build calendar in memory first for better formatting
-        let mut weeks: Vec<Vec<Option<(u32, Option<Decimal>)>>> = Vec::new();
+        let mut weeks: CalendarWeeks = Vec::new();
         let mut current_week: Vec<Option<(u32, Option<Decimal>)>> = Vec::new();
 
         // Add padding for first week
@@ -1,4 +1,4 @@
-use crate::models::DailySummary;
+use trader_rank_core::models::DailySummary;
 use colored::*;
 use rust_decimal::prelude::*;
 
@@ -184,7 +184,7 @@ impl ChartRenderer {
                 format!("▼${:.2}", summary.realized_pnl.abs()).red()
             };
 
-            println!("{} {} {}", date_str.bright_white(), bar.to_string(), pnl_str);
+            println!("{} {} {}", date_str.bright_white(), bar, pnl_str);
         }
     }
 
@@ -225,7 +225,7 @@ impl ChartRenderer {
             // Also show commission impact
             let commission_str = format!("(-${:.2})", summary.total_commission).yellow();
 
-            println!("{} {} {} {}", date_str.bright_white(), bar.to_string(), gross_pnl_str, commission_str);
+            println!("{} {} {} {}", date_str.bright_white(), bar, gross_pnl_str, commission_str);
         }
 
         // Show total commission impact
@@ -522,8 +522,8 @@ impl ChartRenderer {
         // Draw 50% reference line
         let fifty_percent_y = ((display_max - 50.0) / range * (height - 1) as f64) as usize;
         if fifty_percent_y < height {
-            for x in 0..width {
-                chart[fifty_percent_y][x] = '·';
+            for cell in chart[fifty_percent_y].iter_mut() {
+                *cell = '·';
             }
         }
 
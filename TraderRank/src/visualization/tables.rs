@@ -1,4 +1,4 @@
-use crate::models::{DailySummary, TradingSummary};
+use trader_rank_core::models::{DailySummary, TradingSummary};
 use colored::*;
 use rust_decimal::Decimal;
 
@@ -174,6 +174,17 @@ impl TableRenderer {
         print_row("Total Trades:", summary.total_trades.to_string());
         print_row("Overall Win Rate:", format!("{:.1}%", summary.overall_win_rate));
 
+        let swing_trades: u32 = summary.daily_summaries.iter().map(|d| d.swing_trades).sum();
+        if swing_trades > 0 {
+            let swing_pnl: Decimal = summary.daily_summaries.iter().map(|d| d.swing_pnl).sum();
+            print_row("Intraday:", format!("{} trades, {}",
+                summary.total_trades - swing_trades,
+                Self::format_currency_plain(summary.total_pnl - swing_pnl)));
+            print_row("Swing:", format!("{} trades, {}",
+                swing_trades,
+                Self::format_currency_plain(swing_pnl)));
+        }
+
         if let Some((date, pnl)) = &summary.best_day {
             let pnl_str = Self::format_currency_plain(*pnl);
             let pnl_display = if *pnl > Decimal::ZERO {
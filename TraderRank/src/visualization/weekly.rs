@@ -1,4 +1,4 @@
-use crate::models::TradingSummary;
+use trader_rank_core::models::TradingSummary;
 use chrono::{Datelike, Weekday};
 use colored::*;
 use rust_decimal::Decimal;
@@ -578,7 +578,7 @@ impl WeeklyRenderer {
         Self::render_monthly_consistency(&recent_months);
     }
 
-    fn render_monthly_pnl_chart(months: &[&crate::models::MonthlySummary]) {
+    fn render_monthly_pnl_chart(months: &[&trader_rank_core::models::MonthlySummary]) {
         println!("\n{}", "📊 Monthly P&L Trend".bold().cyan());
 
         if months.is_empty() {
@@ -605,10 +605,10 @@ impl WeeklyRenderer {
             let bar_width = if range != Decimal::ZERO {
                 let normalized = if pnl >= Decimal::ZERO {
                     (pnl / max_pnl.max(min_pnl.abs()) * Decimal::from(chart_width / 2))
-                        .to_i32().unwrap_or(0).abs() as usize
+                        .to_i32().unwrap_or(0).unsigned_abs() as usize
                 } else {
                     (pnl.abs() / max_pnl.max(min_pnl.abs()) * Decimal::from(chart_width / 2))
-                        .to_i32().unwrap_or(0).abs() as usize
+                        .to_i32().unwrap_or(0).unsigned_abs() as usize
                 };
                 normalized.min(chart_width / 2)
             } else {
@@ -706,7 +706,7 @@ impl WeeklyRenderer {
         }
     }
 
-    fn render_monthly_consistency(months: &[&crate::models::MonthlySummary]) {
+    fn render_monthly_consistency(months: &[&trader_rank_core::models::MonthlySummary]) {
         println!("\n{}", "📈 Monthly Consistency Analysis".bold().yellow());
         println!("{}", "─".repeat(80));
 
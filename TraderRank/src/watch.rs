@@ -0,0 +1,168 @@
+//! `watch`: poll the source directories and import trade files as they arrive.
+//!
+//! Files are fingerprinted by size and modification time on every poll. A
+//! change is imported once a poll finds it no different from the one before,
+//! so a statement still being downloaded or copied is not read half-written,
+//! including the catch-up import of files that arrived while not watching.
+
+use crate::commands::App;
+use anyhow::Result;
+use chrono::{Local, NaiveDate};
+use colored::*;
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use trader_rank_core::models::TradingSummary;
+use trader_rank_core::storage;
+
+/// Most changed days listed after an import; the rest are counted.
+const MAX_DAYS_SHOWN: usize = 5;
+
+type Fingerprints = BTreeMap<PathBuf, (u64, Option<SystemTime>)>;
+
+/// What the store held before an import, to report what the import added.
+struct Before {
+    fills: usize,
+    days: BTreeMap<NaiveDate, (u32, Decimal)>,
+}
+
+/// Import new and changed files every `interval` until interrupted. Import
+/// errors are printed and the file is tried again when it next changes; errors
+/// reading the store to report an import are printed and watching goes on.
+pub fn watch(app: &App, interval: Duration) -> Result<()> {
+    app.require_source_dir()?;
+    let mut dirs = vec![app.source_dir.display().to_string()];
+    if let Some(dir) = &app.imports_dir {
+        dirs.push(dir.display().to_string());
+    }
+    println!("{}", format!("👀 Watching {} every {}s (Ctrl-C to stop)", dirs.join(" and "), interval.as_secs()).cyan());
+
+    // Nothing is imported until a poll sees the files settled, the first time too
+    let mut imported: Option<Fingerprints> = None;
+    let mut last_seen = fingerprints(app)?;
+    loop {
+        thread::sleep(interval);
+        let seen = match fingerprints(app) {
+            Ok(seen) => seen,
+            Err(e) => {
+                println!("{}", format!("⚠️  Could not list the source files: {}", e).red());
+                continue;
+            }
+        };
+        // Still being written: wait for a poll that sees the same files
+        if seen != last_seen {
+            last_seen = seen;
+            continue;
+        }
+        let Some(previous) = &imported else {
+            // Catch up on what arrived while not watching
+            if let Err(e) = app.import(None) {
+                println!("{}", format!("❌ Import failed: {:#}", e).red());
+            }
+            imported = Some(seen);
+            continue;
+        };
+        if seen == *previous {
+            continue;
+        }
+
+        let changed: Vec<String> = seen.iter()
+            .filter(|(path, print)| previous.get(*path) != Some(print))
+            .map(|(path, _)| storage::file_name(path))
+            .collect();
+        if !changed.is_empty() {
+            println!("\n{}", format!("🔔 {}  {}", Local::now().format("%H:%M:%S"), changed.join(", ")).bold().cyan());
+        }
+
+        // The import stands even when what it added cannot be shown
+        let before = Before::load(app);
+        match app.import(None) {
+            Ok(()) => {
+                if let Err(e) = before.and_then(|before| print_delta(app, &before)) {
+                    println!("{}", format!("⚠️  Could not summarize the import: {:#}", e).red());
+                }
+            }
+            Err(e) => println!("{}", format!("❌ Import failed: {:#}", e).red()),
+        }
+        imported = Some(seen);
+    }
+}
+
+/// Size and modification time of every trade file in the watched directories.
+fn fingerprints(app: &App) -> Result<Fingerprints> {
+    let mut prints = Fingerprints::new();
+    for path in app.source_paths()? {
+        // A file removed since it was listed is left out
+        if let Ok(metadata) = std::fs::metadata(&path) {
+            prints.insert(path, (metadata.len(), metadata.modified().ok()));
+        }
+    }
+    Ok(prints)
+}
+
+impl Before {
+    fn load(app: &App) -> Result<Self> {
+        let fills = app.store.load_trades()?.map_or(0, |ledger| ledger.len());
+        let days = match app.store.load_processed_data()? {
+            Some(data) => day_totals(&data.summary),
+            None => BTreeMap::new(),
+        };
+        Ok(Before { fills, days })
+    }
+}
+
+fn day_totals(summary: &TradingSummary) -> BTreeMap<NaiveDate, (u32, Decimal)> {
+    summary.daily_summaries.iter()
+        .map(|d| (d.date.date_naive(), (d.total_trades, d.realized_pnl)))
+        .collect()
+}
+
+/// Print the fills the import added, the days whose round trips changed and
+/// the current winning or losing streak.
+fn print_delta(app: &App, before: &Before) -> Result<()> {
+    let Some(data) = app.store.load_processed_data()? else {
+        return Ok(());
+    };
+    let fills = app.store.load_trades()?.map_or(0, |ledger| ledger.len());
+    let days = day_totals(&data.summary);
+    let changed: Vec<_> = days.iter()
+        .filter(|(date, totals)| before.days.get(*date) != Some(*totals))
+        .collect();
+    if fills == before.fills && changed.is_empty() {
+        println!("{}", "   No new trades".dimmed());
+        return Ok(());
+    }
+
+    let round_trips = days.values().map(|(trades, _)| trades).sum::<u32>() as i64
+        - before.days.values().map(|(trades, _)| trades).sum::<u32>() as i64;
+    println!("{}", format!("   +{} fill(s), {:+} round trip(s)", fills.saturating_sub(before.fills), round_trips).white());
+
+    let skipped = changed.len().saturating_sub(MAX_DAYS_SHOWN);
+    if skipped > 0 {
+        println!("{}", format!("   … {} earlier day(s) changed", skipped).dimmed());
+    }
+    for (date, (trades, pnl)) in changed.into_iter().skip(skipped) {
+        let amount = format!("${:.2}", pnl);
+        let was = match before.days.get(date) {
+            Some((trades, pnl)) => format!(" (was {} trades, ${:.2})", trades, pnl),
+            None => String::new(),
+        };
+        println!("   📅 {}  {} trades  {}{}",
+            date,
+            trades,
+            if *pnl < Decimal::ZERO { amount.red() } else { amount.green() },
+            was.dimmed(),
+        );
+    }
+
+    let streaks = data.summary.day_streaks();
+    let current = match streaks.current {
+        0 => "no streak".white(),
+        n if n > 0 => format!("{} winning day(s)", n).green(),
+        n => format!("{} losing day(s)", -n).red(),
+    };
+    println!("   🔥 Streak: {}  {}", current, format!("(longest {} won, {} lost)", streaks.longest_win, streaks.longest_loss).dimmed());
+    Ok(())
+}
@@ -0,0 +1,409 @@
+use crate::models::{Trade, MatchedTrade, DailySummary, WeeklySummary, MonthlySummary, TradingSummary, TimeSlotPerformance};
+use crate::trade_matcher::{self, MatchOptions, OpenPosition};
+use crate::exchange_time::{self, DateRange, Tz};
+use chrono::{DateTime, NaiveDate, Utc, Datelike, Weekday};
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+pub struct TradingAnalytics;
+
+/// (most profitable hour, least profitable hour), each as (hour, P&L)
+type HourExtremes = (Option<(u32, Decimal)>, Option<(u32, Decimal)>);
+
+impl TradingAnalytics {
+    /// Summarize day trades by day, week and month. Days and hours are those of
+    /// `exchange_tz`, e.g. `DEFAULT_EXCHANGE_TZ` (America/New_York).
+    pub fn analyze_trades(trades: &[Trade], exchange_tz: Tz) -> TradingSummary {
+        Self::analyze_trades_with(trades, MatchOptions::intraday(exchange_tz))
+    }
+
+    /// Summarize trades by day, week and month, matching round trips per `options`.
+    /// A round trip counts towards the day it was closed; volume towards the day of each fill.
+    pub fn analyze_trades_with(trades: &[Trade], options: MatchOptions) -> TradingSummary {
+        Self::analyze_trades_in(trades, options, DateRange::default())
+    }
+
+    /// `analyze_trades_with`, keeping only the trading days in `range`. Positions
+    /// are matched over all of `trades`, so a round trip closed in the range
+    /// keeps its entry from before it.
+    pub fn analyze_trades_in(trades: &[Trade], options: MatchOptions, range: DateRange) -> TradingSummary {
+        Self::analyze_positions_in(trades, options, range).0
+    }
+
+    /// `analyze_trades_in`, also returning the positions left open: at the end of
+    /// a trading day in intraday mode, or at the last fill when carrying positions.
+    /// Nothing is printed; callers decide whether open positions need a warning.
+    pub fn analyze_positions_in(trades: &[Trade], options: MatchOptions, range: DateRange) -> (TradingSummary, Vec<OpenPosition>) {
+        let (matched, open) = trade_matcher::match_positions(trades, options);
+        (Self::summarize_matched_in(trades, matched, options.exchange_tz, range), open)
+    }
+
+    /// Summarize round trips already matched from `trades`, keeping only the
+    /// trading days in `range`. Nothing is matched again, so one matching can be
+    /// summarized in parts, e.g. per symbol.
+    pub fn summarize_matched_in(trades: &[Trade], matched: Vec<MatchedTrade>, exchange_tz: Tz, range: DateRange) -> TradingSummary {
+        // Group fills and round trips by exchange-local trading day
+        let mut daily_trades: HashMap<NaiveDate, Vec<Trade>> = HashMap::new();
+        for trade in trades {
+            daily_trades.entry(trade.trading_date(exchange_tz)).or_default().push(trade.clone());
+        }
+        let mut daily_matched: HashMap<NaiveDate, Vec<MatchedTrade>> = HashMap::new();
+        for mt in matched {
+            daily_matched.entry(mt.exit_date(exchange_tz)).or_default().push(mt);
+        }
+
+        let mut daily_summaries: Vec<DailySummary> = daily_trades.into_iter()
+            .map(|(date, trades)| {
+                let matched = daily_matched.remove(&date).unwrap_or_default();
+                Self::calculate_daily_summary(date, trades, &matched, exchange_tz)
+            })
+            .filter(|s| s.total_trades > 0) // Drop days with no completed round trips
+            .filter(|s| range.contains(s.date.date_naive()))
+            .collect();
+
+        daily_summaries.sort_by_key(|s| s.date);
+
+        let total_pnl = daily_summaries.iter().map(|s| s.realized_pnl).sum();
+        let total_volume = daily_summaries.iter().map(|s| s.total_volume).sum();
+        let total_trades = daily_summaries.iter().map(|s| s.total_trades).sum();
+
+        let total_wins: u32 = daily_summaries.iter().map(|s| s.winning_trades).sum();
+        let overall_win_rate = if total_trades > 0 {
+            (total_wins as f64) / (total_trades as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        let best_day = daily_summaries
+            .iter()
+            .max_by_key(|s| s.realized_pnl)
+            .map(|s| (s.date, s.realized_pnl));
+
+        let worst_day = daily_summaries
+            .iter()
+            .min_by_key(|s| s.realized_pnl)
+            .map(|s| (s.date, s.realized_pnl));
+
+        let (most_profitable_hour, least_profitable_hour) = Self::analyze_hourly_performance(&daily_summaries);
+
+        // Calculate weekly summaries
+        let weekly_summaries = Self::calculate_weekly_summaries(&daily_summaries);
+
+        // Find best and worst weeks
+        let best_week = weekly_summaries
+            .iter()
+            .max_by_key(|w| w.realized_pnl)
+            .map(|w| ((w.year, w.week_number), w.realized_pnl));
+
+        let worst_week = weekly_summaries
+            .iter()
+            .min_by_key(|w| w.realized_pnl)
+            .map(|w| ((w.year, w.week_number), w.realized_pnl));
+
+        // Calculate monthly summaries
+        let monthly_summaries = Self::calculate_monthly_summaries(&daily_summaries);
+
+        // Find best and worst months
+        let best_month = monthly_summaries
+            .iter()
+            .max_by_key(|m| m.realized_pnl)
+            .map(|m| ((m.year, m.month), m.realized_pnl));
+
+        let worst_month = monthly_summaries
+            .iter()
+            .min_by_key(|m| m.realized_pnl)
+            .map(|m| ((m.year, m.month), m.realized_pnl));
+
+        TradingSummary {
+            start_date: daily_summaries.first().map(|s| s.date).unwrap_or_else(Utc::now),
+            end_date: daily_summaries.last().map(|s| s.date).unwrap_or_else(Utc::now),
+            daily_summaries,
+            weekly_summaries,
+            monthly_summaries,
+            total_pnl,
+            total_volume,
+            total_trades,
+            overall_win_rate,
+            best_day,
+            worst_day,
+            best_week,
+            worst_week,
+            best_month,
+            worst_month,
+            most_profitable_hour,
+            least_profitable_hour,
+            accounts: Self::accounts(trades),
+        }
+    }
+
+    /// Summarize each account's trades separately, keyed by account ID.
+    /// Trades without an account are only part of `analyze_trades` over all trades.
+    pub fn analyze_by_account(trades: &[Trade], exchange_tz: Tz) -> BTreeMap<String, TradingSummary> {
+        Self::analyze_by_account_with(trades, MatchOptions::intraday(exchange_tz))
+    }
+
+    /// `analyze_by_account`, matching round trips per `options`.
+    pub fn analyze_by_account_with(trades: &[Trade], options: MatchOptions) -> BTreeMap<String, TradingSummary> {
+        Self::accounts(trades)
+            .into_iter()
+            .map(|account| {
+                let account_trades: Vec<Trade> = trades.iter()
+                    .filter(|t| t.in_account(&account))
+                    .cloned()
+                    .collect();
+                let summary = Self::analyze_trades_with(&account_trades, options);
+                (account, summary)
+            })
+            .collect()
+    }
+
+    /// Distinct account IDs named by the trades, sorted.
+    pub fn accounts(trades: &[Trade]) -> Vec<String> {
+        let accounts: BTreeSet<&str> = trades.iter()
+            .filter_map(|t| t.account.as_deref())
+            .collect();
+        accounts.into_iter().map(str::to_string).collect()
+    }
+
+    /// One day's summary from its fills and the round trips closed that day.
+    fn calculate_daily_summary(date: NaiveDate, mut trades: Vec<Trade>, matched: &[MatchedTrade], exchange_tz: Tz) -> DailySummary {
+        debug_assert!(!trades.is_empty(), "calculate_daily_summary called with empty trades");
+        trades.sort_by_key(|t| t.time);
+
+        let mut summary = DailySummary::new(exchange_time::date_to_utc(date));
+
+        // Total volume counts every fill, matched or not, at its contract multiplier
+        summary.total_volume = trades.iter().map(|t| t.notional()).sum();
+
+        // Classify realized trades as wins/losses based on GROSS P&L (before commission).
+        // Note: hourly performance classifies on NET P&L (after commission). This is intentional —
+        // daily win/loss reflects trade direction quality, hourly reflects actual dollar outcome.
+        let mut winning_pnls = Vec::new();
+        let mut losing_pnls = Vec::new();
+        let mut symbols_set = HashSet::new();
+
+        for mt in matched {
+            let pnl = mt.gross_pnl;
+            if pnl > Decimal::ZERO {
+                summary.winning_trades += 1;
+                winning_pnls.push(pnl);
+                if pnl > summary.largest_win {
+                    summary.largest_win = pnl;
+                }
+            } else if pnl < Decimal::ZERO {
+                summary.losing_trades += 1;
+                losing_pnls.push(pnl);
+                if pnl < summary.largest_loss {
+                    summary.largest_loss = pnl;
+                }
+            }
+            summary.gross_pnl += pnl;
+            summary.total_commission += mt.commission;
+            symbols_set.insert(mt.symbol.clone());
+            // Swing trades are counted like total_trades: breakeven round trips are left out
+            if mt.is_swing() && pnl != Decimal::ZERO {
+                summary.swing_trades += 1;
+                summary.swing_pnl += mt.net_pnl;
+            }
+        }
+
+        // Commission is only counted for matched round trips
+        summary.realized_pnl = summary.gross_pnl - summary.total_commission;
+
+        summary.total_trades = summary.winning_trades + summary.losing_trades;
+        summary.symbols_traded = symbols_set.into_iter().collect();
+        summary.symbols_traded.sort();
+
+        if !winning_pnls.is_empty() {
+            let sum: Decimal = winning_pnls.iter().sum();
+            summary.avg_win = sum / Decimal::from(winning_pnls.len());
+        }
+
+        if !losing_pnls.is_empty() {
+            let sum: Decimal = losing_pnls.iter().sum();
+            summary.avg_loss = sum / Decimal::from(losing_pnls.len());
+        }
+
+        summary.win_rate = if summary.total_trades > 0 {
+            (summary.winning_trades as f64) / (summary.total_trades as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        summary.time_slot_performance = Self::calculate_hourly_performance(&trades, matched, exchange_tz);
+
+        summary
+    }
+
+    /// Per-hour breakdown for one day. Fill counts use the hour of every execution;
+    /// round-trip P&L is attributed to the hour of the closing fill. Hours are exchange-local.
+    fn calculate_hourly_performance(trades: &[Trade], matched: &[MatchedTrade], exchange_tz: Tz) -> Vec<TimeSlotPerformance> {
+        // hourly_data: hour -> (trade_count, pnl, wins, losses)
+        let mut hourly_data: HashMap<u32, (u32, Decimal, u32, u32)> = HashMap::new();
+
+        // Count all trades per hour for the trade_count field
+        for trade in trades {
+            let hour = trade.hour_of_day(exchange_tz);
+            hourly_data.entry(hour).or_insert((0, Decimal::ZERO, 0, 0)).0 += 1;
+        }
+
+        for mt in matched {
+            let hour = mt.exit_hour(exchange_tz);
+            let entry = hourly_data.entry(hour).or_insert((0, Decimal::ZERO, 0, 0));
+            entry.1 += mt.net_pnl;
+            if mt.net_pnl > Decimal::ZERO {
+                entry.2 += 1;
+            } else if mt.net_pnl < Decimal::ZERO {
+                entry.3 += 1;
+            }
+        }
+
+        let mut slots: Vec<TimeSlotPerformance> = hourly_data
+            .into_iter()
+            .map(|(hour, (trades, pnl, wins, losses))| {
+                let win_rate = if wins + losses > 0 {
+                    (wins as f64) / ((wins + losses) as f64) * 100.0
+                } else {
+                    0.0
+                };
+
+                TimeSlotPerformance {
+                    hour,
+                    trades,
+                    pnl,
+                    win_rate,
+                }
+            })
+            .collect();
+
+        slots.sort_by_key(|s| s.hour);
+        slots
+    }
+
+    fn analyze_hourly_performance(summaries: &[DailySummary]) -> HourExtremes {
+        let mut hourly_totals: HashMap<u32, Decimal> = HashMap::new();
+
+        for summary in summaries {
+            for slot in &summary.time_slot_performance {
+                *hourly_totals.entry(slot.hour).or_insert(Decimal::ZERO) += slot.pnl;
+            }
+        }
+
+        let most_profitable = hourly_totals
+            .iter()
+            .max_by_key(|(_, pnl)| **pnl)
+            .map(|(hour, pnl)| (*hour, *pnl));
+
+        let least_profitable = hourly_totals
+            .iter()
+            .min_by_key(|(_, pnl)| **pnl)
+            .map(|(hour, pnl)| (*hour, *pnl));
+
+        (most_profitable, least_profitable)
+    }
+
+    fn calculate_weekly_summaries(daily_summaries: &[DailySummary]) -> Vec<WeeklySummary> {
+        if daily_summaries.is_empty() {
+            return Vec::new();
+        }
+
+        let mut weekly_summaries = Vec::new();
+        let mut week_groups: HashMap<(i32, u32), Vec<DailySummary>> = HashMap::new();
+
+        // Group daily summaries by ISO week
+        for daily in daily_summaries {
+            let iso_week = daily.date.iso_week();
+            let week_key = (iso_week.year(), iso_week.week());
+            week_groups.entry(week_key)
+                .or_default()
+                .push(daily.clone());
+        }
+
+        // Create weekly summaries
+        for ((year, week_num), mut daily_in_week) in week_groups {
+            // Sort by date
+            daily_in_week.sort_by_key(|d| d.date);
+
+            let start_date = daily_in_week.first().unwrap().date;
+            let _end_date = daily_in_week.last().unwrap().date;
+
+            // Calculate week start and end dates (Monday to Sunday)
+            let naive_start = start_date.date_naive();
+            let days_from_monday = match naive_start.weekday() {
+                Weekday::Mon => 0,
+                Weekday::Tue => 1,
+                Weekday::Wed => 2,
+                Weekday::Thu => 3,
+                Weekday::Fri => 4,
+                Weekday::Sat => 5,
+                Weekday::Sun => 6,
+            };
+
+            let week_start = naive_start - chrono::Duration::days(days_from_monday as i64);
+            let week_end = week_start + chrono::Duration::days(6);
+
+            let week_start_utc = DateTime::<Utc>::from_naive_utc_and_offset(
+                week_start.and_hms_opt(0, 0, 0).unwrap(),
+                Utc
+            );
+            let week_end_utc = DateTime::<Utc>::from_naive_utc_and_offset(
+                week_end.and_hms_opt(23, 59, 59).unwrap(),
+                Utc
+            );
+
+            let mut weekly = WeeklySummary::new(week_num, year, week_start_utc, week_end_utc);
+            weekly.update_from_daily_summaries(daily_in_week);
+            weekly_summaries.push(weekly);
+        }
+
+        // Sort by week start date
+        weekly_summaries.sort_by_key(|w| w.start_date);
+        weekly_summaries
+    }
+
+    /// Public: recompute weekly summaries from a (possibly filtered) set of daily summaries
+    pub fn calculate_weekly_from_daily(daily_summaries: &[DailySummary]) -> Vec<WeeklySummary> {
+        Self::calculate_weekly_summaries(daily_summaries)
+    }
+
+    /// Public: recompute monthly summaries from a (possibly filtered) set of daily summaries
+    pub fn calculate_monthly_from_daily(daily_summaries: &[DailySummary]) -> Vec<MonthlySummary> {
+        Self::calculate_monthly_summaries(daily_summaries)
+    }
+
+    /// Regenerate monthly summaries from daily summaries (when upgrading summaries cached before months were kept)
+    pub fn regenerate_monthly_summaries(daily_summaries: &[DailySummary]) -> Vec<MonthlySummary> {
+        Self::calculate_monthly_summaries(daily_summaries)
+    }
+
+    fn calculate_monthly_summaries(daily_summaries: &[DailySummary]) -> Vec<MonthlySummary> {
+        if daily_summaries.is_empty() {
+            return Vec::new();
+        }
+
+        let mut month_groups: HashMap<(i32, u32), Vec<&DailySummary>> = HashMap::new();
+
+        // Group daily summaries by year and month
+        for daily in daily_summaries {
+            let year = daily.date.year();
+            let month = daily.date.month();
+            month_groups.entry((year, month))
+                .or_default()
+                .push(daily);
+        }
+
+        let mut monthly_summaries: Vec<MonthlySummary> = month_groups
+            .into_iter()
+            .map(|((year, month), dailies)| {
+                let mut monthly = MonthlySummary::new(year, month);
+                let owned_dailies: Vec<DailySummary> = dailies.into_iter().cloned().collect();
+                monthly.update_from_daily_summaries(&owned_dailies);
+                monthly
+            })
+            .collect();
+
+        // Sort by year and month
+        monthly_summaries.sort_by_key(|m| (m.year, m.month));
+        monthly_summaries
+    }
+}
@@ -1,10 +1,28 @@
-use crate::models::Trade;
+use crate::models::{MatchedTrade, Trade};
+use crate::trade_matcher::{match_trades_with, MatchOptions};
+use crate::exchange_time::{DateRange, Tz};
 use rust_decimal::Decimal;
 
 pub struct TimePatternAnalyzer;
 
 impl TimePatternAnalyzer {
-    pub fn identify_best_trading_periods(trades: &[Trade]) -> Vec<TradingPeriod> {
+    /// Rank intraday sessions by realized round-trip P&L (long and short),
+    /// attributing each round trip to the exchange-local hour of its closing fill.
+    pub fn identify_best_trading_periods(trades: &[Trade], exchange_tz: Tz) -> Vec<TradingPeriod> {
+        Self::identify_best_trading_periods_with(trades, MatchOptions::intraday(exchange_tz))
+    }
+
+    /// `identify_best_trading_periods`, matching round trips per `options`.
+    pub fn identify_best_trading_periods_with(trades: &[Trade], options: MatchOptions) -> Vec<TradingPeriod> {
+        Self::identify_best_trading_periods_in(trades, options, DateRange::default())
+    }
+
+    /// `identify_best_trading_periods_with`, counting only round trips closed on a day in `range`.
+    pub fn identify_best_trading_periods_in(trades: &[Trade], options: MatchOptions, range: DateRange) -> Vec<TradingPeriod> {
+        let exchange_tz = options.exchange_tz;
+        let mut matched = match_trades_with(trades, options);
+        matched.retain(|mt| range.contains(mt.exit_date(exchange_tz)));
+
         let mut periods = vec![
             TradingPeriod::new("Pre-Market", 4, 9),
             TradingPeriod::new("Market Open", 9, 10),
@@ -16,10 +34,10 @@ impl TimePatternAnalyzer {
         ];
 
         for period in &mut periods {
-            period.calculate_metrics(trades);
+            period.calculate_metrics(&matched, exchange_tz);
         }
 
-        periods.sort_by(|a, b| b.total_pnl.cmp(&a.total_pnl));
+        periods.sort_by_key(|p| std::cmp::Reverse(p.total_pnl));
         periods
     }
 }
@@ -48,11 +66,11 @@ impl TradingPeriod {
         }
     }
 
-    fn calculate_metrics(&mut self, trades: &[Trade]) {
-        let period_trades: Vec<&Trade> = trades
+    fn calculate_metrics(&mut self, matched: &[MatchedTrade], exchange_tz: Tz) {
+        let period_trades: Vec<&MatchedTrade> = matched
             .iter()
             .filter(|t| {
-                let hour = t.hour_of_day();
+                let hour = t.exit_hour(exchange_tz);
                 hour >= self.start_hour && hour < self.end_hour
             })
             .collect();
@@ -63,7 +81,7 @@ impl TradingPeriod {
         let mut losses = 0;
 
         for trade in &period_trades {
-            let pnl = trade.net_pnl();
+            let pnl = trade.net_pnl;
             self.total_pnl += pnl;
 
             if pnl > Decimal::ZERO {
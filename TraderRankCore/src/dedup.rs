@@ -0,0 +1,179 @@
+//! Removing fills that were imported more than once, e.g. from overlapping
+//! exports or the same day in a CSV and a Flex statement.
+//!
+//! Fills with an execution ID are the same fill exactly when their IDs (and
+//! accounts) match. Fills without one are compared field by field, but only
+//! against fills from *other* sources: a single file never lists a fill twice,
+//! so identical partial fills within one file are all kept.
+
+use crate::models::{Side, Trade};
+use chrono::Duration;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Fills from different sources whose times differ by at most this much can be
+/// the same fill (sources round or stamp execution times differently).
+pub const FUZZY_TIME_TOLERANCE_SECS: i64 = 2;
+
+/// Why a fill was dropped as a duplicate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateReason {
+    /// Same execution ID as a fill already imported
+    ExecutionId,
+    /// No execution ID to compare; symbol, side, quantity and price match a
+    /// fill from another source within `FUZZY_TIME_TOLERANCE_SECS`
+    MatchingFields,
+}
+
+impl fmt::Display for DuplicateReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DuplicateReason::ExecutionId => f.write_str("same execution ID"),
+            DuplicateReason::MatchingFields => write!(f, "same fill within {}s", FUZZY_TIME_TOLERANCE_SECS),
+        }
+    }
+}
+
+/// A dropped fill and the fill it duplicates. Sources are indexes into the
+/// list passed to `dedupe_sources`.
+#[derive(Debug, Clone)]
+pub struct DuplicateFill {
+    pub kept: Trade,
+    pub kept_source: usize,
+    pub dropped: Trade,
+    pub dropped_source: usize,
+    pub reason: DuplicateReason,
+}
+
+impl fmt::Display for DuplicateFill {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} @ {} at {} ({})",
+            self.dropped.symbol,
+            self.dropped.side,
+            self.dropped.quantity,
+            self.dropped.fill_price,
+            self.dropped.time.format("%Y-%m-%d %H:%M:%S UTC"),
+            self.reason,
+        )
+    }
+}
+
+/// The unique fills, in time order, and every duplicate that was dropped.
+#[derive(Debug, Clone, Default)]
+pub struct DedupOutcome {
+    pub trades: Vec<Trade>,
+    pub duplicates: Vec<DuplicateFill>,
+}
+
+impl DedupOutcome {
+    /// Duplicates found without an execution ID to confirm them.
+    pub fn fuzzy_duplicates(&self) -> impl Iterator<Item = &DuplicateFill> {
+        self.duplicates.iter().filter(|d| d.reason == DuplicateReason::MatchingFields)
+    }
+}
+
+/// A kept fill and the sources whose copy of it has already been dropped,
+/// so each kept fill absorbs at most one fill from any other source.
+struct Kept {
+    trade: Trade,
+    source: usize,
+    absorbed_from: Vec<usize>,
+}
+
+/// Merge the fills of several sources (one list per file), dropping duplicates.
+pub fn dedupe_sources(sources: Vec<Vec<Trade>>) -> DedupOutcome {
+    let mut kept: Vec<Kept> = Vec::new();
+    let mut by_execution_id: HashMap<(Option<String>, String), usize> = HashMap::new();
+    let mut by_fields: HashMap<FieldsKey, Vec<usize>> = HashMap::new();
+    let mut duplicates = Vec::new();
+
+    for (source, trades) in sources.into_iter().enumerate() {
+        for trade in trades {
+            let duplicate = match &trade.execution_id {
+                Some(id) => by_execution_id
+                    .get(&(trade.account.clone(), id.clone()))
+                    .map(|&idx| (idx, DuplicateReason::ExecutionId))
+                    .or_else(|| find_fuzzy(&kept, &by_fields, &trade, source).map(|idx| (idx, DuplicateReason::MatchingFields))),
+                None => find_fuzzy(&kept, &by_fields, &trade, source).map(|idx| (idx, DuplicateReason::MatchingFields)),
+            };
+
+            if let Some((idx, reason)) = duplicate {
+                let original = &mut kept[idx];
+                // Keep whichever copy carries an execution ID
+                let (dropped, dropped_source) = match &trade.execution_id {
+                    Some(id) if original.trade.execution_id.is_none() => {
+                        by_execution_id.insert((trade.account.clone(), id.clone()), idx);
+                        (std::mem::replace(&mut original.trade, trade), std::mem::replace(&mut original.source, source))
+                    }
+                    _ => (trade, source),
+                };
+                original.absorbed_from.push(dropped_source);
+                duplicates.push(DuplicateFill {
+                    kept: original.trade.clone(),
+                    kept_source: original.source,
+                    dropped,
+                    dropped_source,
+                    reason,
+                });
+                continue;
+            }
+
+            let idx = kept.len();
+            if let Some(id) = &trade.execution_id {
+                by_execution_id.insert((trade.account.clone(), id.clone()), idx);
+            }
+            by_fields.entry(fields_key(&trade)).or_default().push(idx);
+            kept.push(Kept { trade, source, absorbed_from: Vec::new() });
+        }
+    }
+
+    let mut trades: Vec<Trade> = kept.into_iter().map(|k| k.trade).collect();
+    trades.sort_by_key(|t| t.time);
+    DedupOutcome { trades, duplicates }
+}
+
+/// Symbol, side, quantity and price; normalized so "100" and "100.00" agree.
+type FieldsKey = (String, Side, Decimal, Decimal);
+
+fn fields_key(trade: &Trade) -> FieldsKey {
+    (
+        trade.symbol.clone(),
+        trade.side.clone(),
+        trade.quantity.normalize(),
+        trade.fill_price.normalize(),
+    )
+}
+
+/// A kept fill from another source that `trade` duplicates: same symbol, side,
+/// quantity and price, close in time, accounts not contradicting each other,
+/// and execution IDs not both present (differing IDs are different fills).
+fn find_fuzzy(
+    kept: &[Kept],
+    by_fields: &HashMap<FieldsKey, Vec<usize>>,
+    trade: &Trade,
+    source: usize,
+) -> Option<usize> {
+    let tolerance = Duration::seconds(FUZZY_TIME_TOLERANCE_SECS);
+    by_fields.get(&fields_key(trade))?
+        .iter()
+        .copied()
+        .filter(|&idx| {
+            let candidate = &kept[idx];
+            candidate.source != source
+                && !candidate.absorbed_from.contains(&source)
+                && !(candidate.trade.execution_id.is_some() && trade.execution_id.is_some())
+                && accounts_compatible(&candidate.trade, trade)
+                && (candidate.trade.time - trade.time).abs() <= tolerance
+        })
+        .min_by_key(|&idx| (kept[idx].trade.time - trade.time).abs())
+}
+
+fn accounts_compatible(a: &Trade, b: &Trade) -> bool {
+    match (&a.account, &b.account) {
+        (Some(x), Some(y)) => x == y,
+        _ => true,
+    }
+}
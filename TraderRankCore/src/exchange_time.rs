@@ -0,0 +1,78 @@
+//! Exchange-local calendar. Trade times are stored as UTC instants; trading
+//! days, hours and sessions are read in the exchange's timezone, so a fill at
+//! 09:35 New York time is in the "Market Open" hour in both EST and EDT.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc};
+pub use chrono_tz::Tz;
+
+/// Exchange timezone used when none is configured (NYSE/Nasdaq).
+pub const DEFAULT_EXCHANGE_TZ: Tz = chrono_tz::America::New_York;
+
+/// The instant a wall-clock time in `tz` refers to.
+/// Ambiguous times in the DST fall-back hour take the earlier instant;
+/// times skipped by the spring-forward gap do not exist and yield `None`.
+pub fn local_to_utc(naive: NaiveDateTime, tz: Tz) -> Option<DateTime<Utc>> {
+    tz.from_local_datetime(&naive)
+        .earliest()
+        .map(|local| local.with_timezone(&Utc))
+}
+
+/// Calendar date of an instant in the exchange timezone.
+pub fn trading_date(time: DateTime<Utc>, tz: Tz) -> NaiveDate {
+    time.with_timezone(&tz).date_naive()
+}
+
+/// Hour (0-23) of an instant in the exchange timezone.
+pub fn local_hour(time: DateTime<Utc>, tz: Tz) -> u32 {
+    time.with_timezone(&tz).hour()
+}
+
+/// A trading date as stored in `DailySummary::date`: midnight UTC of the
+/// exchange-local date, so `date_naive()` gives the trading day back.
+pub fn date_to_utc(date: NaiveDate) -> DateTime<Utc> {
+    DateTime::<Utc>::from_naive_utc_and_offset(date.and_hms_opt(0, 0, 0).unwrap(), Utc)
+}
+
+/// Trading days from `from` to `to`, both included; `None` leaves that end open.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DateRange {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl DateRange {
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.from.is_none_or(|from| date >= from) && self.to.is_none_or(|to| date <= to)
+    }
+
+    /// True when neither end is set.
+    pub fn is_all(&self) -> bool {
+        self.from.is_none() && self.to.is_none()
+    }
+
+    /// The first instant of the range: midnight exchange time on `from`.
+    pub fn start(&self, tz: Tz) -> Option<DateTime<Utc>> {
+        self.from.map(|date| start_of_day(date, tz))
+    }
+
+    /// The first instant after the range: midnight exchange time after `to`.
+    pub fn end(&self, tz: Tz) -> Option<DateTime<Utc>> {
+        self.to.and_then(|date| date.succ_opt()).map(|date| start_of_day(date, tz))
+    }
+}
+
+/// The first instant of an exchange-local day; days with no midnight (a DST
+/// gap at 00:00) start an hour later.
+fn start_of_day(date: NaiveDate, tz: Tz) -> DateTime<Utc> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap();
+    local_to_utc(midnight, tz)
+        .or_else(|| local_to_utc(midnight + chrono::Duration::hours(1), tz))
+        .unwrap_or_else(|| date_to_utc(date))
+}
+
+/// Parse an IANA timezone name such as "America/New_York".
+pub fn parse_tz(name: &str) -> anyhow::Result<Tz> {
+    name.trim()
+        .parse::<Tz>()
+        .map_err(|_| anyhow::anyhow!("Unknown timezone '{}' (expected an IANA name such as America/New_York)", name))
+}
@@ -0,0 +1,89 @@
+//! CSV exports of imported fills and matched round trips.
+
+use crate::exchange_time::Tz;
+use crate::models::{HoldingPeriod, Instrument, MatchedTrade, Trade};
+use anyhow::Result;
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::io::Write;
+
+/// Write fills in the generic Trades format, so the file can be imported again.
+///
+/// Times are RFC 3339 with the exchange's UTC offset, which the Trades parser
+/// reads as the same instant whatever its source timezone. Instrument columns
+/// are left blank for USD stocks.
+pub fn write_fills_csv<W: Write>(trades: &[Trade], exchange_tz: Tz, writer: W) -> Result<()> {
+    let mut csv = csv::Writer::from_writer(writer);
+    csv.write_record([
+        "Symbol", "Side", "Quantity", "Fill Price", "Time", "Net Amount", "Commission",
+        "Asset Class", "Multiplier", "Currency", "Account", "Execution ID", "Closes Lot",
+    ])?;
+
+    for trade in trades {
+        let (asset_class, multiplier, currency) = instrument_columns(&trade.instrument);
+        csv.write_record([
+            trade.symbol.clone(),
+            trade.side.to_string(),
+            trade.quantity.to_string(),
+            trade.fill_price.to_string(),
+            exchange_time(trade.time, exchange_tz),
+            trade.net_amount.to_string(),
+            trade.commission.to_string(),
+            asset_class,
+            multiplier,
+            currency,
+            trade.account.clone().unwrap_or_default(),
+            trade.execution_id.clone().unwrap_or_default(),
+            trade.closes_lot.clone().unwrap_or_default(),
+        ])?;
+    }
+
+    csv.flush()?;
+    Ok(())
+}
+
+/// Write round trips, one row each, with entry and exit in exchange time.
+pub fn write_round_trips_csv<W: Write>(matched: &[MatchedTrade], exchange_tz: Tz, writer: W) -> Result<()> {
+    let mut csv = csv::Writer::from_writer(writer);
+    csv.write_record([
+        "Symbol", "Side", "Quantity", "Entry Time", "Exit Time", "Entry Price", "Exit Price",
+        "Gross P&L", "Commission", "Net P&L", "Holding", "Asset Class", "Multiplier", "Currency", "Account",
+    ])?;
+
+    for mt in matched {
+        let (asset_class, multiplier, currency) = instrument_columns(&mt.instrument);
+        csv.write_record([
+            mt.symbol.clone(),
+            mt.side.clone(),
+            mt.quantity.to_string(),
+            exchange_time(mt.entry_time, exchange_tz),
+            exchange_time(mt.exit_time, exchange_tz),
+            mt.entry_price.to_string(),
+            mt.exit_price.to_string(),
+            mt.gross_pnl.round_dp(2).to_string(),
+            mt.commission.round_dp(2).to_string(),
+            mt.net_pnl.round_dp(2).to_string(),
+            match mt.holding {
+                HoldingPeriod::Intraday => "Intraday".to_string(),
+                HoldingPeriod::Swing => "Swing".to_string(),
+            },
+            asset_class,
+            multiplier,
+            currency,
+            mt.account.clone().unwrap_or_default(),
+        ])?;
+    }
+
+    csv.flush()?;
+    Ok(())
+}
+
+fn exchange_time(time: DateTime<Utc>, exchange_tz: Tz) -> String {
+    time.with_timezone(&exchange_tz).to_rfc3339_opts(SecondsFormat::AutoSi, false)
+}
+
+fn instrument_columns(instrument: &Instrument) -> (String, String, String) {
+    if *instrument == Instrument::stock() {
+        return (String::new(), String::new(), String::new());
+    }
+    (instrument.asset_class.to_string(), instrument.multiplier.to_string(), instrument.currency.clone())
+}
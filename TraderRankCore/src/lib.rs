@@ -0,0 +1,24 @@
+//! Shared core of TraderRank: trade models, file parsers, the position-tracking
+//! analytics engine, the round-trip trade matcher, the tax lot report, CSV
+//! exports, the trade stores and the colour palettes. Used by both the CLI and
+//! the desktop app so a fix in one place reaches every tool.
+
+pub mod models;
+pub mod parser;
+pub mod analytics;
+pub mod trade_matcher;
+pub mod exchange_time;
+pub mod dedup;
+pub mod tax;
+pub mod export;
+pub mod storage;
+pub mod theme;
+
+pub use models::{Trade, Side, Instrument, AssetClass, MatchedTrade, HoldingPeriod, DailySummary, WeeklySummary, MonthlySummary, TradingSummary, TimeSlotPerformance};
+pub use parser::{CsvParser, ImportProfile, ParseReport};
+pub use analytics::{TradingAnalytics, TimePatternAnalyzer};
+pub use trade_matcher::{match_trades, match_trades_with, LotMethod, MatchOptions, OpenPosition};
+pub use exchange_time::{DateRange, Tz, DEFAULT_EXCHANGE_TZ};
+pub use dedup::{dedupe_sources, DedupOutcome};
+pub use tax::TaxReport;
+pub use storage::{JsonStore, SqliteStore, Store, StoreKind};
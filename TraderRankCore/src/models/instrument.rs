@@ -0,0 +1,127 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Broad asset class of a traded instrument, named by IB's `assetCategory` codes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum AssetClass {
+    /// Stocks and ETFs
+    #[default]
+    Stk,
+    /// Equity, index and futures options
+    Opt,
+    Fut,
+    /// Spot currency
+    Cash,
+    Crypto,
+}
+
+impl AssetClass {
+    /// Parse an IB code ("STK", "OPT", "FOP", ...) or Activity Statement
+    /// category name ("Stocks", "Equity and Index Options", ...), ignoring case.
+    /// Unsupported classes such as bonds yield `None`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.trim().to_lowercase().as_str() {
+            "stk" | "stock" | "stocks" | "etf" => Some(AssetClass::Stk),
+            "opt" | "fop" | "option" | "options" | "equity and index options" | "options on futures" => Some(AssetClass::Opt),
+            "fut" | "future" | "futures" => Some(AssetClass::Fut),
+            "cash" | "forex" | "fx" => Some(AssetClass::Cash),
+            "crypto" | "cryptocurrency" => Some(AssetClass::Crypto),
+            _ => None,
+        }
+    }
+
+    /// Multiplier assumed when the source does not give one: 100 for
+    /// (US equity) options, 1 otherwise. Futures multipliers vary by contract
+    /// and should always come from the source.
+    pub fn default_multiplier(&self) -> Decimal {
+        match self {
+            AssetClass::Opt => Decimal::ONE_HUNDRED,
+            _ => Decimal::ONE,
+        }
+    }
+}
+
+impl fmt::Display for AssetClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let code = match self {
+            AssetClass::Stk => "STK",
+            AssetClass::Opt => "OPT",
+            AssetClass::Fut => "FUT",
+            AssetClass::Cash => "CASH",
+            AssetClass::Crypto => "CRYPTO",
+        };
+        f.write_str(code)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OptionRight {
+    #[serde(rename = "C")]
+    Call,
+    #[serde(rename = "P")]
+    Put,
+}
+
+impl OptionRight {
+    /// "C"/"Call" or "P"/"Put", ignoring case.
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.trim().to_lowercase().as_str() {
+            "c" | "call" => Some(OptionRight::Call),
+            "p" | "put" => Some(OptionRight::Put),
+            _ => None,
+        }
+    }
+}
+
+/// What a trade's symbol refers to: its asset class, contract multiplier,
+/// currency and, for derivatives, the contract terms.
+///
+/// Prices are per unit of the underlying, so the cash value of a fill is
+/// quantity × price × multiplier. Amounts are in `currency`; no FX conversion is done.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Instrument {
+    pub asset_class: AssetClass,
+    pub multiplier: Decimal,
+    pub currency: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub underlying: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiry: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strike: Option<Decimal>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub right: Option<OptionRight>,
+}
+
+impl Default for Instrument {
+    fn default() -> Self {
+        Self::stock()
+    }
+}
+
+impl Instrument {
+    /// A USD stock with multiplier 1, assumed for sources without instrument details.
+    pub fn stock() -> Self {
+        Self::of_class(AssetClass::Stk)
+    }
+
+    /// A USD instrument of the given class with its default multiplier.
+    pub fn of_class(asset_class: AssetClass) -> Self {
+        Self {
+            asset_class,
+            multiplier: asset_class.default_multiplier(),
+            currency: "USD".to_string(),
+            underlying: None,
+            expiry: None,
+            strike: None,
+            right: None,
+        }
+    }
+
+    pub fn is_derivative(&self) -> bool {
+        matches!(self.asset_class, AssetClass::Opt | AssetClass::Fut)
+    }
+}
@@ -0,0 +1,62 @@
+use crate::exchange_time::{self, Tz};
+use super::Instrument;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Whether a round trip was opened and closed on the same trading day.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HoldingPeriod {
+    #[default]
+    Intraday,
+    /// Held overnight: closed on a later trading day than it was opened
+    Swing,
+}
+
+impl HoldingPeriod {
+    /// Intraday when both instants fall on the same trading day in `exchange_tz`.
+    pub fn between(entry_time: DateTime<Utc>, exit_time: DateTime<Utc>, exchange_tz: Tz) -> Self {
+        if exchange_time::trading_date(entry_time, exchange_tz) == exchange_time::trading_date(exit_time, exchange_tz) {
+            HoldingPeriod::Intraday
+        } else {
+            HoldingPeriod::Swing
+        }
+    }
+}
+
+/// A completed round trip produced by `trade_matcher::match_trades`: one
+/// closing fill against one open lot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchedTrade {
+    pub symbol: String,
+    pub entry_time: DateTime<Utc>, // when the lot was opened (its first fill under average cost)
+    pub exit_time: DateTime<Utc>,
+    pub side: String,           // "Long" (bought then sold) or "Short" (sold then covered)
+    pub quantity: Decimal,
+    pub entry_price: Decimal,   // lot price; weighted average of the open fills under average cost
+    pub exit_price: Decimal,    // price of the closing fill
+    pub gross_pnl: Decimal,     // (exit - entry) * qty * multiplier
+    pub commission: Decimal,    // total commission for this round trip
+    pub net_pnl: Decimal,       // gross - commission
+    pub entry_fills: u32,       // number of opening executions in the lot
+    pub exit_fills: u32,        // number of closing executions
+    pub instrument: Instrument,
+    pub account: Option<String>,
+    pub holding: HoldingPeriod,
+}
+
+impl MatchedTrade {
+    pub fn is_swing(&self) -> bool {
+        self.holding == HoldingPeriod::Swing
+    }
+
+    /// Hour of the closing fill in the exchange timezone.
+    pub fn exit_hour(&self, exchange_tz: Tz) -> u32 {
+        exchange_time::local_hour(self.exit_time, exchange_tz)
+    }
+
+    /// Trading day of the closing fill in the exchange timezone.
+    pub fn exit_date(&self, exchange_tz: Tz) -> NaiveDate {
+        exchange_time::trading_date(self.exit_time, exchange_tz)
+    }
+}
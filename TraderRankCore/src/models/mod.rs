@@ -0,0 +1,9 @@
+pub mod trade;
+pub mod summary;
+pub mod matched_trade;
+pub mod instrument;
+
+pub use trade::{Trade, Side};
+pub use summary::{DailySummary, DayStreaks, WeeklySummary, MonthlySummary, TradingSummary, TimeSlotPerformance};
+pub use matched_trade::{HoldingPeriod, MatchedTrade};
+pub use instrument::{AssetClass, Instrument, OptionRight};
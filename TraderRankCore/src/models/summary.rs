@@ -119,6 +119,12 @@ pub struct DailySummary {
     pub largest_loss: Decimal,
     pub symbols_traded: Vec<String>,
     pub time_slot_performance: Vec<TimeSlotPerformance>,
+    /// Round trips among `total_trades` that were opened on an earlier day
+    #[serde(default)]
+    pub swing_trades: u32,
+    /// Net P&L of those swing trades, included in `realized_pnl`
+    #[serde(default)]
+    pub swing_pnl: Decimal,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -178,6 +184,9 @@ pub struct TradingSummary {
     pub worst_month: Option<((i32, u32), Decimal)>,  // (year, month)
     pub most_profitable_hour: Option<(u32, Decimal)>,
     pub least_profitable_hour: Option<(u32, Decimal)>,
+    /// Accounts whose trades are included, sorted (empty when no source named one)
+    #[serde(default)]
+    pub accounts: Vec<String>,
 }
 
 impl WeeklySummary {
@@ -318,6 +327,8 @@ impl DailySummary {
             largest_loss: Decimal::ZERO,
             symbols_traded: Vec::new(),
             time_slot_performance: Vec::new(),
+            swing_trades: 0,
+            swing_pnl: Decimal::ZERO,
         }
     }
 
@@ -331,4 +342,32 @@ impl DailySummary {
         }
         None
     }
-}
\ No newline at end of file
+}
+
+/// Runs of consecutive winning and losing trading days.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DayStreaks {
+    /// The run the last trading day belongs to: positive for winning days,
+    /// negative for losing days, zero after a flat day
+    pub current: i32,
+    pub longest_win: u32,
+    pub longest_loss: u32,
+}
+
+impl TradingSummary {
+    /// Winning and losing streaks over the daily summaries by net P&L. A flat
+    /// day ends either streak.
+    pub fn day_streaks(&self) -> DayStreaks {
+        let mut streaks = DayStreaks::default();
+        for day in &self.daily_summaries {
+            streaks.current = match day.realized_pnl {
+                pnl if pnl > Decimal::ZERO => streaks.current.max(0) + 1,
+                pnl if pnl < Decimal::ZERO => streaks.current.min(0) - 1,
+                _ => 0,
+            };
+            streaks.longest_win = streaks.longest_win.max(streaks.current.max(0) as u32);
+            streaks.longest_loss = streaks.longest_loss.max(streaks.current.min(0).unsigned_abs());
+        }
+        streaks
+    }
+}
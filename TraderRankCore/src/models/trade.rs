@@ -0,0 +1,136 @@
+use crate::exchange_time::{self, Tz};
+use super::Instrument;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::hash::{Hash, Hasher};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+impl std::fmt::Display for Side {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Side::Buy => write!(f, "Buy"),
+            Side::Sell => write!(f, "Sell"),
+        }
+    }
+}
+
+impl FromStr for Side {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "buy" | "long" => Ok(Side::Buy),
+            "sell" | "short" => Ok(Side::Sell),
+            _ => Err(anyhow::anyhow!("Invalid side: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trade {
+    pub symbol: String,
+    pub side: Side,
+    pub quantity: Decimal,
+    pub fill_price: Decimal,
+    pub time: DateTime<Utc>,
+    pub net_amount: Decimal,
+    pub commission: Decimal,
+    /// Realized P&L as reported by the broker, when the source includes it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub realized_pnl: Option<Decimal>,
+    /// Asset class, multiplier and currency; a USD stock unless the source says otherwise
+    #[serde(default)]
+    pub instrument: Instrument,
+    /// Broker account the fill belongs to, when the source names one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    /// Broker's stable ID for this execution (e.g. Flex `tradeID`), used to
+    /// recognise the same fill across imports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_id: Option<String>,
+    /// For a closing fill under specific-ID lot matching: the execution ID of
+    /// the opening fill whose lot it closes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closes_lot: Option<String>,
+    /// Name of the file the fill was imported from, recorded when it is
+    /// merged into a trade ledger
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+impl PartialEq for Trade {
+    fn eq(&self, other: &Self) -> bool {
+        self.symbol == other.symbol
+            && self.side == other.side
+            && self.quantity == other.quantity
+            && self.fill_price == other.fill_price
+            && self.time == other.time
+            && self.account == other.account
+            && self.execution_id == other.execution_id
+    }
+}
+
+impl Eq for Trade {}
+
+impl Hash for Trade {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.symbol.hash(state);
+        self.side.hash(state);
+        self.quantity.hash(state);
+        self.fill_price.hash(state);
+        self.time.hash(state);
+        self.account.hash(state);
+        self.execution_id.hash(state);
+    }
+}
+
+impl Trade {
+    pub fn gross_pnl(&self) -> Decimal {
+        match self.side {
+            Side::Buy => -self.net_amount,
+            Side::Sell => self.net_amount,
+        }
+    }
+
+    pub fn net_pnl(&self) -> Decimal {
+        self.gross_pnl() - self.commission
+    }
+
+    /// True if the fill belongs to `account`.
+    pub fn in_account(&self, account: &str) -> bool {
+        self.account.as_deref() == Some(account)
+    }
+
+    /// Cash value of the fill: quantity × price × contract multiplier.
+    pub fn notional(&self) -> Decimal {
+        self.quantity * self.fill_price * self.instrument.multiplier
+    }
+
+    /// Hour of the fill in the exchange timezone.
+    pub fn hour_of_day(&self, exchange_tz: Tz) -> u32 {
+        exchange_time::local_hour(self.time, exchange_tz)
+    }
+
+    /// Trading day of the fill in the exchange timezone.
+    pub fn trading_date(&self, exchange_tz: Tz) -> NaiveDate {
+        exchange_time::trading_date(self.time, exchange_tz)
+    }
+
+    /// Parse "YYYY-MM-DD HH:MM:SS" as wall-clock time in `source_tz`, or an
+    /// RFC 3339 timestamp with its own offset (e.g. "2024-01-02T14:35:12Z").
+    pub fn parse_time(time_str: &str, source_tz: Tz) -> anyhow::Result<DateTime<Utc>> {
+        if let Ok(time) = DateTime::parse_from_rfc3339(time_str) {
+            return Ok(time.with_timezone(&Utc));
+        }
+        let naive = NaiveDateTime::parse_from_str(time_str, "%Y-%m-%d %H:%M:%S")?;
+        exchange_time::local_to_utc(naive, source_tz)
+            .ok_or_else(|| anyhow::anyhow!("Time {} does not exist in {}", time_str, source_tz))
+    }
+}
\ No newline at end of file
@@ -0,0 +1,151 @@
+use super::report::{DiagnosticKind, LineError};
+
+/// A logical column of the generic Trades format and the header names it is known by.
+pub struct ColumnSpec {
+    /// Name used in messages
+    pub name: &'static str,
+    /// Accepted header names, lowercase, in order of preference
+    pub aliases: &'static [&'static str],
+}
+
+pub const SYMBOL: ColumnSpec = ColumnSpec {
+    name: "Symbol",
+    aliases: &["symbol", "ticker"],
+};
+pub const SIDE: ColumnSpec = ColumnSpec {
+    name: "Side",
+    aliases: &["side", "action", "buy/sell"],
+};
+pub const QUANTITY: ColumnSpec = ColumnSpec {
+    name: "Qty",
+    aliases: &["qty", "quantity", "shares", "filled qty"],
+};
+pub const PRICE: ColumnSpec = ColumnSpec {
+    name: "Fill Price",
+    aliases: &["fill price", "price", "avg price", "avg fill price", "execution price"],
+};
+pub const TIME: ColumnSpec = ColumnSpec {
+    name: "Time",
+    aliases: &["time", "fill time", "date/time", "datetime", "date time", "execution time", "timestamp"],
+};
+pub const NET_AMOUNT: ColumnSpec = ColumnSpec {
+    name: "Net Amount",
+    aliases: &["net amount", "amount", "proceeds"],
+};
+pub const COMMISSION: ColumnSpec = ColumnSpec {
+    name: "Commission",
+    aliases: &["commission", "commissions", "comm", "fee", "fees"],
+};
+pub const ASSET_CLASS: ColumnSpec = ColumnSpec {
+    name: "Asset Class",
+    aliases: &["asset class", "asset category", "sec type", "security type", "asset type"],
+};
+pub const MULTIPLIER: ColumnSpec = ColumnSpec {
+    name: "Multiplier",
+    aliases: &["multiplier", "mult", "contract multiplier"],
+};
+pub const CURRENCY: ColumnSpec = ColumnSpec {
+    name: "Currency",
+    aliases: &["currency", "ccy"],
+};
+pub const ACCOUNT: ColumnSpec = ColumnSpec {
+    name: "Account",
+    aliases: &["account", "account id", "account number", "acct"],
+};
+/// Must identify a single fill; order IDs are shared by an order's partial fills
+pub const EXECUTION_ID: ColumnSpec = ColumnSpec {
+    name: "Execution ID",
+    aliases: &["execution id", "exec id", "execid", "trade id", "tradeid", "fill id", "transaction id"],
+};
+/// Execution ID of the opening fill a closing fill sells from (specific-ID lots)
+pub const CLOSES_LOT: ColumnSpec = ColumnSpec {
+    name: "Closes Lot",
+    aliases: &["closes lot", "lot id", "closing lot", "opening execution id", "open trade id"],
+};
+
+/// Column indexes of a Trades file, resolved from its header row.
+///
+/// Columns may appear in any order and unknown columns are ignored.
+/// Net Amount defaults to quantity × price × multiplier and Commission to zero when absent.
+/// Without instrument columns every row is a USD stock.
+#[derive(Debug, Clone)]
+pub struct TradeColumns {
+    pub symbol: usize,
+    pub side: usize,
+    pub quantity: usize,
+    pub price: usize,
+    pub time: usize,
+    pub net_amount: Option<usize>,
+    pub commission: Option<usize>,
+    pub asset_class: Option<usize>,
+    pub multiplier: Option<usize>,
+    pub currency: Option<usize>,
+    pub account: Option<usize>,
+    pub execution_id: Option<usize>,
+    pub closes_lot: Option<usize>,
+}
+
+impl TradeColumns {
+    /// Resolve column indexes from a header row.
+    /// Fails with the list of required columns that could not be found.
+    pub fn from_header(header: &[String]) -> Result<Self, LineError> {
+        let required = [&SYMBOL, &SIDE, &QUANTITY, &PRICE, &TIME];
+        let found: Vec<Option<usize>> = required.iter().map(|spec| find_column(header, spec)).collect();
+
+        let missing: Vec<String> = required.iter()
+            .zip(&found)
+            .filter(|(_, idx)| idx.is_none())
+            .map(|(spec, _)| format!("{} (one of: {})", spec.name, spec.aliases.join(", ")))
+            .collect();
+        if !missing.is_empty() {
+            return Err(LineError::new(
+                DiagnosticKind::MissingColumns,
+                format!("Missing required columns: {}", missing.join("; ")),
+            ));
+        }
+
+        let idx: Vec<usize> = found.into_iter().flatten().collect();
+        Ok(Self {
+            symbol: idx[0],
+            side: idx[1],
+            quantity: idx[2],
+            price: idx[3],
+            time: idx[4],
+            net_amount: find_column(header, &NET_AMOUNT),
+            commission: find_column(header, &COMMISSION),
+            asset_class: find_column(header, &ASSET_CLASS),
+            multiplier: find_column(header, &MULTIPLIER),
+            currency: find_column(header, &CURRENCY),
+            account: find_column(header, &ACCOUNT),
+            execution_id: find_column(header, &EXECUTION_ID),
+            closes_lot: find_column(header, &CLOSES_LOT),
+        })
+    }
+}
+
+/// True if the header names the columns that identify a Trades file
+/// (symbol, side, quantity and price), whether or not the rest are present.
+pub fn looks_like_trades(header: &[String]) -> bool {
+    [&SYMBOL, &SIDE, &QUANTITY, &PRICE]
+        .iter()
+        .all(|spec| find_column(header, spec).is_some())
+}
+
+/// Index of the first header cell matching the spec, trying aliases in order of preference.
+pub fn find_column(header: &[String], spec: &ColumnSpec) -> Option<usize> {
+    let normalized: Vec<String> = header.iter().map(|h| normalize(h)).collect();
+    spec.aliases
+        .iter()
+        .find_map(|alias| normalized.iter().position(|h| h == alias))
+}
+
+/// Index of the header cell with the given name, ignoring case and extra whitespace.
+pub fn find_named(header: &[String], name: &str) -> Option<usize> {
+    let name = normalize(name);
+    header.iter().position(|h| normalize(h) == name)
+}
+
+/// Lowercase and collapse whitespace so "Fill  Price " matches "fill price".
+fn normalize(header: &str) -> String {
+    header.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
@@ -0,0 +1,669 @@
+use crate::models::{AssetClass, Instrument, Trade, Side};
+use crate::exchange_time::{self, Tz, DEFAULT_EXCHANGE_TZ};
+use super::columns::{self, TradeColumns};
+use super::{excel_reader, flex_xml, ib_activity};
+use super::profile::ImportProfile;
+use super::report::{Detection, DiagnosticKind, LineError, ParseReport};
+use anyhow::{bail, Context, Result};
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileFormat {
+    Trades,
+    InteractiveBrokers,
+    /// IB Activity Statement (multi-section CSV), read from its Trades section
+    IbActivityStatement,
+    /// IB Flex Query XML statement
+    IbFlex,
+    Positions,
+    /// Matched a user-defined import profile (by name)
+    Profile(String),
+    Unknown,
+}
+
+impl fmt::Display for FileFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileFormat::Trades => write!(f, "Trades"),
+            FileFormat::InteractiveBrokers => write!(f, "IB Transaction History"),
+            FileFormat::IbActivityStatement => write!(f, "IB Activity Statement"),
+            FileFormat::IbFlex => write!(f, "IB Flex XML"),
+            FileFormat::Positions => write!(f, "Positions"),
+            FileFormat::Profile(name) => write!(f, "profile '{}'", name),
+            FileFormat::Unknown => write!(f, "unrecognized format"),
+        }
+    }
+}
+
+/// A single record of fields, from a CSV file or a spreadsheet row.
+pub struct Row {
+    /// 1-based line (or sheet row) the record starts on
+    pub line: usize,
+    pub fields: Vec<String>,
+    /// The record as written in the file, for diagnostics
+    pub raw: String,
+}
+
+/// Delimiters tried when sniffing a CSV file, in order of preference on ties.
+const DELIMITERS: [u8; 3] = [b',', b';', b'\t'];
+
+/// Parser for trade files. User-defined import profiles are tried before the built-in formats.
+#[derive(Debug, Clone)]
+pub struct CsvParser {
+    profiles: Vec<ImportProfile>,
+    /// Timezone of the wall-clock times in built-in formats, and in profiles that do not declare their own
+    timezone: Tz,
+}
+
+impl Default for CsvParser {
+    fn default() -> Self {
+        Self {
+            profiles: Vec::new(),
+            timezone: DEFAULT_EXCHANGE_TZ,
+        }
+    }
+}
+
+impl CsvParser {
+    /// Parser for the built-in formats only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parser that also recognises the given import profiles, in order.
+    pub fn with_profiles(profiles: Vec<ImportProfile>) -> Self {
+        Self { profiles, ..Self::default() }
+    }
+
+    /// Read the built-in formats' timestamps (generic Trades, IB Transaction History,
+    /// Activity Statement and Flex), and those of profiles without a `timezone`, as
+    /// wall-clock times in `timezone` instead of America/New_York.
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    pub fn profiles(&self) -> &[ImportProfile] {
+        &self.profiles
+    }
+
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+    }
+
+    pub fn detect_format(&self, file_path: &Path) -> Result<FileFormat> {
+        Ok(self.detect(file_path)?.format)
+    }
+
+    /// Detect the format of a file, with the reasons for the decision.
+    pub fn detect(&self, file_path: &Path) -> Result<Detection> {
+        if excel_reader::is_workbook(file_path) {
+            // A workbook is as good as its first recognisable sheet
+            let mut reasons = Vec::new();
+            for (sheet, rows) in excel_reader::read_workbook(file_path)? {
+                let detection = self.detect_rows(&rows);
+                if !matches!(detection.format, FileFormat::Unknown) {
+                    return Ok(Detection {
+                        format: detection.format,
+                        reasons: detection.reasons.into_iter().map(|r| format!("sheet '{}': {}", sheet, r)).collect(),
+                    });
+                }
+                reasons.push(format!("sheet '{}' is not a recognised format", sheet));
+            }
+            return Ok(Detection { format: FileFormat::Unknown, reasons });
+        }
+
+        if flex_xml::is_xml(file_path) {
+            let xml = std::fs::read_to_string(file_path)
+                .with_context(|| format!("Failed to read {:?}", file_path))?;
+            return Ok(Self::detect_xml(&xml));
+        }
+
+        let rows = Self::read_csv_rows(file_path)?;
+        Ok(self.detect_rows(&rows))
+    }
+
+    fn detect_xml(xml: &str) -> Detection {
+        if flex_xml::is_flex_statement(xml) {
+            Detection {
+                format: FileFormat::IbFlex,
+                reasons: vec!["XML document with a FlexQueryResponse/FlexStatements element".to_string()],
+            }
+        } else {
+            Detection {
+                format: FileFormat::Unknown,
+                reasons: vec!["XML document is not an IB Flex statement".to_string()],
+            }
+        }
+    }
+
+    /// Detect the format of a CSV file or worksheet. Mostly the leading rows decide,
+    /// but an Activity Statement's Trades section may appear anywhere.
+    fn detect_rows(&self, rows: &[Row]) -> Detection {
+        let found = |format: FileFormat, reason: String| Detection { format, reasons: vec![reason] };
+
+        // User-defined profiles take precedence over the built-in formats
+        for profile in &self.profiles {
+            if let Some(idx) = profile.find_header(rows) {
+                return found(
+                    FileFormat::Profile(profile.name.clone()),
+                    format!("line {} has the profile's header signature: {}", rows[idx].line, profile.header_signature.join(", ")),
+                );
+            }
+        }
+
+        // Check for Interactive Brokers format
+        // Look for "Transaction History,Header" line which defines the columns
+        for row in rows.iter().take(20) {
+            let line_lower = row.fields.join(",").to_lowercase();
+            if line_lower.starts_with("transaction history,header,") {
+                // Verify it has the expected IB columns
+                if line_lower.contains("date") &&
+                   line_lower.contains("symbol") &&
+                   line_lower.contains("transaction type") &&
+                   line_lower.contains("quantity") {
+                    return found(
+                        FileFormat::InteractiveBrokers,
+                        format!("line {} is a Transaction History header with Date, Symbol, Transaction Type and Quantity", row.line),
+                    );
+                }
+            }
+        }
+
+        // Activity Statements start with a "Statement" section; the Trades section comes later
+        if let Some(row) = ib_activity::find_trades_header(rows) {
+            return found(
+                FileFormat::IbActivityStatement,
+                format!("line {} is an Activity Statement Trades header with Symbol, Date/Time and Quantity", row.line),
+            );
+        }
+
+        // Check first line for other formats
+        if let Some(header) = rows.first() {
+            let header_lower = header.fields.join(",").to_lowercase();
+
+            // Check for positions file indicators
+            for indicator in ["unrealized", "last price", "position id"] {
+                if header_lower.contains(indicator) {
+                    return found(FileFormat::Positions, format!("header mentions '{}'", indicator));
+                }
+            }
+
+            // Check for trades file indicators (columns in any order)
+            if columns::looks_like_trades(&header.fields) {
+                return found(FileFormat::Trades, "header has Symbol, Side, quantity and price columns".to_string());
+            }
+
+            // "Avg Price" alone (without a side) is a positions snapshot
+            if header_lower.contains("avg price") {
+                return found(FileFormat::Positions, "header has 'avg price' but no trade side".to_string());
+            }
+        }
+
+        found(FileFormat::Unknown, "no known header (Trades, IB, or an import profile) found".to_string())
+    }
+
+    /// Parse a file into trades, recording every dropped line.
+    /// Only unreadable files (I/O errors, malformed CSV or XML) are errors.
+    pub fn parse_file(&self, file_path: &Path) -> Result<ParseReport> {
+        let file_name = file_path.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown");
+
+        if excel_reader::is_workbook(file_path) {
+            let sheets = excel_reader::read_workbook(file_path)?;
+            let mut report = ParseReport::new(file_name, Detection { format: FileFormat::Unknown, reasons: Vec::new() });
+
+            for (sheet, rows) in &sheets {
+                let detection = self.detect_rows(rows);
+                let first_diagnostic = report.diagnostics.len();
+                self.parse_rows(rows, &detection.format, &mut report);
+                for diagnostic in &mut report.diagnostics[first_diagnostic..] {
+                    diagnostic.sheet = Some(sheet.clone());
+                }
+
+                if matches!(report.format, FileFormat::Unknown) && !matches!(detection.format, FileFormat::Unknown) {
+                    report.format = detection.format;
+                }
+                report.format_reasons.extend(detection.reasons.into_iter().map(|r| format!("sheet '{}': {}", sheet, r)));
+            }
+            return Ok(report);
+        }
+
+        if flex_xml::is_xml(file_path) {
+            let xml = std::fs::read_to_string(file_path)
+                .with_context(|| format!("Failed to read {:?}", file_path))?;
+            let mut report = ParseReport::new(file_name, Self::detect_xml(&xml));
+            if report.format == FileFormat::IbFlex {
+                let (trades, diagnostics) = flex_xml::parse_flex_trades(&xml, self.timezone)
+                    .with_context(|| format!("Failed to parse Flex statement {}", file_name))?;
+                report.trades = trades;
+                report.diagnostics = diagnostics;
+            }
+            return Ok(report);
+        }
+
+        let rows = Self::read_csv_rows(file_path)?;
+        let mut report = ParseReport::new(file_name, self.detect_rows(&rows));
+        let format = report.format.clone();
+        self.parse_rows(&rows, &format, &mut report);
+        Ok(report)
+    }
+
+    /// Parse a table of rows in a detected format, adding trades and diagnostics to the report.
+    /// Positions and unrecognised tables yield nothing.
+    fn parse_rows(&self, rows: &[Row], format: &FileFormat, report: &mut ParseReport) {
+        match format {
+            FileFormat::Positions | FileFormat::Unknown => {}
+            FileFormat::InteractiveBrokers => Self::parse_ib_rows(rows, self.timezone, report),
+            FileFormat::IbActivityStatement => ib_activity::parse_activity_rows(rows, self.timezone, report),
+            // Flex statements are XML documents, never rows
+            FileFormat::IbFlex => {}
+            FileFormat::Trades => Self::parse_trades_rows(rows, self.timezone, report),
+            FileFormat::Profile(name) => {
+                let profile = self.profiles.iter().find(|p| &p.name == name)
+                    .expect("detected profile is loaded");
+                profile.parse_rows(rows, self.timezone, report);
+            }
+        }
+    }
+
+    /// Parse the generic Trades format. Column positions come from the header row.
+    fn parse_trades_rows(rows: &[Row], timezone: Tz, report: &mut ParseReport) {
+        let Some(header) = rows.first() else {
+            return;
+        };
+        let columns = match TradeColumns::from_header(&header.fields) {
+            Ok(columns) => columns,
+            Err(e) => {
+                // Without the required columns no row can be read
+                report.diagnostics.push(e.at(header));
+                return;
+            }
+        };
+
+        for row in rows.iter().skip(1) {
+            if row.fields.iter().all(|f| f.trim().is_empty()) {
+                continue;
+            }
+
+            match Self::parse_line(&columns, &row.fields, timezone) {
+                Ok(trade) => report.trades.push(trade),
+                Err(e) => report.diagnostics.push(e.at(row)),
+            }
+        }
+    }
+
+    /// Read a CSV file per RFC 4180: quoted fields may contain delimiters,
+    /// doubled quotes and newlines. A UTF-8 BOM is stripped, and the
+    /// delimiter (`,`, `;` or tab) is sniffed from the first line.
+    ///
+    /// `;` separates fields where `,` is the decimal separator, so in those files
+    /// a number with one comma and no dot is read as a decimal (`1,250` is 1.25).
+    pub fn read_csv_rows(file_path: &Path) -> Result<Vec<Row>> {
+        let bytes = std::fs::read(file_path)
+            .with_context(|| format!("Failed to read {:?}", file_path))?;
+        Self::read_csv_bytes(&bytes)
+            .with_context(|| format!("Failed to read CSV records from {:?}", file_path))
+    }
+
+    fn read_csv_bytes(bytes: &[u8]) -> Result<Vec<Row>> {
+        let delimiter = Self::sniff_delimiter(bytes);
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .delimiter(delimiter)
+            .from_reader(bytes);
+
+        let mut records = Vec::new();
+        for record in reader.records() {
+            let record = record?;
+            let (line, start) = record.position()
+                .map(|p| (p.line() as usize, p.byte() as usize))
+                .unwrap_or((records.len() + 1, 0));
+            records.push((line, start, record));
+        }
+
+        // A record's raw text runs to where the next record starts
+        let mut rows = Vec::with_capacity(records.len());
+        for (idx, (line, start, record)) in records.iter().enumerate() {
+            let end = records.get(idx + 1).map(|(_, next, _)| *next).unwrap_or(bytes.len());
+            let raw = String::from_utf8_lossy(&bytes[(*start).min(end)..end]);
+            rows.push(Row {
+                line: *line,
+                fields: record.iter()
+                    .map(|f| match delimiter {
+                        b';' => Self::decimal_comma(f),
+                        _ => f.to_string(),
+                    })
+                    .collect(),
+                raw: raw.trim_start_matches('\u{feff}').trim_end_matches(['\r', '\n']).to_string(),
+            });
+        }
+
+        Ok(rows)
+    }
+
+    /// A field of a `;`-delimited file with its lone decimal comma made a dot, so
+    /// `parse_decimal` does not take `1,250` for thousands. Other fields are unchanged.
+    fn decimal_comma(field: &str) -> String {
+        let Some((int, fraction)) = field.split_once(',') else {
+            return field.to_string();
+        };
+        let digits_around = int.ends_with(|c: char| c.is_ascii_digit()) && fraction.starts_with(|c: char| c.is_ascii_digit());
+        let numeric = field.chars().all(|c| c.is_ascii_digit() || ",-+$() ".contains(c));
+        if digits_around && numeric && !fraction.contains(',') {
+            format!("{}.{}", int, fraction)
+        } else {
+            field.to_string()
+        }
+    }
+
+    /// Pick the delimiter that occurs most often (outside quotes) on the first non-empty line.
+    fn sniff_delimiter(bytes: &[u8]) -> u8 {
+        let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+        let first_line = bytes
+            .split(|b| *b == b'\n')
+            .find(|l| l.iter().any(|b| !b.is_ascii_whitespace()))
+            .unwrap_or(&[]);
+
+        let mut counts = [0usize; DELIMITERS.len()];
+        let mut in_quotes = false;
+        for b in first_line {
+            if *b == b'"' {
+                in_quotes = !in_quotes;
+            } else if !in_quotes {
+                if let Some(i) = DELIMITERS.iter().position(|d| d == b) {
+                    counts[i] += 1;
+                }
+            }
+        }
+
+        let best = (0..DELIMITERS.len())
+            .fold(0, |best, i| if counts[i] > counts[best] { i } else { best });
+        DELIMITERS[best]
+    }
+
+    /// Parse a money or quantity field as written by brokers and spreadsheets:
+    /// surrounding `$` and whitespace, `1,234.56` thousands separators,
+    /// `(12.50)` accounting negatives and scientific notation are accepted.
+    ///
+    /// When both `,` and `.` appear, the last one is the decimal separator:
+    /// `1.234,56` is European and `1,234.56` is not. A lone comma followed by
+    /// other than three digits is a decimal comma (`12,50`). Thousands groups
+    /// that are not three digits, or a mix that could be read either way
+    /// (`1.234,567`), are an error rather than a guess.
+    pub fn parse_decimal(raw: &str) -> Result<Decimal> {
+        let mut s = raw.trim().replace(['$', ' '], "");
+        let negative = s.starts_with('(') && s.ends_with(')');
+        if negative {
+            s = s[1..s.len() - 1].to_string();
+        }
+
+        if s.contains(',') {
+            s = match Self::normalize_separators(&s) {
+                Some(normalized) => normalized,
+                None => bail!("Invalid number: {}", raw.trim()),
+            };
+        }
+
+        let value = if s.contains(['e', 'E']) {
+            Decimal::from_scientific(&s)
+        } else {
+            Decimal::from_str(&s)
+        }
+        .with_context(|| format!("Invalid number: {}", raw.trim()))?;
+
+        Ok(if negative { -value } else { value })
+    }
+
+    /// Rewrite a number containing commas with `.` as its only separator, or
+    /// None when its separators are inconsistent or ambiguous.
+    fn normalize_separators(s: &str) -> Option<String> {
+        let (sign, digits) = match s.strip_prefix(['-', '+']) {
+            Some(rest) => (&s[..1], rest),
+            None => ("", s),
+        };
+        // Thousands groups: one to three leading digits, then groups of exactly three
+        let grouped = |int: &str, separator: char| {
+            let mut groups = int.split(separator);
+            let first = groups.next().unwrap_or("");
+            (1..=3).contains(&first.len())
+                && first.bytes().all(|b| b.is_ascii_digit())
+                && groups.all(|g| g.len() == 3 && g.bytes().all(|b| b.is_ascii_digit()))
+        };
+
+        let last_comma = digits.rfind(',')?;
+        let normalized = match digits.rfind('.') {
+            // `1,234.56`: commas group thousands before the decimal point
+            Some(dot) if dot > last_comma => {
+                let (int, fraction) = digits.split_at(dot);
+                grouped(int, ',').then(|| format!("{}{}", int.replace(',', ""), fraction))?
+            }
+            // `1.234,56`: dots group thousands before a decimal comma of one or two digits
+            Some(_) => {
+                let (int, fraction) = (&digits[..last_comma], &digits[last_comma + 1..]);
+                let is_cents = (1..=2).contains(&fraction.len()) && fraction.bytes().all(|b| b.is_ascii_digit());
+                (is_cents && grouped(int, '.')).then(|| format!("{}.{}", int.replace('.', ""), fraction))?
+            }
+            None if digits.matches(',').count() == 1 && digits.len() - last_comma - 1 != 3 => digits.replace(',', "."),
+            None => grouped(digits, ',').then(|| digits.replace(',', ""))?,
+        };
+        Some(format!("{}{}", sign, normalized))
+    }
+
+    /// `parse_decimal` for a named field of a record, as a line diagnostic on failure.
+    pub fn parse_field_decimal(raw: &str, field: &str) -> std::result::Result<Decimal, LineError> {
+        if raw.trim().is_empty() {
+            return Err(LineError::new(DiagnosticKind::MissingValue, format!("Missing {}", field)));
+        }
+        Self::parse_decimal(raw)
+            .map_err(|_| LineError::new(DiagnosticKind::InvalidNumber, format!("Invalid {}: {}", field, raw.trim())))
+    }
+
+    /// A fill's quantity, as signed in the file. A zero quantity is a line
+    /// diagnostic, as it opens and closes nothing.
+    pub fn parse_field_quantity(raw: &str) -> std::result::Result<Decimal, LineError> {
+        let quantity = Self::parse_field_decimal(raw, "quantity")?;
+        if quantity == Decimal::ZERO {
+            return Err(LineError::new(DiagnosticKind::InvalidNumber, "Zero quantity"));
+        }
+        Ok(quantity)
+    }
+
+    /// `Side::from_str` as a line diagnostic on failure.
+    pub fn parse_field_side(raw: &str) -> std::result::Result<Side, LineError> {
+        Side::from_str(raw.trim())
+            .map_err(|e| LineError::new(DiagnosticKind::InvalidSide, e.to_string()))
+    }
+
+    /// Build an instrument from optional asset class, multiplier and currency fields.
+    /// Blank fields fall back to a USD stock and the class's default multiplier;
+    /// asset classes that are not traded here (e.g. bonds) are `UnsupportedAsset`.
+    pub fn parse_field_instrument(asset_class: &str, multiplier: &str, currency: &str) -> std::result::Result<Instrument, LineError> {
+        let mut instrument = if asset_class.trim().is_empty() {
+            Instrument::stock()
+        } else {
+            let class = AssetClass::from_code(asset_class).ok_or_else(|| LineError::new(
+                DiagnosticKind::UnsupportedAsset,
+                format!("{} trades are not imported", asset_class.trim()),
+            ))?;
+            Instrument::of_class(class)
+        };
+
+        if !multiplier.trim().is_empty() {
+            let value = Self::parse_field_decimal(multiplier, "multiplier")?;
+            if value <= Decimal::ZERO {
+                return Err(LineError::new(DiagnosticKind::InvalidNumber, format!("Invalid multiplier: {}", multiplier.trim())));
+            }
+            instrument.multiplier = value;
+        }
+        if !currency.trim().is_empty() {
+            instrument.currency = currency.trim().to_uppercase();
+        }
+        Ok(instrument)
+    }
+
+    fn parse_line(columns: &TradeColumns, parts: &[String], timezone: Tz) -> std::result::Result<Trade, LineError> {
+        // Short rows read missing trailing fields as empty
+        let field = |idx: usize| parts.get(idx).map(|p| p.trim()).unwrap_or("");
+        let optional = |idx: Option<usize>| idx.map(field).unwrap_or("");
+
+        let symbol = field(columns.symbol);
+        if symbol.is_empty() {
+            return Err(LineError::new(DiagnosticKind::MissingValue, "Missing symbol"));
+        }
+
+        // Some exports sign quantities by side (`-100` to sell); the side column says which way
+        let quantity = Self::parse_field_quantity(field(columns.quantity))?.abs();
+        let fill_price = Self::parse_field_decimal(field(columns.price), "fill price")?;
+
+        let instrument = Self::parse_field_instrument(
+            optional(columns.asset_class),
+            optional(columns.multiplier),
+            optional(columns.currency),
+        )?;
+
+        let net_amount_str = optional(columns.net_amount);
+        let net_amount = if net_amount_str.is_empty() {
+            quantity * fill_price * instrument.multiplier
+        } else {
+            Self::parse_field_decimal(net_amount_str, "net amount")?
+        };
+
+        let commission_str = optional(columns.commission);
+        let commission = if commission_str.is_empty() {
+            Decimal::ZERO
+        } else {
+            Self::parse_field_decimal(commission_str, "commission")?
+        };
+
+        let time_str = field(columns.time);
+        let time = Trade::parse_time(time_str, timezone)
+            .map_err(|_| LineError::new(DiagnosticKind::InvalidTime, format!("Invalid time: {} (expected YYYY-MM-DD HH:MM:SS in {})", time_str, timezone)))?;
+
+        Ok(Trade {
+            symbol: symbol.to_string(),
+            side: Self::parse_field_side(field(columns.side))?,
+            quantity,
+            fill_price,
+            time,
+            net_amount,
+            commission,
+            realized_pnl: None,
+            instrument,
+            account: Some(optional(columns.account)).filter(|a| !a.is_empty()).map(str::to_string),
+            execution_id: Some(optional(columns.execution_id)).filter(|id| !id.is_empty()).map(str::to_string),
+            closes_lot: Some(optional(columns.closes_lot)).filter(|id| !id.is_empty()).map(str::to_string),
+            source: None,
+        })
+    }
+
+    /// Parse Interactive Brokers transaction history rows
+    /// Format: Transaction History,Data,Date,Account,Description,Transaction Type,Symbol,Quantity,Price,Gross Amount,Commission,Net Amount
+    fn parse_ib_rows(rows: &[Row], timezone: Tz, report: &mut ParseReport) {
+        for row in rows {
+            let fields = &row.fields;
+
+            // Only process "Transaction History,Data," lines
+            if fields.len() < 2 || fields[0].trim() != "Transaction History" || fields[1].trim() != "Data" {
+                continue;
+            }
+
+            // Bad lines are reported, not fatal
+            match Self::parse_ib_line(fields, row.line, timezone) {
+                Ok(trade) => report.trades.push(trade),
+                Err(e) => report.diagnostics.push(e.at(row)),
+            }
+        }
+    }
+
+    /// Parse a single Interactive Brokers transaction line
+    /// Format: Transaction History,Data,Date,Account,Description,Transaction Type,Symbol,Quantity,Price,Gross Amount,Commission,Net Amount
+    /// Index:  0                   1    2    3       4           5                6      7        8     9            10         11
+    fn parse_ib_line(parts: &[String], line_num: usize, timezone: Tz) -> std::result::Result<Trade, LineError> {
+        if parts.len() < 12 {
+            return Err(LineError::new(
+                DiagnosticKind::TooFewFields,
+                format!("Invalid IB CSV format: expected 12 fields, got {}", parts.len()),
+            ));
+        }
+
+        // Index mapping for IB format:
+        // 0: "Transaction History"
+        // 1: "Data"
+        // 2: Date (YYYY-MM-DD)
+        // 3: Account
+        // 4: Description
+        // 5: Transaction Type (Buy/Sell)
+        // 6: Symbol
+        // 7: Quantity (negative for sells)
+        // 8: Price
+        // 9: Gross Amount
+        // 10: Commission (may be "-" for zero)
+        // 11: Net Amount
+
+        let date_str = parts[2].trim();
+        let account = parts[3].trim();
+        let transaction_type = parts[5].trim();
+        let symbol = parts[6].trim();
+        let quantity_str = parts[7].trim();
+        let price_str = parts[8].trim();
+        let commission_str = parts[10].trim();
+        let net_amount_str = parts[11].trim();
+
+        // Parse date (IB format is YYYY-MM-DD, no time; spreadsheet cells may carry a midnight time)
+        // Assign a default time based on transaction order within the day
+        // We'll use market open (09:30, local to the statement's timezone) as default
+        let date = NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
+            .or_else(|_| NaiveDateTime::parse_from_str(date_str, "%Y-%m-%d %H:%M:%S").map(|dt| dt.date()))
+            .map_err(|_| LineError::new(DiagnosticKind::InvalidTime, format!("Invalid date: {}", date_str)))?;
+        // Spread line_num across seconds and milliseconds to support up to 60,000 unique timestamps per day
+        let secs = (line_num as u32 / 1000) % 30;
+        let millis = line_num as u32 % 1000;
+        let datetime = NaiveDateTime::new(date, chrono::NaiveTime::from_hms_milli_opt(9, 30 + secs, 0, millis).unwrap());
+        let time = exchange_time::local_to_utc(datetime, timezone)
+            .ok_or_else(|| LineError::new(DiagnosticKind::InvalidTime, format!("Time {} does not exist in {}", datetime, timezone)))?;
+
+        // Parse side from transaction type
+        let side = Self::parse_field_side(transaction_type)?;
+
+        // Parse quantity (IB uses negative for sells, we want absolute value)
+        let quantity = Self::parse_field_quantity(quantity_str)?.abs();
+
+        // Parse price
+        let fill_price = Self::parse_field_decimal(price_str, "price")?;
+
+        // Parse commission (IB uses "-" for zero commission, and sometimes scientific notation, e.g. "-6.6E-4")
+        let commission = if commission_str == "-" || commission_str.is_empty() {
+            Decimal::ZERO
+        } else {
+            Self::parse_field_decimal(commission_str, "commission")?
+                .abs()  // Commission should always be positive
+        };
+
+        // Parse net amount
+        let net_amount = Self::parse_field_decimal(net_amount_str, "net amount")?;
+
+        Ok(Trade {
+            symbol: symbol.to_string(),
+            side,
+            quantity,
+            fill_price,
+            time,
+            net_amount,
+            commission,
+            realized_pnl: None,
+            // Transaction History has no asset class column
+            instrument: Instrument::stock(),
+            account: Some(account).filter(|a| !a.is_empty()).map(str::to_string),
+            execution_id: None,
+            closes_lot: None,
+            source: None,
+        })
+    }
+}
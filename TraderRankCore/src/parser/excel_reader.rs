@@ -0,0 +1,73 @@
+use super::csv_parser::Row;
+use anyhow::{Context, Result};
+use calamine::{open_workbook_auto, Data, Reader};
+use chrono::NaiveDateTime;
+use std::path::Path;
+
+/// Spreadsheet extensions read through calamine instead of as CSV text.
+pub const WORKBOOK_EXTENSIONS: &[&str] = &["xlsx", "xlsm", "xlsb", "xls", "ods"];
+
+/// True if the path has a spreadsheet extension (case-insensitive).
+pub fn is_workbook(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| WORKBOOK_EXTENSIONS.iter().any(|w| ext.eq_ignore_ascii_case(w)))
+        .unwrap_or(false)
+}
+
+/// Read every worksheet as (sheet name, rows of cell text).
+///
+/// Cells are rendered the way a CSV export would write them, so the same
+/// format detection and row parsers apply: dates become `YYYY-MM-DD HH:MM:SS`,
+/// whole-number floats drop their trailing `.0`, and empty cells become "".
+pub fn read_workbook(path: &Path) -> Result<Vec<(String, Vec<Row>)>> {
+    let mut workbook = open_workbook_auto(path)
+        .with_context(|| format!("Failed to open workbook {:?}", path))?;
+
+    let mut sheets = Vec::new();
+    for name in workbook.sheet_names() {
+        let range = workbook.worksheet_range(&name)
+            .with_context(|| format!("Failed to read sheet '{}' in {:?}", name, path))?;
+
+        // Rows are numbered as the spreadsheet numbers them, even when the used
+        // range starts below the first row
+        let first_row = range.start().map_or(0, |(row, _)| row as usize);
+        let rows = range.rows()
+            .enumerate()
+            .map(|(idx, row)| {
+                let fields: Vec<String> = row.iter().map(cell_to_string).collect();
+                Row {
+                    line: first_row + idx + 1,
+                    raw: fields.join(","),
+                    fields,
+                }
+            })
+            .collect();
+        sheets.push((name, rows));
+    }
+
+    Ok(sheets)
+}
+
+fn cell_to_string(cell: &Data) -> String {
+    match cell {
+        Data::Empty | Data::Error(_) => String::new(),
+        Data::String(s) | Data::DurationIso(s) => s.trim().to_string(),
+        // ODS stores dates as ISO 8601 text ("2024-01-02T09:30:00")
+        Data::DateTimeIso(s) => NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+            .map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|_| s.trim().to_string()),
+        Data::Int(i) => i.to_string(),
+        Data::Float(f) => {
+            if f.fract() == 0.0 && f.abs() < 1e15 {
+                format!("{}", *f as i64)
+            } else {
+                f.to_string()
+            }
+        }
+        Data::Bool(b) => b.to_string(),
+        Data::DateTime(dt) => dt.as_datetime()
+            .map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default(),
+    }
+}
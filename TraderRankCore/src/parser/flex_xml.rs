@@ -0,0 +1,280 @@
+use crate::models::{AssetClass, Instrument, OptionRight, Side, Trade};
+use super::report::{Diagnostic, DiagnosticKind, LineError};
+use crate::exchange_time::{self, Tz};
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use rust_decimal::Decimal;
+use std::path::Path;
+use std::str::FromStr;
+
+/// True if the path has an `.xml` extension (case-insensitive).
+pub fn is_xml(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("xml"))
+        .unwrap_or(false)
+}
+
+/// True if the document looks like an IB Flex Query statement.
+pub fn is_flex_statement(xml: &str) -> bool {
+    xml.contains("<FlexQueryResponse") || xml.contains("<FlexStatements")
+}
+
+/// A single trade execution parsed from Flex XML, before a time is assigned.
+struct FlexTrade {
+    symbol: String,
+    side: Side,
+    quantity: Decimal,
+    price: Decimal,
+    date: NaiveDate,
+    time: Option<NaiveTime>,
+    commission: Decimal,
+    net_amount: Decimal,
+    realized_pnl: Option<Decimal>,
+    instrument: Instrument,
+    account: Option<String>,
+    execution_id: Option<String>,
+}
+
+/// Parse trade executions from a Flex statement, as downloaded from the
+/// Flex Web Service or saved from Client Portal.
+/// Handles both Activity Statement (<Trade>) and Trade Confirmation (<TradeConfirm>) formats.
+///
+/// Trades are returned in time order. Executions without a time are given
+/// synthetic times from 09:30 in statement order, like IB Transaction History rows.
+/// Times are wall-clock times in `timezone`, the Flex Query's reporting timezone
+/// (US/Eastern unless changed). Elements that cannot be read are returned as
+/// diagnostics; only a malformed document is an error.
+pub fn parse_flex_trades(xml: &str, timezone: Tz) -> Result<(Vec<Trade>, Vec<Diagnostic>)> {
+    let doc = roxmltree::Document::parse(xml)
+        .context("Failed to parse Flex XML")?;
+
+    let mut trades = Vec::new();
+    let mut diagnostics = Vec::new();
+
+    // Look for <Trade> elements (Activity Statement) and <TradeConfirm> elements
+    for node in doc.descendants() {
+        let tag = node.tag_name().name();
+        if tag != "Trade" && tag != "TradeConfirm" {
+            continue;
+        }
+
+        // Skip summary/header rows — only want EXECUTION level detail
+        if let Some(level) = node.attribute("levelOfDetail") {
+            if level != "EXECUTION" && level != "ORDER" {
+                continue;
+            }
+        }
+
+        match parse_flex_trade(node, timezone) {
+            Ok(Some(trade)) => trades.push(trade),
+            Ok(None) => {}
+            Err(e) => {
+                let line = doc.text_pos_at(node.range().start).row as usize;
+                diagnostics.push(e.at_line(line, &xml[node.range()]));
+            }
+        }
+    }
+
+    // Sort by date+time so matching sees fills in order
+    trades.sort_by_key(|t| NaiveDateTime::new(t.date, t.time.unwrap_or(NaiveTime::MIN)));
+
+    let trades = trades.into_iter()
+        .enumerate()
+        .map(|(index, t)| {
+            let time = t.time.unwrap_or_else(|| {
+                let secs = (index as u32 / 1000) % 30;
+                let millis = index as u32 % 1000;
+                NaiveTime::from_hms_milli_opt(9, 30 + secs, 0, millis).unwrap()
+            });
+
+            Trade {
+                symbol: t.symbol,
+                side: t.side,
+                quantity: t.quantity,
+                fill_price: t.price,
+                time: exchange_time::local_to_utc(NaiveDateTime::new(t.date, time), timezone)
+                    .expect("trade times are checked to exist in parse_flex_trade"),
+                net_amount: t.net_amount,
+                commission: t.commission,
+                realized_pnl: t.realized_pnl,
+                instrument: t.instrument,
+                account: t.account,
+                execution_id: t.execution_id,
+                closes_lot: None,
+                source: None,
+            }
+        })
+        .collect();
+
+    Ok((trades, diagnostics))
+}
+
+/// Read one execution-level <Trade>/<TradeConfirm> element.
+/// Zero-quantity fills yield `None`.
+fn parse_flex_trade(node: roxmltree::Node, timezone: Tz) -> Result<Option<FlexTrade>, LineError> {
+    // Skip forex conversions, bonds and other non-trading asset classes
+    let instrument = parse_instrument(node)?;
+
+    let symbol = match node.attribute("symbol") {
+        Some(s) if !s.is_empty() => s.to_string(),
+        _ => return Err(LineError::new(DiagnosticKind::MissingValue, "Missing symbol")),
+    };
+
+    let buy_sell = node.attribute("buySell").unwrap_or("");
+    let side = match buy_sell.to_uppercase().as_str() {
+        "BUY" => Side::Buy,
+        "SELL" => Side::Sell,
+        "BUY (CA.)" => Side::Buy,   // cancelled/corrected
+        "SELL (CA.)" => Side::Sell,
+        _ => return Err(LineError::new(DiagnosticKind::InvalidSide, format!("Invalid buySell: '{}'", buy_sell))),
+    };
+
+    let quantity = parse_attr_decimal(node, "quantity")?.abs();
+    if quantity == Decimal::ZERO {
+        return Ok(None);
+    }
+    let price = parse_attr_decimal(node, "tradePrice")?;
+    let commission = parse_attr_decimal(node, "ibCommission")?.abs();
+    // parse_attr_decimal reads a missing attribute as zero, so look for one that is present
+    let net_amount = match ["netCash", "proceeds"].into_iter().find(|a| node.attribute(*a).is_some_and(|v| !v.is_empty())) {
+        Some(attr) => parse_attr_decimal(node, attr)?,
+        None => quantity * price * instrument.multiplier,
+    };
+
+    // Parse date and time
+    // IB Flex uses multiple formats:
+    //   - Combined: dateTime="YYYYMMDD;HHMMSS" (Activity Statements)
+    //   - Separate: tradeDate="YYYYMMDD" + tradeTime="HHMMSS"
+    //   - orderTime="YYYYMMDD;HHMMSS" as fallback
+    let (date, time) = parse_trade_datetime(&node)?;
+    // Missing times are later given synthetic times between 09:30 and 10:00, checked here as 09:30
+    let wall_clock = NaiveDateTime::new(date, time.unwrap_or(NaiveTime::from_hms_opt(9, 30, 0).unwrap()));
+    if exchange_time::local_to_utc(wall_clock, timezone).is_none() {
+        return Err(LineError::new(DiagnosticKind::InvalidTime, format!("Time {} does not exist in {}", wall_clock, timezone)));
+    }
+
+    let realized_pnl = node.attribute("fifoPnlRealized")
+        .filter(|v| !v.is_empty())
+        .map(|_| parse_attr_decimal(node, "fifoPnlRealized"))
+        .transpose()?;
+
+    // Trades usually carry accountId; otherwise it is on the enclosing <FlexStatement>
+    let account = node.ancestors()
+        .find_map(|n| n.attribute("accountId").filter(|a| !a.is_empty()))
+        .map(str::to_string);
+
+    // tradeID is shared by Activity and Trade Confirmation queries; execution IDs are the fallback
+    let execution_id = ["tradeID", "ibExecID", "execID"]
+        .iter()
+        .find_map(|attr| node.attribute(*attr).map(str::trim).filter(|v| !v.is_empty()))
+        .map(str::to_string);
+
+    Ok(Some(FlexTrade {
+        symbol,
+        side,
+        quantity,
+        price,
+        date,
+        time,
+        commission,
+        net_amount,
+        realized_pnl,
+        instrument,
+        account,
+        execution_id,
+    }))
+}
+
+/// Read the instrument attributes of a trade element.
+/// Missing attributes fall back to a USD stock and the class's default multiplier.
+fn parse_instrument(node: roxmltree::Node) -> Result<Instrument, LineError> {
+    let attr = |name: &str| node.attribute(name).map(str::trim).filter(|v| !v.is_empty());
+
+    let asset_class = match attr("assetCategory") {
+        // CASH is a forex conversion, not a trade of its own
+        Some(code) if code.eq_ignore_ascii_case("CASH") => None,
+        Some(code) => AssetClass::from_code(code),
+        None => Some(AssetClass::Stk),
+    };
+    let Some(asset_class) = asset_class else {
+        let code = attr("assetCategory").unwrap_or_default();
+        return Err(LineError::new(DiagnosticKind::UnsupportedAsset, format!("{} trades are not imported", code)));
+    };
+
+    let mut instrument = Instrument::of_class(asset_class);
+    if attr("multiplier").is_some() {
+        let multiplier = parse_attr_decimal(node, "multiplier")?;
+        if multiplier > Decimal::ZERO {
+            instrument.multiplier = multiplier;
+        }
+    }
+    if let Some(currency) = attr("currency") {
+        instrument.currency = currency.to_uppercase();
+    }
+
+    if instrument.is_derivative() {
+        instrument.underlying = attr("underlyingSymbol").map(str::to_string);
+        instrument.expiry = attr("expiry").and_then(|v| {
+            NaiveDate::parse_from_str(v, "%Y%m%d")
+                .or_else(|_| NaiveDate::parse_from_str(v, "%Y-%m-%d"))
+                .ok()
+        });
+        if attr("strike").is_some() {
+            instrument.strike = Some(parse_attr_decimal(node, "strike")?);
+        }
+        instrument.right = attr("putCall").and_then(OptionRight::from_code);
+    }
+
+    Ok(instrument)
+}
+
+/// Parse date and time from an IB Flex XML trade node.
+/// Tries multiple attribute names and formats.
+fn parse_trade_datetime(node: &roxmltree::Node) -> Result<(NaiveDate, Option<NaiveTime>), LineError> {
+    // Try combined dateTime first (most common in Activity Statements): "YYYYMMDD;HHMMSS"
+    if let Some(dt_str) = node.attribute("dateTime").or_else(|| node.attribute("orderTime")) {
+        if let Some((date_part, time_part)) = dt_str.split_once(';') {
+            if let Ok(date) = NaiveDate::parse_from_str(date_part, "%Y%m%d") {
+                let time = NaiveTime::parse_from_str(time_part, "%H%M%S")
+                    .or_else(|_| NaiveTime::parse_from_str(time_part, "%H:%M:%S"))
+                    .ok();
+                return Ok((date, time));
+            }
+        }
+        // Try as date-only
+        if let Ok(date) = NaiveDate::parse_from_str(dt_str, "%Y%m%d") {
+            return Ok((date, None));
+        }
+    }
+
+    // Try separate tradeDate + tradeTime
+    let date_str = node.attribute("tradeDate")
+        .or_else(|| node.attribute("reportDate"))
+        .unwrap_or("");
+    let date = NaiveDate::parse_from_str(date_str, "%Y%m%d")
+        .or_else(|_| NaiveDate::parse_from_str(date_str, "%Y-%m-%d"))
+        .map_err(|_| LineError::new(
+            DiagnosticKind::InvalidTime,
+            format!("Invalid trade date: '{}' (no dateTime or tradeDate found)", date_str),
+        ))?;
+
+    let time = node.attribute("tradeTime").and_then(|t| {
+        NaiveTime::parse_from_str(t, "%H%M%S")
+            .or_else(|_| NaiveTime::parse_from_str(t, "%H:%M:%S"))
+            .ok()
+    });
+
+    Ok((date, time))
+}
+
+/// Parse a Decimal from an XML attribute, returning ZERO for empty/missing.
+fn parse_attr_decimal(node: roxmltree::Node, attr: &str) -> Result<Decimal, LineError> {
+    let val = node.attribute(attr).unwrap_or("0");
+    if val.is_empty() || val == "--" || val == "-" {
+        return Ok(Decimal::ZERO);
+    }
+    Decimal::from_str(val)
+        .or_else(|_| Decimal::from_scientific(val))
+        .map_err(|_| LineError::new(DiagnosticKind::InvalidNumber, format!("Invalid decimal in {}: {}", attr, val)))
+}
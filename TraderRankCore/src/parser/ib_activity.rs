@@ -0,0 +1,297 @@
+use crate::models::{AssetClass, Instrument, OptionRight, Side, Trade};
+use super::csv_parser::{CsvParser, Row};
+use super::report::{DiagnosticKind, LineError, ParseReport};
+use crate::exchange_time::{self, Tz};
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// Execution timestamp formats seen in the Trades section ("2024-01-02, 09:35:12" is the default).
+const DATE_TIME_FORMATS: &[&str] = &[
+    "%Y-%m-%d, %H:%M:%S",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d;%H:%M:%S",
+    "%Y%m%d;%H%M%S",
+];
+
+/// Asset categories that are not trades of their own (currency conversions, fixed income).
+const SKIPPED_ASSET_CATEGORIES: &[&str] = &["Forex", "Bonds"];
+
+/// The first Activity Statement Trades section header
+/// ("Trades,Header,DataDiscriminator,Asset Category,...,Date/Time,Quantity,...").
+/// The section can sit anywhere in the statement, so every row is checked.
+pub fn find_trades_header(rows: &[Row]) -> Option<&Row> {
+    rows.iter().find(|row| {
+        is_section_row(row, "Header")
+            && ["Symbol", "Date/Time", "Quantity"]
+                .iter()
+                .all(|name| row.fields.iter().any(|f| f.trim() == *name))
+    })
+}
+
+fn is_section_row(row: &Row, kind: &str) -> bool {
+    is_row_of(row, "Trades", kind)
+}
+
+fn is_row_of(row: &Row, section: &str, kind: &str) -> bool {
+    row.fields.len() > 2 && row.fields[0].trim() == section && row.fields[1].trim() == kind
+}
+
+/// Contract details from the "Financial Instrument Information" section, by symbol.
+/// The section usually follows Trades, so it is read before any trade is parsed.
+fn read_instrument_info(rows: &[Row]) -> HashMap<String, Instrument> {
+    const SECTION: &str = "Financial Instrument Information";
+    let mut instruments = HashMap::new();
+    let mut header: Option<HashMap<String, usize>> = None;
+
+    for row in rows {
+        if is_row_of(row, SECTION, "Header") {
+            header = Some(header_columns(row));
+            continue;
+        }
+        if !is_row_of(row, SECTION, "Data") {
+            continue;
+        }
+        let Some(columns) = &header else {
+            continue;
+        };
+        let data = DataRow { columns, fields: &row.fields };
+        let symbol = data.get("Symbol");
+        let Some(asset_class) = AssetClass::from_code(data.get("Asset Category")) else {
+            continue;
+        };
+        if symbol.is_empty() {
+            continue;
+        }
+
+        let mut instrument = Instrument::of_class(asset_class);
+        if let Ok(multiplier) = CsvParser::parse_field_decimal(data.get("Multiplier"), "multiplier") {
+            if multiplier > Decimal::ZERO {
+                instrument.multiplier = multiplier;
+            }
+        }
+        if instrument.is_derivative() {
+            instrument.underlying = Some(data.get("Underlying")).filter(|u| !u.is_empty()).map(str::to_string);
+            instrument.expiry = NaiveDate::parse_from_str(data.get("Expiry"), "%Y-%m-%d").ok();
+            instrument.strike = CsvParser::parse_field_decimal(data.get("Strike"), "strike").ok();
+            instrument.right = OptionRight::from_code(data.get("Type"));
+        }
+        instruments.insert(symbol.to_string(), instrument);
+    }
+
+    instruments
+}
+
+/// The account from "Account Information,Data,Account,U1234567", if the statement has one.
+fn read_statement_account(rows: &[Row]) -> Option<String> {
+    rows.iter()
+        .find(|row| is_row_of(row, "Account Information", "Data") && row.fields[2].trim() == "Account")
+        .and_then(|row| row.fields.get(3))
+        .map(|a| a.trim().to_string())
+        .filter(|a| !a.is_empty())
+}
+
+fn header_columns(row: &Row) -> HashMap<String, usize> {
+    row.fields.iter()
+        .enumerate()
+        .map(|(idx, name)| (name.trim().to_string(), idx))
+        .collect()
+}
+
+/// Parse the Trades section of an IB Activity Statement CSV.
+///
+/// Columns are read by header name, and every "Trades,Header" row restarts the
+/// mapping because asset classes differ in their columns. SubTotal/Total rows,
+/// closed-lot detail and Forex/Bond conversions are skipped. When the statement
+/// includes per-execution rows, the per-order summary rows are dropped so fills
+/// are not counted twice. Execution times are wall-clock times in `timezone`,
+/// the statement's reporting timezone (US/Eastern unless changed in Client Portal).
+///
+/// Multipliers and contract terms come from the Financial Instrument Information
+/// section when the statement has one; otherwise option symbols such as
+/// "AAPL 19JAN24 190 C" are decoded and the class's default multiplier is used.
+pub fn parse_activity_rows(rows: &[Row], timezone: Tz, report: &mut ParseReport) {
+    let instruments = read_instrument_info(rows);
+    let statement_account = read_statement_account(rows);
+    let mut orders = Vec::new();
+    let mut executions = Vec::new();
+    let mut order_diagnostics = Vec::new();
+    let mut execution_diagnostics = Vec::new();
+    let mut header: Option<HashMap<String, usize>> = None;
+
+    for row in rows {
+        if is_section_row(row, "Header") {
+            header = Some(header_columns(row));
+            continue;
+        }
+        // SubTotal and Total rows fall through here
+        if !is_section_row(row, "Data") {
+            continue;
+        }
+        let Some(columns) = &header else {
+            continue;
+        };
+        let data = DataRow { columns, fields: &row.fields };
+
+        let discriminator = data.get("DataDiscriminator");
+        let (trades, diagnostics) = match discriminator {
+            "Order" | "" => (&mut orders, &mut order_diagnostics),
+            "Trade" | "Execution" => (&mut executions, &mut execution_diagnostics),
+            // ClosedLot rows describe the lots an order closed, not new fills
+            _ => continue,
+        };
+
+        let asset_category = data.get("Asset Category");
+        if SKIPPED_ASSET_CATEGORIES.contains(&asset_category) {
+            let e = LineError::new(DiagnosticKind::UnsupportedAsset, format!("{} trades are not imported", asset_category));
+            diagnostics.push(e.at(row));
+            continue;
+        }
+
+        // Bad lines are reported, not fatal
+        match parse_activity_trade(&data, &instruments, statement_account.as_deref(), timezone) {
+            Ok(trade) => trades.push(trade),
+            Err(e) => diagnostics.push(e.at(row)),
+        }
+    }
+
+    if executions.is_empty() && execution_diagnostics.is_empty() {
+        report.trades.extend(orders);
+        report.diagnostics.extend(order_diagnostics);
+    } else {
+        report.trades.extend(executions);
+        report.diagnostics.extend(execution_diagnostics);
+    }
+}
+
+/// A Trades data row with its fields looked up by the current header's column names.
+struct DataRow<'a> {
+    columns: &'a HashMap<String, usize>,
+    fields: &'a [String],
+}
+
+impl<'a> DataRow<'a> {
+    /// Trimmed field text, or "" when the column is absent.
+    fn get(&self, name: &str) -> &'a str {
+        self.columns.get(name)
+            .and_then(|&idx| self.fields.get(idx))
+            .map(|f| f.trim())
+            .unwrap_or("")
+    }
+}
+
+/// Parse one Trades data row.
+fn parse_activity_trade(
+    data: &DataRow,
+    instruments: &HashMap<String, Instrument>,
+    statement_account: Option<&str>,
+    timezone: Tz,
+) -> Result<Trade, LineError> {
+    let symbol = data.get("Symbol");
+    if symbol.is_empty() {
+        return Err(LineError::new(DiagnosticKind::MissingValue, "Missing symbol"));
+    }
+    let instrument = trade_instrument(data, symbol, instruments)?;
+
+    let date_str = data.get("Date/Time");
+    let naive = DATE_TIME_FORMATS
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(date_str, fmt).ok())
+        .ok_or_else(|| LineError::new(DiagnosticKind::InvalidTime, format!("Invalid date/time: {}", date_str)))?;
+    let time = exchange_time::local_to_utc(naive, timezone)
+        .ok_or_else(|| LineError::new(DiagnosticKind::InvalidTime, format!("Time {} does not exist in {}", date_str, timezone)))?;
+
+    // IB signs the quantity: negative for sells
+    let signed_quantity = CsvParser::parse_field_quantity(data.get("Quantity"))?;
+    let side = if signed_quantity < Decimal::ZERO { Side::Sell } else { Side::Buy };
+
+    let fill_price = CsvParser::parse_field_decimal(data.get("T. Price"), "price")?;
+
+    // Optional amounts may be blank or "-"; the first of the alternative column names that has a value wins
+    let optional = |names: &[&str], what: &str| -> Result<Option<Decimal>, LineError> {
+        match names.iter().map(|n| data.get(n)).find(|v| !v.is_empty() && *v != "-" && *v != "--") {
+            Some(v) => CsvParser::parse_field_decimal(v, what).map(Some),
+            None => Ok(None),
+        }
+    };
+
+    // Commissions and fees are reported as negative cash flows
+    let commission = optional(&["Comm/Fee", "Comm in USD", "Commission"], "commission")?
+        .unwrap_or_default()
+        .abs();
+    let proceeds = optional(&["Proceeds"], "proceeds")?
+        .unwrap_or(-signed_quantity * fill_price * instrument.multiplier);
+    let realized_pnl = optional(&["Realized P/L", "Realized P&L"], "realized P/L")?;
+
+    Ok(Trade {
+        symbol: symbol.to_string(),
+        side,
+        quantity: signed_quantity.abs(),
+        fill_price,
+        time,
+        // Net cash, like Flex netCash: proceeds less commission
+        net_amount: proceeds - commission,
+        commission,
+        realized_pnl,
+        instrument,
+        // Consolidated statements have an Account column; single-account ones name it once
+        account: Some(data.get("Account"))
+            .filter(|a| !a.is_empty())
+            .or(statement_account)
+            .map(str::to_string),
+        // Activity Statement CSVs carry no per-fill ID
+        execution_id: None,
+        closes_lot: None,
+        source: None,
+    })
+}
+
+/// The instrument of a Trades row: its Asset Category and Currency, with
+/// contract details from the instrument section or the option symbol.
+fn trade_instrument(data: &DataRow, symbol: &str, instruments: &HashMap<String, Instrument>) -> Result<Instrument, LineError> {
+    let category = data.get("Asset Category");
+    let asset_class = if category.is_empty() {
+        AssetClass::Stk
+    } else {
+        AssetClass::from_code(category).ok_or_else(|| {
+            LineError::new(DiagnosticKind::UnsupportedAsset, format!("{} trades are not imported", category))
+        })?
+    };
+
+    let mut instrument = match instruments.get(symbol) {
+        Some(known) if known.asset_class == asset_class => known.clone(),
+        _ => {
+            let mut instrument = Instrument::of_class(asset_class);
+            if asset_class == AssetClass::Opt {
+                decode_option_symbol(symbol, &mut instrument);
+            }
+            instrument
+        }
+    };
+    let currency = data.get("Currency");
+    if !currency.is_empty() {
+        instrument.currency = currency.to_uppercase();
+    }
+    Ok(instrument)
+}
+
+/// Fill in option terms from an IB option symbol, "UNDERLYING DDMONYY STRIKE C|P".
+/// Symbols in other shapes are left as they are.
+fn decode_option_symbol(symbol: &str, instrument: &mut Instrument) {
+    let parts: Vec<&str> = symbol.split_whitespace().collect();
+    let [underlying, expiry, strike, right] = parts[..] else {
+        return;
+    };
+    let (Ok(expiry), Ok(strike), Some(right)) = (
+        NaiveDate::parse_from_str(expiry, "%d%b%y"),
+        strike.parse::<Decimal>(),
+        OptionRight::from_code(right),
+    ) else {
+        return;
+    };
+
+    instrument.underlying = Some(underlying.to_string());
+    instrument.expiry = Some(expiry);
+    instrument.strike = Some(strike);
+    instrument.right = Some(right);
+}
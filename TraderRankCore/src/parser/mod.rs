@@ -0,0 +1,23 @@
+pub mod columns;
+pub mod csv_parser;
+pub mod excel_reader;
+pub mod flex_xml;
+pub mod ib_activity;
+pub mod profile;
+pub mod report;
+
+pub use csv_parser::CsvParser;
+pub use profile::ImportProfile;
+pub use report::{Diagnostic, DiagnosticKind, ParseReport};
+
+use std::path::Path;
+
+/// True if the path is a file type the parser reads: CSV, a spreadsheet or Flex XML.
+pub fn is_trade_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("csv"))
+        .unwrap_or(false)
+        || excel_reader::is_workbook(path)
+        || flex_xml::is_xml(path)
+}
@@ -0,0 +1,379 @@
+use crate::models::{Side, Trade};
+use super::columns;
+use super::csv_parser::{CsvParser, Row};
+use super::report::{DiagnosticKind, LineError, ParseReport};
+use anyhow::{anyhow, Context, Result};
+use crate::exchange_time;
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Number of leading rows searched for a profile's header row.
+const HEADER_SEARCH_ROWS: usize = 20;
+
+/// A user-defined import format for a broker export, loaded from a TOML or JSON file.
+///
+/// ```toml
+/// name = "Webull"
+/// header_signature = ["Symbol", "Side", "Filled", "Avg Price", "Filled Time"]
+/// datetime_format = "%m/%d/%Y %H:%M:%S"
+/// timezone = "America/New_York"
+///
+/// [columns]
+/// symbol = "Symbol"
+/// side = "Side"
+/// quantity = "Filled"
+/// price = "Avg Price"
+/// time = "Filled Time"
+///
+/// [sides]
+/// buy = ["BUY", "BOT"]
+/// sell = ["SELL", "SLD", "SHORT"]
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ImportProfile {
+    pub name: String,
+    /// Header cells that must all appear in one row for a file to use this profile.
+    /// Data rows follow that header row; anything above it is ignored.
+    pub header_signature: Vec<String>,
+    pub columns: ProfileColumns,
+    /// Side values; when omitted the built-in Buy/Long/Sell/Short names are used
+    #[serde(default)]
+    pub sides: Option<SideVocabulary>,
+    /// chrono format of the time column, or of "date time" when a separate date column is mapped.
+    /// Date-only formats are read as midnight.
+    #[serde(default = "default_datetime_format")]
+    pub datetime_format: String,
+    /// IANA timezone the timestamps are written in; when omitted, the parser's
+    /// source timezone (America/New_York unless `CsvParser::with_timezone` sets another)
+    #[serde(default)]
+    pub timezone: Option<Tz>,
+    #[serde(default)]
+    pub quantity_sign: QuantitySign,
+    #[serde(default)]
+    pub commission_sign: CommissionSign,
+}
+
+/// Header names of the columns a profile reads.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileColumns {
+    pub symbol: String,
+    /// May be omitted when `quantity_sign = "signed"`
+    pub side: Option<String>,
+    pub quantity: String,
+    pub price: String,
+    pub time: String,
+    /// Separate date column, joined with the time column as "date time"
+    pub date: Option<String>,
+    /// Defaults to quantity × price × multiplier when omitted
+    pub net_amount: Option<String>,
+    /// Defaults to zero when omitted
+    pub commission: Option<String>,
+    /// Asset class ("STK", "OPT", "FUT", "Stocks", ...); every row is a stock when omitted
+    pub asset_class: Option<String>,
+    /// Contract multiplier; defaults to 100 for options and 1 otherwise
+    pub multiplier: Option<String>,
+    /// Defaults to USD when omitted
+    pub currency: Option<String>,
+    /// Broker account ID
+    pub account: Option<String>,
+    /// ID unique to each fill (not an order ID), used to drop the same fill imported twice
+    pub execution_id: Option<String>,
+    /// Execution ID of the opening fill a closing fill sells from, for specific-ID lot matching
+    pub closes_lot: Option<String>,
+}
+
+/// Broker-specific side values, matched case-insensitively.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SideVocabulary {
+    pub buy: Vec<String>,
+    pub sell: Vec<String>,
+}
+
+/// How the quantity column encodes direction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuantitySign {
+    /// Quantities are unsigned; the side column gives the direction
+    #[default]
+    Absolute,
+    /// Negative quantities are sells; a side column is optional
+    Signed,
+}
+
+/// How the commission column is written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommissionSign {
+    /// Costs are positive numbers
+    #[default]
+    Positive,
+    /// Costs are negative numbers (cash-flow convention) and rebates positive
+    Negative,
+}
+
+fn default_datetime_format() -> String {
+    "%Y-%m-%d %H:%M:%S".to_string()
+}
+
+/// Column indexes of a profile, resolved against a file's header row.
+struct ProfileIndexes {
+    symbol: usize,
+    side: Option<usize>,
+    quantity: usize,
+    price: usize,
+    time: usize,
+    date: Option<usize>,
+    net_amount: Option<usize>,
+    commission: Option<usize>,
+    asset_class: Option<usize>,
+    multiplier: Option<usize>,
+    currency: Option<usize>,
+    account: Option<usize>,
+    execution_id: Option<usize>,
+    closes_lot: Option<usize>,
+}
+
+impl ImportProfile {
+    /// Load a profile from a `.toml` or `.json` file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read import profile {:?}", path))?;
+
+        let is_json = path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.eq_ignore_ascii_case("json"))
+            .unwrap_or(false);
+        let profile: ImportProfile = if is_json {
+            serde_json::from_str(&text)
+                .with_context(|| format!("Invalid import profile {:?}", path))?
+        } else {
+            toml::from_str(&text)
+                .with_context(|| format!("Invalid import profile {:?}", path))?
+        };
+
+        profile.validate()
+            .with_context(|| format!("Invalid import profile {:?}", path))?;
+        Ok(profile)
+    }
+
+    /// Load every `.toml` and `.json` profile in a directory, in file name order.
+    /// A missing directory has no profiles.
+    pub fn load_dir(dir: &Path) -> Result<Vec<Self>> {
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+            .with_context(|| format!("Failed to read profiles directory {:?}", dir))?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| {
+                p.extension()
+                    .and_then(|ext| ext.to_str())
+                    .map(|ext| ext.eq_ignore_ascii_case("toml") || ext.eq_ignore_ascii_case("json"))
+                    .unwrap_or(false)
+            })
+            .collect();
+        paths.sort();
+
+        paths.iter().map(|p| Self::load(p)).collect()
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.header_signature.is_empty() {
+            return Err(anyhow!("header_signature must list at least one column"));
+        }
+        if self.columns.side.is_none() && self.quantity_sign != QuantitySign::Signed {
+            return Err(anyhow!("columns.side is required unless quantity_sign = \"signed\""));
+        }
+        Ok(())
+    }
+
+    /// Index of the first row (within the leading rows) that contains the whole header signature.
+    pub fn find_header(&self, rows: &[Row]) -> Option<usize> {
+        rows.iter()
+            .take(HEADER_SEARCH_ROWS)
+            .position(|row| {
+                self.header_signature
+                    .iter()
+                    .all(|name| columns::find_named(&row.fields, name).is_some())
+            })
+    }
+
+    /// Parse the rows following the profile's header row, adding trades and diagnostics to the report.
+    /// Times are read in the profile's timezone, else in `source_tz`.
+    /// Rows without the header signature are one diagnostic at the first row.
+    pub fn parse_rows(&self, rows: &[Row], source_tz: Tz, report: &mut ParseReport) {
+        let Some(header_idx) = self.find_header(rows) else {
+            let error = LineError::new(
+                DiagnosticKind::MissingColumns,
+                format!(
+                    "Import profile '{}' skipped: none of the first {} rows has its header signature ({})",
+                    self.name, HEADER_SEARCH_ROWS, self.header_signature.join(", "),
+                ),
+            );
+            report.diagnostics.push(match rows.first() {
+                Some(row) => error.at(row),
+                None => error.at_line(1, ""),
+            });
+            return;
+        };
+        let header = &rows[header_idx];
+        let indexes = match self.resolve_columns(&header.fields) {
+            Ok(indexes) => indexes,
+            Err(e) => {
+                report.diagnostics.push(e.at(header));
+                return;
+            }
+        };
+
+        let timezone = self.timezone.unwrap_or(source_tz);
+        for row in &rows[header_idx + 1..] {
+            if row.fields.iter().all(|f| f.trim().is_empty()) {
+                continue;
+            }
+
+            match self.parse_fields(&indexes, &row.fields, timezone) {
+                Ok(trade) => report.trades.push(trade),
+                Err(e) => report.diagnostics.push(e.at(row)),
+            }
+        }
+    }
+
+    fn resolve_columns(&self, header: &[String]) -> Result<ProfileIndexes, LineError> {
+        let mut missing = Vec::new();
+        let mut required = |name: &str| {
+            let idx = columns::find_named(header, name);
+            if idx.is_none() {
+                missing.push(name.to_string());
+            }
+            idx.unwrap_or_default()
+        };
+
+        let symbol = required(&self.columns.symbol);
+        let quantity = required(&self.columns.quantity);
+        let price = required(&self.columns.price);
+        let time = required(&self.columns.time);
+        let side = self.columns.side.as_deref().map(&mut required);
+        let date = self.columns.date.as_deref().map(&mut required);
+        let net_amount = self.columns.net_amount.as_deref().map(&mut required);
+        let commission = self.columns.commission.as_deref().map(&mut required);
+        let asset_class = self.columns.asset_class.as_deref().map(&mut required);
+        let multiplier = self.columns.multiplier.as_deref().map(&mut required);
+        let currency = self.columns.currency.as_deref().map(&mut required);
+        let account = self.columns.account.as_deref().map(&mut required);
+        let execution_id = self.columns.execution_id.as_deref().map(&mut required);
+        let closes_lot = self.columns.closes_lot.as_deref().map(&mut required);
+
+        if !missing.is_empty() {
+            return Err(LineError::new(
+                DiagnosticKind::MissingColumns,
+                format!("Import profile '{}' maps columns missing from the header: {}", self.name, missing.join(", ")),
+            ));
+        }
+
+        Ok(ProfileIndexes { symbol, side, quantity, price, time, date, net_amount, commission, asset_class, multiplier, currency, account, execution_id, closes_lot })
+    }
+
+    fn parse_fields(&self, idx: &ProfileIndexes, parts: &[String], timezone: Tz) -> Result<Trade, LineError> {
+        let field = |i: usize| parts.get(i).map(|p| p.trim()).unwrap_or("");
+
+        let symbol = field(idx.symbol);
+        if symbol.is_empty() {
+            return Err(LineError::new(DiagnosticKind::MissingValue, "Missing symbol"));
+        }
+
+        let signed_quantity = CsvParser::parse_field_quantity(field(idx.quantity))?;
+        let quantity = signed_quantity.abs();
+
+        let side = match idx.side {
+            Some(i) => self.parse_side(field(i))?,
+            None if signed_quantity < Decimal::ZERO => Side::Sell,
+            None => Side::Buy,
+        };
+
+        let fill_price = CsvParser::parse_field_decimal(field(idx.price), "fill price")?;
+
+        let time_str = match idx.date {
+            Some(i) => format!("{} {}", field(i), field(idx.time)),
+            None => field(idx.time).to_string(),
+        };
+        let time = self.parse_time(&time_str, timezone)?;
+
+        let optional = |i: Option<usize>| i.map(field).unwrap_or("");
+        let instrument = CsvParser::parse_field_instrument(
+            optional(idx.asset_class),
+            optional(idx.multiplier),
+            optional(idx.currency),
+        )?;
+
+        let net_amount = match idx.net_amount.map(field).filter(|s| !s.is_empty()) {
+            Some(s) => CsvParser::parse_field_decimal(s, "net amount")?.abs(),
+            None => quantity * fill_price * instrument.multiplier,
+        };
+
+        let commission = match idx.commission.map(field).filter(|s| !s.is_empty()) {
+            Some(s) => {
+                let value = CsvParser::parse_field_decimal(s, "commission")?;
+                match self.commission_sign {
+                    CommissionSign::Positive => value,
+                    CommissionSign::Negative => -value,
+                }
+            }
+            None => Decimal::ZERO,
+        };
+
+        Ok(Trade {
+            symbol: symbol.to_string(),
+            side,
+            quantity,
+            fill_price,
+            time,
+            net_amount,
+            commission,
+            realized_pnl: None,
+            instrument,
+            account: Some(optional(idx.account)).filter(|a| !a.is_empty()).map(str::to_string),
+            execution_id: Some(optional(idx.execution_id)).filter(|id| !id.is_empty()).map(str::to_string),
+            closes_lot: Some(optional(idx.closes_lot)).filter(|id| !id.is_empty()).map(str::to_string),
+            source: None,
+        })
+    }
+
+    fn parse_side(&self, value: &str) -> Result<Side, LineError> {
+        let Some(sides) = &self.sides else {
+            return CsvParser::parse_field_side(value);
+        };
+
+        if sides.buy.iter().any(|v| v.eq_ignore_ascii_case(value)) {
+            Ok(Side::Buy)
+        } else if sides.sell.iter().any(|v| v.eq_ignore_ascii_case(value)) {
+            Ok(Side::Sell)
+        } else {
+            Err(LineError::new(DiagnosticKind::InvalidSide, format!("Invalid side: {}", value)))
+        }
+    }
+
+    /// Parse a local timestamp in the profile's format and `timezone` into UTC.
+    fn parse_time(&self, value: &str, timezone: Tz) -> Result<chrono::DateTime<Utc>, LineError> {
+        let naive = NaiveDateTime::parse_from_str(value, &self.datetime_format)
+            .or_else(|_| {
+                NaiveDate::parse_from_str(value, &self.datetime_format)
+                    .map(|d| d.and_hms_opt(0, 0, 0).unwrap())
+            })
+            .map_err(|_| LineError::new(
+                DiagnosticKind::InvalidTime,
+                format!("Invalid time '{}' (expected format {})", value, self.datetime_format),
+            ))?;
+
+        // Ambiguous times in the DST fall-back hour take the earlier instant
+        exchange_time::local_to_utc(naive, timezone)
+            .ok_or_else(|| LineError::new(DiagnosticKind::InvalidTime, format!("Time {} does not exist in {}", value, timezone)))
+    }
+}
@@ -0,0 +1,144 @@
+use crate::models::Trade;
+use super::csv_parser::{FileFormat, Row};
+use std::fmt;
+
+/// Why a line of an input file produced no trade.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiagnosticKind {
+    /// The header lacks columns the format requires, so none of its rows could be read
+    MissingColumns,
+    /// The row has fewer fields than the format requires
+    TooFewFields,
+    /// A required field is empty
+    MissingValue,
+    InvalidSide,
+    InvalidNumber,
+    InvalidTime,
+    /// A fill in an asset class that is not imported (e.g. Forex conversions)
+    UnsupportedAsset,
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            DiagnosticKind::MissingColumns => "missing columns",
+            DiagnosticKind::TooFewFields => "too few fields",
+            DiagnosticKind::MissingValue => "missing value",
+            DiagnosticKind::InvalidSide => "invalid side",
+            DiagnosticKind::InvalidNumber => "invalid number",
+            DiagnosticKind::InvalidTime => "invalid time",
+            DiagnosticKind::UnsupportedAsset => "unsupported asset",
+        };
+        f.write_str(text)
+    }
+}
+
+/// Failure to turn one record into a trade, before its location is attached.
+#[derive(Debug, Clone)]
+pub struct LineError {
+    pub kind: DiagnosticKind,
+    pub message: String,
+}
+
+impl LineError {
+    pub fn new(kind: DiagnosticKind, message: impl Into<String>) -> Self {
+        Self { kind, message: message.into() }
+    }
+
+    /// Attach the location and raw text of the row that failed.
+    pub fn at(self, row: &Row) -> Diagnostic {
+        self.at_line(row.line, &row.raw)
+    }
+
+    /// Attach a location and raw text for records that are not rows (e.g. XML elements).
+    pub fn at_line(self, line: usize, raw: &str) -> Diagnostic {
+        Diagnostic {
+            line,
+            sheet: None,
+            raw: raw.to_string(),
+            kind: self.kind,
+            message: self.message,
+        }
+    }
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for LineError {}
+
+/// A line that was dropped while parsing a file.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    /// 1-based line (or sheet row, or XML line) the record starts on
+    pub line: usize,
+    /// Worksheet name, for spreadsheets
+    pub sheet: Option<String>,
+    /// The record as it appears in the file
+    pub raw: String,
+    pub kind: DiagnosticKind,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.sheet {
+            Some(sheet) => write!(f, "[{}] line {}: {}: {}", sheet, self.line, self.kind, self.message),
+            None => write!(f, "line {}: {}: {}", self.line, self.kind, self.message),
+        }
+    }
+}
+
+/// The detected format of a file (or worksheet) and the evidence for it.
+#[derive(Debug, Clone)]
+pub struct Detection {
+    pub format: FileFormat,
+    pub reasons: Vec<String>,
+}
+
+/// Everything `CsvParser::parse_file` learned about one file:
+/// the trades it yielded, the lines it dropped and why it was read as it was.
+#[derive(Debug, Clone)]
+pub struct ParseReport {
+    pub file_name: String,
+    /// For workbooks, the format of the first recognised sheet
+    pub format: FileFormat,
+    pub format_reasons: Vec<String>,
+    pub trades: Vec<Trade>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ParseReport {
+    pub fn new(file_name: &str, detection: Detection) -> Self {
+        Self {
+            file_name: file_name.to_string(),
+            format: detection.format,
+            format_reasons: detection.reasons,
+            trades: Vec::new(),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    /// True if the file was not read as trades (positions snapshot or unrecognised).
+    pub fn is_skipped(&self) -> bool {
+        matches!(self.format, FileFormat::Positions | FileFormat::Unknown)
+    }
+
+    /// One-line outcome, e.g. "42 trades, 2 lines dropped (IB Activity Statement)".
+    pub fn summary(&self) -> String {
+        if self.is_skipped() {
+            let reason = self.format_reasons.first().map(String::as_str).unwrap_or("no reason recorded");
+            return format!("skipped ({}: {})", self.format, reason);
+        }
+
+        let mut text = format!("{} trades", self.trades.len());
+        if !self.diagnostics.is_empty() {
+            text.push_str(&format!(", {} lines dropped", self.diagnostics.len()));
+        }
+        text.push_str(&format!(" ({})", self.format));
+        text
+    }
+}
@@ -0,0 +1,201 @@
+use super::schema::{self, DocumentSchema, Migration};
+use super::{Annotations, ProcessedData, Store};
+use crate::analytics::TradingAnalytics;
+use crate::models::{MatchedTrade, Trade, TradingSummary};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// `processed_data.json`.
+pub const PROCESSED_DATA: DocumentSchema = DocumentSchema {
+    name: "processed data",
+    migrations: &[Migration {
+        to: 1,
+        description: "fill in fields older files left out and rebuild missing monthly summaries",
+        apply: processed_data_v1,
+    }],
+};
+
+/// `trades.json`, the trade ledger.
+pub const TRADES: DocumentSchema = DocumentSchema {
+    name: "trade ledger",
+    migrations: &[Migration { to: 1, description: "wrap the list of fills in an object", apply: |value| wrap_list(value, "trades") }],
+};
+
+/// `matched_trades.json`.
+pub const MATCHED_TRADES: DocumentSchema = DocumentSchema {
+    name: "matched trades",
+    migrations: &[Migration { to: 1, description: "wrap the list of round trips in an object", apply: |value| wrap_list(value, "matched_trades") }],
+};
+
+/// `annotations.json`.
+pub const ANNOTATIONS: DocumentSchema = DocumentSchema {
+    name: "annotations",
+    migrations: &[Migration { to: 1, description: "record the schema version", apply: |_| Ok(()) }],
+};
+
+/// The trade ledger document: `C` is a slice when saving, a `Vec` when loading.
+#[derive(Serialize, Deserialize)]
+struct Trades<C> {
+    trades: C,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MatchedTrades<C> {
+    matched_trades: C,
+}
+
+/// Pretty-printed JSON files in the data directory: `processed_data.json`,
+/// `trades.json`, `matched_trades.json`, `annotations.json`, and a summary
+/// snapshot per run in `Summaries/`. Each file but the snapshots records its
+/// schema version and is upgraded when an older one is loaded (see `schema`).
+pub struct JsonStore {
+    data_dir: PathBuf,
+}
+
+impl JsonStore {
+    pub fn new(data_dir: PathBuf) -> Result<Self> {
+        if !data_dir.exists() {
+            fs::create_dir_all(&data_dir)
+                .with_context(|| format!("Failed to create data directory: {:?}", data_dir))?;
+        }
+
+        let summaries_dir = data_dir.join("Summaries");
+        if !summaries_dir.exists() {
+            fs::create_dir_all(&summaries_dir)
+                .with_context(|| format!("Failed to create summaries directory: {:?}", summaries_dir))?;
+        }
+
+        Ok(Self { data_dir })
+    }
+
+    fn get_processed_data_path(&self) -> PathBuf {
+        self.data_dir.join("processed_data.json")
+    }
+
+    fn get_trades_path(&self) -> PathBuf {
+        self.data_dir.join("trades.json")
+    }
+}
+
+impl Store for JsonStore {
+    fn load_processed_data(&self) -> Result<Option<ProcessedData>> {
+        PROCESSED_DATA.load(&self.get_processed_data_path())
+    }
+
+    fn save_processed_data(&self, data: &ProcessedData) -> Result<()> {
+        PROCESSED_DATA.save(&self.get_processed_data_path(), data)
+    }
+
+    fn load_trades(&self) -> Result<Option<Vec<Trade>>> {
+        let ledger: Option<Trades<Vec<Trade>>> = TRADES.load(&self.get_trades_path())?;
+        Ok(ledger.map(|l| l.trades))
+    }
+
+    fn save_trades(&self, trades: &[Trade]) -> Result<()> {
+        TRADES.save(&self.get_trades_path(), &Trades { trades })
+    }
+
+    fn has_trade_ledger(&self) -> Result<bool> {
+        Ok(self.get_trades_path().exists())
+    }
+
+    fn load_matched_trades(&self) -> Result<Vec<MatchedTrade>> {
+        let matched: Option<MatchedTrades<Vec<MatchedTrade>>> = MATCHED_TRADES.load(&self.data_dir.join("matched_trades.json"))?;
+        Ok(matched.map(|m| m.matched_trades).unwrap_or_default())
+    }
+
+    fn save_matched_trades(&self, matched: &[MatchedTrade]) -> Result<()> {
+        MATCHED_TRADES.save(&self.data_dir.join("matched_trades.json"), &MatchedTrades { matched_trades: matched })
+    }
+
+    fn load_annotations(&self) -> Result<Annotations> {
+        Ok(ANNOTATIONS.load(&self.data_dir.join("annotations.json"))?.unwrap_or_default())
+    }
+
+    fn save_annotations(&self, annotations: &Annotations) -> Result<()> {
+        ANNOTATIONS.save(&self.data_dir.join("annotations.json"), annotations)
+    }
+
+    fn clear(&self) -> Result<()> {
+        for path in [self.get_trades_path(), self.get_processed_data_path(), self.data_dir.join("matched_trades.json")] {
+            if path.exists() {
+                fs::remove_file(&path).with_context(|| format!("Failed to remove {:?}", path))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn save_daily_summary(&self, summary: &TradingSummary) -> Result<()> {
+        let summaries_dir = self.data_dir.join("Summaries");
+        let file_name = format!("summary_{}.json", Utc::now().format("%Y%m%d_%H%M%S"));
+        write_json(&summaries_dir.join(file_name), summary, "trading summary")
+    }
+}
+
+fn write_json<T: Serialize + ?Sized>(file_path: &Path, value: &T, what: &str) -> Result<()> {
+    let json_str = serde_json::to_string_pretty(value)
+        .with_context(|| format!("Failed to serialize {}", what))?;
+
+    fs::write(file_path, json_str)
+        .with_context(|| format!("Failed to write {} to {:?}", what, file_path))?;
+
+    Ok(())
+}
+
+/// Version 0 files listed processed files by name only, so they are dropped and
+/// the files read once more; the timezone, account summaries, swing trades and
+/// lot method were added over time, and the oldest summaries lack months.
+fn processed_data_v1(value: &mut Value) -> Result<()> {
+    let data = schema::object_mut(value)?;
+    data.remove("processed_files");
+    data.entry("sources").or_insert_with(|| json!([]));
+    data.entry("exchange_timezone").or_insert(Value::Null);
+    data.entry("account_summaries").or_insert_with(|| json!({}));
+    data.entry("carry_positions").or_insert(Value::Bool(false));
+    data.entry("lot_method").or_insert_with(|| json!("average"));
+
+    if let Some(summary) = data.get_mut("summary") {
+        with_monthly_summaries(summary)?;
+    }
+    if let Some(Value::Object(accounts)) = data.get_mut("account_summaries") {
+        for summary in accounts.values_mut() {
+            with_monthly_summaries(summary)?;
+        }
+    }
+    Ok(())
+}
+
+/// Rebuild the monthly summaries and best/worst months of a summary cached
+/// before they existed.
+fn with_monthly_summaries(value: &mut Value) -> Result<()> {
+    let mut summary: TradingSummary = serde_json::from_value(value.clone())
+        .context("Failed to deserialize cached summary")?;
+    if !summary.monthly_summaries.is_empty() || summary.daily_summaries.is_empty() {
+        return Ok(());
+    }
+
+    summary.monthly_summaries = TradingAnalytics::regenerate_monthly_summaries(&summary.daily_summaries);
+    summary.best_month = summary.monthly_summaries
+        .iter()
+        .max_by_key(|m| m.realized_pnl)
+        .map(|m| ((m.year, m.month), m.realized_pnl));
+    summary.worst_month = summary.monthly_summaries
+        .iter()
+        .min_by_key(|m| m.realized_pnl)
+        .map(|m| ((m.year, m.month), m.realized_pnl));
+
+    *value = serde_json::to_value(&summary)?;
+    Ok(())
+}
+
+/// Version 0 lists were bare JSON arrays, which have nowhere to put a version.
+fn wrap_list(value: &mut Value, field: &str) -> Result<()> {
+    if value.is_array() {
+        *value = json!({ field: value.take() });
+    }
+    Ok(())
+}
@@ -0,0 +1,256 @@
+//! Where imported trades and analysis results are kept between runs.
+//!
+//! `Store` is implemented by `JsonStore`, plain JSON files in the data
+//! directory, and `SqliteStore`, one embedded SQLite database with indexed
+//! trade queries. Both hold the trade ledger, the imported source files and
+//! cached analysis, matched trades, and annotations (exclusions and R configs).
+
+pub mod json;
+pub mod schema;
+pub mod sources;
+pub mod sqlite;
+
+pub use json::JsonStore;
+pub use sources::{file_name, scan_source_files, scan_sources, source_files, SourceFile, SourceScan, SourceStatus};
+pub use sqlite::SqliteStore;
+
+use crate::exchange_time::Tz;
+use crate::models::{MatchedTrade, Trade, TradingSummary};
+use crate::trade_matcher::{LotMethod, MatchOptions};
+use anyhow::{bail, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The cached analysis of every imported source file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessedData {
+    pub last_processed: DateTime<Utc>,
+    /// Imported files by content
+    pub sources: Vec<SourceFile>,
+    pub summary: TradingSummary,
+    /// Timezone the summary's days and hours are bucketed in (`None` when cached before it was recorded)
+    pub exchange_timezone: Option<Tz>,
+    /// The same analysis for each account on its own
+    pub account_summaries: BTreeMap<String, TradingSummary>,
+    /// Whether positions were carried across days
+    pub carry_positions: bool,
+    /// Lot-matching method
+    pub lot_method: LotMethod,
+}
+
+/// The R unit (dollar risk per trade) for the week starting on `week_start`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RConfig {
+    pub week_start: NaiveDate,
+    pub r_value: Decimal,
+}
+
+/// User annotations on the analysis.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Annotations {
+    /// Excluded days and trades, key -> reason (may be empty).
+    /// Keys are "day:YYYY-MM-DD" or "trade:SYMBOL:EXIT_TIME" in exchange-local time.
+    #[serde(default)]
+    pub exclusions: BTreeMap<String, String>,
+    #[serde(default)]
+    pub r_configs: Vec<RConfig>,
+}
+
+/// Which fills a trade query returns; `None` fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct TradeFilter {
+    /// First instant included
+    pub from: Option<DateTime<Utc>>,
+    /// First instant no longer included
+    pub until: Option<DateTime<Utc>>,
+    pub symbol: Option<String>,
+    pub account: Option<String>,
+}
+
+impl TradeFilter {
+    pub fn matches(&self, trade: &Trade) -> bool {
+        self.from.is_none_or(|from| trade.time >= from)
+            && self.until.is_none_or(|until| trade.time < until)
+            && self.symbol.as_deref().is_none_or(|symbol| trade.symbol == symbol)
+            && self.account.as_deref().is_none_or(|account| trade.in_account(account))
+    }
+}
+
+/// A place to keep trades and analysis between runs.
+pub trait Store {
+    /// The cached analysis, or `None` before the first import.
+    fn load_processed_data(&self) -> Result<Option<ProcessedData>>;
+
+    fn save_processed_data(&self, data: &ProcessedData) -> Result<()>;
+
+    /// Every fill imported so far, deduplicated, in time order; `None` before the first import.
+    fn load_trades(&self) -> Result<Option<Vec<Trade>>>;
+
+    /// Replace the trade ledger.
+    fn save_trades(&self, trades: &[Trade]) -> Result<()>;
+
+    /// Ledger fills that pass `filter`, in time order.
+    fn query_trades(&self, filter: &TradeFilter) -> Result<Vec<Trade>> {
+        let trades = self.load_trades()?.unwrap_or_default();
+        Ok(trades.into_iter().filter(|t| filter.matches(t)).collect())
+    }
+
+    /// Round trips of the last analysis, in the order they were saved.
+    fn load_matched_trades(&self) -> Result<Vec<MatchedTrade>>;
+
+    fn save_matched_trades(&self, matched: &[MatchedTrade]) -> Result<()>;
+
+    fn load_annotations(&self) -> Result<Annotations>;
+
+    fn save_annotations(&self, annotations: &Annotations) -> Result<()>;
+
+    /// Remove the trade ledger, source file records, cached analysis and matched
+    /// trades, as before the first import. Annotations are kept.
+    fn clear(&self) -> Result<()>;
+
+    /// Keep a copy of one run's summary. Only the JSON store writes these;
+    /// the latest summary is always in the processed data.
+    fn save_daily_summary(&self, _summary: &TradingSummary) -> Result<()> {
+        Ok(())
+    }
+
+    /// True once a trade ledger has been saved.
+    fn has_trade_ledger(&self) -> Result<bool> {
+        Ok(self.load_trades()?.is_some())
+    }
+
+    /// Imported source files, in the order they were first imported.
+    fn load_sources(&self) -> Result<Vec<SourceFile>> {
+        Ok(self.load_processed_data()?.map(|d| d.sources).unwrap_or_default())
+    }
+
+    /// Every trade file in `source_dir` and whether it needs importing. Without
+    /// a ledger (a store from before it was kept) every file is new, so the
+    /// ledger is rebuilt from the sources.
+    fn scan_sources(&self, source_dir: &Path) -> Result<Vec<SourceScan>> {
+        self.scan_source_files(source_files(source_dir)?)
+    }
+
+    /// `scan_sources` for trade files gathered from more than one directory.
+    fn scan_source_files(&self, paths: Vec<PathBuf>) -> Result<Vec<SourceScan>> {
+        let known = if self.has_trade_ledger()? { self.load_sources()? } else { Vec::new() };
+        scan_source_files(paths, &known)
+    }
+
+    /// Record `files` as imported, replacing earlier records of the same
+    /// names, with the analysis of the updated ledger.
+    fn mark_files_processed(
+        &self,
+        files: Vec<SourceFile>,
+        summary: TradingSummary,
+        account_summaries: BTreeMap<String, TradingSummary>,
+        match_options: MatchOptions,
+    ) -> Result<()> {
+        let mut sources = self.load_sources()?;
+
+        for file in files {
+            match sources.iter_mut().find(|s| s.name == file.name) {
+                Some(existing) => *existing = file,
+                None => sources.push(file),
+            }
+        }
+
+        let data = ProcessedData {
+            last_processed: Utc::now(),
+            sources,
+            summary,
+            exchange_timezone: Some(match_options.exchange_tz),
+            account_summaries,
+            carry_positions: match_options.carry_positions,
+            lot_method: match_options.lot_method,
+        };
+
+        self.save_processed_data(&data)
+    }
+
+    /// Stop tracking the source named `name`, and the copies of it that added no
+    /// fills, and drop the fills it added to the ledger, so it is imported afresh
+    /// next time. Fills merged before sources were recorded on them stay. Returns
+    /// the record, if there was one; the cached analysis is left as it was and
+    /// should be rebuilt.
+    fn forget_source(&self, name: &str) -> Result<Option<SourceFile>> {
+        let Some(mut data) = self.load_processed_data()? else {
+            return Ok(None);
+        };
+        let Some(index) = data.sources.iter().position(|s| s.name == name) else {
+            return Ok(None);
+        };
+        let removed = data.sources.remove(index);
+        data.sources.retain(|s| s.hash != removed.hash || s.trades > 0);
+        self.save_processed_data(&data)?;
+
+        if let Some(mut trades) = self.load_trades()? {
+            trades.retain(|t| t.source.as_deref() != Some(name));
+            self.save_trades(&trades)?;
+        }
+        Ok(Some(removed))
+    }
+}
+
+/// Storage backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoreKind {
+    Json,
+    Sqlite,
+}
+
+impl StoreKind {
+    pub const ALL: [StoreKind; 2] = [StoreKind::Json, StoreKind::Sqlite];
+
+    /// Parse "json" or "sqlite" (case-insensitive).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "json" => Some(StoreKind::Json),
+            "sqlite" | "sqlite3" | "db" => Some(StoreKind::Sqlite),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StoreKind::Json => "json",
+            StoreKind::Sqlite => "sqlite",
+        }
+    }
+
+    /// SQLite when the data directory already has a database, otherwise JSON.
+    pub fn detect(data_dir: &Path) -> Self {
+        if data_dir.join(SqliteStore::FILE_NAME).exists() {
+            StoreKind::Sqlite
+        } else {
+            StoreKind::Json
+        }
+    }
+
+    /// Open this kind of store in `data_dir`, creating it if needed.
+    pub fn open(&self, data_dir: &Path) -> Result<Box<dyn Store>> {
+        if !data_dir.exists() {
+            fs::create_dir_all(data_dir)?;
+        } else if !data_dir.is_dir() {
+            bail!("{:?} is not a directory", data_dir);
+        }
+        Ok(match self {
+            StoreKind::Json => Box::new(JsonStore::new(data_dir.to_path_buf())?),
+            StoreKind::Sqlite => Box::new(SqliteStore::open(&data_dir.join(SqliteStore::FILE_NAME))?),
+        })
+    }
+}
+
+impl fmt::Display for StoreKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            StoreKind::Json => "JSON files",
+            StoreKind::Sqlite => "SQLite",
+        })
+    }
+}
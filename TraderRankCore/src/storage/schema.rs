@@ -0,0 +1,185 @@
+//! Schema versions of persisted JSON documents and the migrations that bring
+//! older documents up to date.
+//!
+//! Every document is a JSON object with a `schema_version` field. Documents
+//! written before versions were recorded have none and are version 0. On load,
+//! an older document is upgraded one version at a time; the original is first
+//! copied next to it (`processed_data.json.v0.bak`) and the upgraded document
+//! is written back. A document from a newer TraderRank is an error rather than
+//! being read, or overwritten, as if it were current.
+
+use anyhow::{bail, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the version field in every document.
+pub const VERSION_FIELD: &str = "schema_version";
+
+/// One upgrade step, from version `to - 1` to `to`.
+pub struct Migration {
+    pub to: u32,
+    pub description: &'static str,
+    pub apply: fn(&mut Value) -> Result<()>,
+}
+
+/// A kind of persisted document: what it holds and how to upgrade it.
+pub struct DocumentSchema {
+    /// What the document holds, for messages ("processed data")
+    pub name: &'static str,
+    /// Upgrade steps in order; the last one's version is the current version
+    pub migrations: &'static [Migration],
+}
+
+impl DocumentSchema {
+    /// The version written by this build.
+    pub fn current_version(&self) -> u32 {
+        self.migrations.last().map_or(0, |m| m.to)
+    }
+
+    /// Upgrade `value` to the current version in place. Returns the version it
+    /// had, or an error if that is newer than this build understands.
+    pub fn upgrade(&self, value: &mut Value) -> Result<u32> {
+        let from = version_of(value)
+            .with_context(|| format!("Invalid {} document", self.name))?;
+        self.check_not_newer(from)?;
+
+        for migration in self.migrations.iter().filter(|m| m.to > from) {
+            (migration.apply)(value)
+                .with_context(|| format!("Failed to upgrade {} to version {} ({})", self.name, migration.to, migration.description))?;
+            set_version(value, migration.to)?;
+        }
+        Ok(from)
+    }
+
+    /// Serialize `value` as a document of the current version.
+    pub fn to_document<T: Serialize + ?Sized>(&self, value: &T) -> Result<Value> {
+        let mut document = serde_json::to_value(value)
+            .with_context(|| format!("Failed to serialize {}", self.name))?;
+        set_version(&mut document, self.current_version())?;
+        Ok(document)
+    }
+
+    /// Read and upgrade the document at `path`, or `None` if it does not exist.
+    /// An upgraded document is backed up and rewritten before it is returned.
+    pub fn load<T: DeserializeOwned>(&self, path: &Path) -> Result<Option<T>> {
+        let Some(mut document) = self.read(path)? else {
+            return Ok(None);
+        };
+
+        let from = self.upgrade(&mut document)
+            .with_context(|| format!("Failed to read {:?}", path))?;
+        if from < self.current_version() {
+            let backup = backup_path(path, from);
+            fs::copy(path, &backup)
+                .with_context(|| format!("Failed to back up {:?} to {:?}", path, backup))?;
+            self.write(path, &document)?;
+        }
+
+        let value = serde_json::from_value(document)
+            .with_context(|| format!("Failed to deserialize {}", self.name))?;
+        Ok(Some(value))
+    }
+
+    /// Like [`load`](Self::load), but when there is no document at `path` yet,
+    /// one at `legacy` (where older versions kept it) is read instead, upgraded
+    /// and written to `path`. The legacy file is left in place as the backup.
+    pub fn load_or_move<T: DeserializeOwned>(&self, path: &Path, legacy: Option<&Path>) -> Result<Option<T>> {
+        if path.exists() {
+            return self.load(path);
+        }
+        let Some(legacy) = legacy else {
+            return Ok(None);
+        };
+        let Some(mut document) = self.read(legacy)? else {
+            return Ok(None);
+        };
+
+        self.upgrade(&mut document)
+            .with_context(|| format!("Failed to read {:?}", legacy))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {:?}", parent))?;
+        }
+        self.write(path, &document)?;
+
+        let value = serde_json::from_value(document)
+            .with_context(|| format!("Failed to deserialize {}", self.name))?;
+        Ok(Some(value))
+    }
+
+    /// Write `value` to `path` as a document of the current version. A file
+    /// already there from a newer version is left alone and is an error; one
+    /// that cannot be read is replaced.
+    pub fn save<T: Serialize + ?Sized>(&self, path: &Path, value: &T) -> Result<()> {
+        if let Ok(Some(existing)) = self.read(path) {
+            if let Ok(version) = version_of(&existing) {
+                self.check_not_newer(version)
+                    .with_context(|| format!("Refusing to overwrite {:?}", path))?;
+            }
+        }
+        self.write(path, &self.to_document(value)?)
+    }
+
+    fn check_not_newer(&self, version: u32) -> Result<()> {
+        let current = self.current_version();
+        if version > current {
+            bail!(
+                "The {} was written by a newer version of TraderRank (schema version {}; this version reads up to {}). Update TraderRank to use it.",
+                self.name, version, current
+            );
+        }
+        Ok(())
+    }
+
+    fn read(&self, path: &Path) -> Result<Option<Value>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {} from {:?}", self.name, path))?;
+        let value = serde_json::from_str(&json)
+            .with_context(|| format!("Failed to parse {} in {:?}", self.name, path))?;
+        Ok(Some(value))
+    }
+
+    fn write(&self, path: &Path, document: &Value) -> Result<()> {
+        let json = serde_json::to_string_pretty(document)
+            .with_context(|| format!("Failed to serialize {}", self.name))?;
+        fs::write(path, json)
+            .with_context(|| format!("Failed to write {} to {:?}", self.name, path))
+    }
+}
+
+/// Where the version-`version` original of `path` is kept before it is upgraded.
+pub fn backup_path(path: &Path, version: u32) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".v{}.bak", version));
+    path.with_file_name(name)
+}
+
+/// The document's schema version; 0 when it has none.
+pub fn version_of(value: &Value) -> Result<u32> {
+    match value.get(VERSION_FIELD) {
+        None => Ok(0),
+        Some(version) => match version.as_u64().and_then(|v| u32::try_from(v).ok()) {
+            Some(version) => Ok(version),
+            None => bail!("{} is not a version number: {}", VERSION_FIELD, version),
+        },
+    }
+}
+
+/// The document's top-level object, for migrations to edit.
+pub fn object_mut(value: &mut Value) -> Result<&mut Map<String, Value>> {
+    match value {
+        Value::Object(map) => Ok(map),
+        _ => bail!("expected a JSON object"),
+    }
+}
+
+fn set_version(value: &mut Value, version: u32) -> Result<()> {
+    object_mut(value)?.insert(VERSION_FIELD.to_string(), Value::from(version));
+    Ok(())
+}
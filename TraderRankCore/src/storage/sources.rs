@@ -0,0 +1,154 @@
+//! Which source files have been imported, recognised by content rather than
+//! name: a re-downloaded statement with more rows is re-imported, and a
+//! renamed copy of an imported file is not imported twice.
+
+use crate::parser;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// An imported source file as it was when last imported.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceFile {
+    pub name: String,
+    /// SHA-256 of the contents, lowercase hex
+    pub hash: String,
+    pub size: u64,
+    /// Modification time reported by the filesystem, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<DateTime<Utc>>,
+    pub processed_at: DateTime<Utc>,
+    /// Ledger fills that came from the file, over all its imports
+    #[serde(default)]
+    pub trades: usize,
+}
+
+impl SourceFile {
+    /// The file at `path` as it is now, with no trades imported yet.
+    pub fn read(path: &Path) -> Result<Self> {
+        let contents = fs::read(path).with_context(|| format!("Failed to read {:?}", path))?;
+        let (size, modified) = metadata(path)?;
+        Ok(Self {
+            name: file_name(path),
+            hash: format!("{:x}", Sha256::digest(&contents)),
+            size,
+            modified,
+            processed_at: Utc::now(),
+            trades: 0,
+        })
+    }
+}
+
+/// How a file in the source directory relates to the imported sources.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceStatus {
+    /// Not imported before
+    New,
+    /// Imported before under this name, but its contents have changed since
+    Changed,
+    /// Imported, and unchanged
+    Unchanged,
+    /// Same contents as the imported file named here
+    Copy(String),
+}
+
+/// A file in the source directory with its current fingerprint.
+#[derive(Debug, Clone)]
+pub struct SourceScan {
+    pub path: PathBuf,
+    pub file: SourceFile,
+    pub status: SourceStatus,
+}
+
+impl SourceScan {
+    /// True when the file has fills that may not be in the ledger yet.
+    pub fn needs_import(&self) -> bool {
+        matches!(self.status, SourceStatus::New | SourceStatus::Changed)
+    }
+}
+
+/// Compare every trade file in `source_dir` against the `known` imports.
+pub fn scan_sources(source_dir: &Path, known: &[SourceFile]) -> Result<Vec<SourceScan>> {
+    scan_source_files(source_files(source_dir)?, known)
+}
+
+/// Compare trade files, possibly from several directories, against the `known`
+/// imports. Files are recorded by name, so names should differ between directories.
+///
+/// A file whose size and modification time match its record is taken as
+/// unchanged without being read; otherwise its contents are hashed.
+pub fn scan_source_files(paths: Vec<PathBuf>, known: &[SourceFile]) -> Result<Vec<SourceScan>> {
+    let mut scans = Vec::new();
+
+    for path in paths {
+        let name = file_name(&path);
+        let previous = known.iter().find(|k| k.name == name);
+
+        let (size, modified) = metadata(&path)?;
+        if let Some(previous) = previous.filter(|p| p.size == size && p.modified.is_some() && p.modified == modified) {
+            scans.push(SourceScan { path, file: previous.clone(), status: SourceStatus::Unchanged });
+            continue;
+        }
+
+        let file = SourceFile::read(&path)?;
+        let status = match previous {
+            Some(previous) if previous.hash == file.hash => SourceStatus::Unchanged,
+            Some(_) => SourceStatus::Changed,
+            None => match known.iter().find(|k| k.hash == file.hash) {
+                Some(original) => SourceStatus::Copy(original.name.clone()),
+                None => SourceStatus::New,
+            },
+        };
+        scans.push(SourceScan { path, file, status });
+    }
+
+    // A new file with the same contents as another file being scanned is a copy of
+    // it: of the one imported under its own name, or else of the first by name
+    for i in 0..scans.len() {
+        if scans[i].status != SourceStatus::New {
+            continue;
+        }
+        let original = scans.iter().enumerate()
+            .filter(|(j, other)| *j != i && other.file.hash == scans[i].file.hash)
+            .find(|(j, other)| other.status != SourceStatus::New || *j < i)
+            .map(|(_, other)| other.file.name.clone());
+        if let Some(original) = original {
+            scans[i].status = SourceStatus::Copy(original);
+        }
+    }
+
+    Ok(scans)
+}
+
+/// Every trade file in the source directory, processed or not, sorted by name.
+pub fn source_files(source_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(source_dir)? {
+        let entry = entry?;
+        let path = entry.path();
+
+        if path.is_file() && parser::is_trade_file(&path) {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+fn metadata(path: &Path) -> Result<(u64, Option<DateTime<Utc>>)> {
+    let metadata = fs::metadata(path).with_context(|| format!("Failed to read metadata of {:?}", path))?;
+    Ok((metadata.len(), metadata.modified().ok().map(DateTime::<Utc>::from)))
+}
+
+/// The file name of `path`, or an empty string if it has none.
+pub fn file_name(path: &Path) -> String {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .map(|s| s.to_string())
+        .unwrap_or_default()
+}
@@ -0,0 +1,383 @@
+use super::{schema, Annotations, ProcessedData, RConfig, SourceFile, Store, TradeFilter};
+use crate::exchange_time;
+use crate::models::{MatchedTrade, Trade};
+use crate::trade_matcher::LotMethod;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+
+/// Tables, created when a database is opened. Times are UTC RFC 3339 with
+/// nanoseconds, so they sort chronologically as text; decimals are text so no
+/// precision is lost. Each trade row also keeps the whole record as JSON.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS trades (
+    id INTEGER PRIMARY KEY,
+    time TEXT NOT NULL,
+    symbol TEXT NOT NULL,
+    side TEXT NOT NULL,
+    quantity TEXT NOT NULL,
+    fill_price TEXT NOT NULL,
+    commission TEXT NOT NULL,
+    account TEXT,
+    execution_id TEXT,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS trades_time ON trades (time);
+CREATE INDEX IF NOT EXISTS trades_symbol_time ON trades (symbol, time);
+CREATE TABLE IF NOT EXISTS source_files (
+    name TEXT PRIMARY KEY,
+    hash TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    modified TEXT,
+    processed_at TEXT NOT NULL,
+    trades INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS source_files_hash ON source_files (hash);
+CREATE TABLE IF NOT EXISTS analysis (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    last_processed TEXT NOT NULL,
+    exchange_timezone TEXT,
+    carry_positions INTEGER NOT NULL,
+    lot_method TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    account_summaries TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS matched_trades (
+    id INTEGER PRIMARY KEY,
+    symbol TEXT NOT NULL,
+    account TEXT,
+    entry_time TEXT NOT NULL,
+    exit_time TEXT NOT NULL,
+    net_pnl TEXT NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS matched_trades_exit_time ON matched_trades (exit_time);
+CREATE INDEX IF NOT EXISTS matched_trades_symbol_exit_time ON matched_trades (symbol, exit_time);
+CREATE TABLE IF NOT EXISTS exclusions (
+    key TEXT PRIMARY KEY,
+    reason TEXT NOT NULL DEFAULT ''
+);
+CREATE TABLE IF NOT EXISTS r_configs (
+    week_start TEXT PRIMARY KEY,
+    r_value TEXT NOT NULL
+);
+";
+
+/// Schema version recorded in the database's `user_version`.
+const SCHEMA_VERSION: u32 = 1;
+
+/// Statements that upgrade a database from the version before each one.
+/// Version 0 recorded source files by name only; dropping the table makes the
+/// files be read once more, and `SCHEMA` creates it with their hashes.
+const MIGRATIONS: &[(u32, &str)] = &[(1, "DROP TABLE IF EXISTS source_files;")];
+
+/// An embedded SQLite database holding the trade ledger, imported source
+/// files, the cached analysis, matched trades, exclusions and R configs.
+pub struct SqliteStore {
+    conn: Connection,
+}
+
+impl SqliteStore {
+    /// File name of the database in a data directory.
+    pub const FILE_NAME: &'static str = "traderrank.db";
+
+    /// Open the database at `path`, creating it and its tables if needed. A
+    /// database from an older version is copied to `traderrank.db.vN.bak` and
+    /// upgraded; one from a newer version is an error.
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open database {:?}", path))?;
+        Self::with_connection(conn, Some(path))
+            .with_context(|| format!("Failed to open database {:?}", path))
+    }
+
+    /// A database that lives only as long as the store.
+    pub fn open_in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?, None)
+    }
+
+    fn with_connection(conn: Connection, path: Option<&Path>) -> Result<Self> {
+        let version: u32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version > SCHEMA_VERSION {
+            bail!(
+                "The database was written by a newer version of TraderRank (schema version {}; this version reads up to {}). Update TraderRank to use it.",
+                version, SCHEMA_VERSION
+            );
+        }
+
+        let tables: u32 = conn.query_row("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table'", [], |row| row.get(0))?;
+        if version < SCHEMA_VERSION && tables > 0 {
+            if let Some(path) = path {
+                let backup = schema::backup_path(path, version);
+                if backup.exists() {
+                    std::fs::remove_file(&backup)?;
+                }
+                conn.execute("VACUUM INTO ?1", [backup.to_string_lossy()])
+                    .with_context(|| format!("Failed to back up the database to {:?}", backup))?;
+            }
+            for (to, sql) in MIGRATIONS.iter().filter(|(to, _)| *to > version) {
+                conn.execute_batch(sql)
+                    .with_context(|| format!("Failed to upgrade the database to version {}", to))?;
+            }
+        }
+
+        conn.execute_batch(SCHEMA)
+            .context("Failed to create database tables")?;
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        Ok(Self { conn })
+    }
+
+    fn read_trades(&self, sql: &str, params: impl rusqlite::Params) -> Result<Vec<Trade>> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let rows = stmt.query_map(params, |row| row.get::<_, String>(0))?;
+        rows.map(|data| serde_json::from_str(&data?).context("Failed to deserialize trade"))
+            .collect()
+    }
+}
+
+impl Store for SqliteStore {
+    fn load_processed_data(&self) -> Result<Option<ProcessedData>> {
+        let row = self.conn
+            .query_row(
+                "SELECT last_processed, exchange_timezone, carry_positions, lot_method, summary, account_summaries
+                 FROM analysis WHERE id = 1",
+                [],
+                |row| Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, bool>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, String>(5)?,
+                )),
+            )
+            .optional()?;
+        let Some((last_processed, exchange_timezone, carry_positions, lot_method, summary, account_summaries)) = row else {
+            return Ok(None);
+        };
+
+        let mut stmt = self.conn.prepare(
+            "SELECT name, hash, size, modified, processed_at, trades FROM source_files ORDER BY rowid",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, i64>(2)?,
+            row.get::<_, Option<String>>(3)?,
+            row.get::<_, String>(4)?,
+            row.get::<_, i64>(5)?,
+        )))?;
+        let mut sources = Vec::new();
+        for row in rows {
+            let (name, hash, size, modified, processed_at, trades) = row?;
+            sources.push(SourceFile {
+                name,
+                hash,
+                size: size as u64,
+                modified: modified.as_deref().map(parse_time).transpose()?,
+                processed_at: parse_time(&processed_at)?,
+                trades: trades as usize,
+            });
+        }
+
+        Ok(Some(ProcessedData {
+            last_processed: parse_time(&last_processed)?,
+            sources,
+            summary: serde_json::from_str(&summary).context("Failed to deserialize cached summary")?,
+            exchange_timezone: exchange_timezone.as_deref().map(exchange_time::parse_tz).transpose()?,
+            account_summaries: serde_json::from_str(&account_summaries).context("Failed to deserialize account summaries")?,
+            carry_positions,
+            lot_method: LotMethod::from_name(&lot_method).unwrap_or_default(),
+        }))
+    }
+
+    fn save_processed_data(&self, data: &ProcessedData) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT OR REPLACE INTO analysis
+                 (id, last_processed, exchange_timezone, carry_positions, lot_method, summary, account_summaries)
+             VALUES (1, ?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                time_text(data.last_processed),
+                data.exchange_timezone.map(|tz| tz.name().to_string()),
+                data.carry_positions,
+                data.lot_method.name(),
+                serde_json::to_string(&data.summary)?,
+                serde_json::to_string(&data.account_summaries)?,
+            ],
+        )?;
+
+        tx.execute("DELETE FROM source_files", [])?;
+        for source in &data.sources {
+            tx.execute(
+                "INSERT INTO source_files (name, hash, size, modified, processed_at, trades)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    source.name,
+                    source.hash,
+                    source.size as i64,
+                    source.modified.map(time_text),
+                    time_text(source.processed_at),
+                    source.trades as i64,
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn load_trades(&self) -> Result<Option<Vec<Trade>>> {
+        if !self.has_trade_ledger()? {
+            return Ok(None);
+        }
+        self.read_trades("SELECT data FROM trades ORDER BY time, id", []).map(Some)
+    }
+
+    fn save_trades(&self, trades: &[Trade]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM trades", [])?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO trades (time, symbol, side, quantity, fill_price, commission, account, execution_id, data)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )?;
+            for trade in trades {
+                insert.execute(params![
+                    time_text(trade.time),
+                    trade.symbol,
+                    trade.side.to_string(),
+                    trade.quantity.to_string(),
+                    trade.fill_price.to_string(),
+                    trade.commission.to_string(),
+                    trade.account,
+                    trade.execution_id,
+                    serde_json::to_string(trade)?,
+                ])?;
+            }
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES ('ledger_saved_at', ?1)",
+            [time_text(Utc::now())],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn query_trades(&self, filter: &TradeFilter) -> Result<Vec<Trade>> {
+        self.read_trades(
+            "SELECT data FROM trades
+             WHERE (?1 IS NULL OR time >= ?1)
+               AND (?2 IS NULL OR time < ?2)
+               AND (?3 IS NULL OR symbol = ?3)
+               AND (?4 IS NULL OR account = ?4)
+             ORDER BY time, id",
+            params![
+                filter.from.map(time_text),
+                filter.until.map(time_text),
+                filter.symbol,
+                filter.account,
+            ],
+        )
+    }
+
+    fn has_trade_ledger(&self) -> Result<bool> {
+        let saved: Option<String> = self.conn
+            .query_row("SELECT value FROM meta WHERE key = 'ledger_saved_at'", [], |row| row.get(0))
+            .optional()?;
+        Ok(saved.is_some())
+    }
+
+    fn load_matched_trades(&self) -> Result<Vec<MatchedTrade>> {
+        let mut stmt = self.conn.prepare("SELECT data FROM matched_trades ORDER BY id")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        rows.map(|data| serde_json::from_str(&data?).context("Failed to deserialize matched trade"))
+            .collect()
+    }
+
+    fn save_matched_trades(&self, matched: &[MatchedTrade]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM matched_trades", [])?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO matched_trades (symbol, account, entry_time, exit_time, net_pnl, data)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for mt in matched {
+                insert.execute(params![
+                    mt.symbol,
+                    mt.account,
+                    time_text(mt.entry_time),
+                    time_text(mt.exit_time),
+                    mt.net_pnl.to_string(),
+                    serde_json::to_string(mt)?,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<()> {
+        self.conn.execute_batch(
+            "BEGIN;
+             DELETE FROM trades;
+             DELETE FROM source_files;
+             DELETE FROM analysis;
+             DELETE FROM matched_trades;
+             DELETE FROM meta WHERE key = 'ledger_saved_at';
+             COMMIT;",
+        )?;
+        Ok(())
+    }
+
+    fn load_annotations(&self) -> Result<Annotations> {
+        let mut stmt = self.conn.prepare("SELECT key, reason FROM exclusions")?;
+        let exclusions = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+
+        let mut stmt = self.conn.prepare("SELECT week_start, r_value FROM r_configs ORDER BY week_start")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+        let mut r_configs = Vec::new();
+        for row in rows {
+            let (week_start, r_value) = row?;
+            r_configs.push(RConfig {
+                week_start: week_start.parse().with_context(|| format!("Invalid R config week: {}", week_start))?,
+                r_value: r_value.parse().with_context(|| format!("Invalid R value: {}", r_value))?,
+            });
+        }
+
+        Ok(Annotations { exclusions, r_configs })
+    }
+
+    fn save_annotations(&self, annotations: &Annotations) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM exclusions", [])?;
+        for (key, reason) in &annotations.exclusions {
+            tx.execute("INSERT INTO exclusions (key, reason) VALUES (?1, ?2)", params![key, reason])?;
+        }
+        tx.execute("DELETE FROM r_configs", [])?;
+        for config in &annotations.r_configs {
+            tx.execute(
+                "INSERT OR REPLACE INTO r_configs (week_start, r_value) VALUES (?1, ?2)",
+                params![config.week_start.to_string(), config.r_value.to_string()],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+fn time_text(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Nanos, true)
+}
+
+fn parse_time(text: &str) -> Result<DateTime<Utc>> {
+    Ok(DateTime::parse_from_rfc3339(text)
+        .with_context(|| format!("Invalid time in database: {}", text))?
+        .with_timezone(&Utc))
+}
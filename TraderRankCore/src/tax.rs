@@ -0,0 +1,360 @@
+//! Realized lots for tax reporting: each closed lot with its dates, proceeds
+//! and basis, classified short- or long-term, with wash sales detected and the
+//! disallowed loss carried into the replacement lot's basis.
+//!
+//! Wash sales follow the common reading of the rule for active traders: a
+//! long lot sold at a loss is a wash sale when shares of the same symbol, in
+//! any account, are bought within `WASH_SALE_WINDOW_DAYS` after the sale.
+//! Short sales are reported but never treated as wash sales, and the
+//! replacement lot's holding period is not extended.
+
+use crate::exchange_time;
+use crate::models::{AssetClass, MatchedTrade, Side, Trade};
+use crate::trade_matcher::{match_trades_with, LotMethod, MatchOptions};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::fmt;
+use std::io::Write;
+
+/// A loss is disallowed when replacement shares are bought within this many days after the sale.
+pub const WASH_SALE_WINDOW_DAYS: i64 = 30;
+
+/// Holding period class of a closed lot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Term {
+    /// Held one year or less (Form 8949 Part I)
+    Short,
+    /// Held more than one year (Form 8949 Part II)
+    Long,
+}
+
+impl Term {
+    /// Form 8949 part the lot is reported in.
+    pub fn form_part(&self) -> &'static str {
+        match self {
+            Term::Short => "I",
+            Term::Long => "II",
+        }
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Term::Short => f.write_str("Short-term"),
+            Term::Long => f.write_str("Long-term"),
+        }
+    }
+}
+
+/// One closed lot as reported for taxes. Dates are trading days in the exchange timezone.
+#[derive(Debug, Clone)]
+pub struct TaxLot {
+    pub symbol: String,
+    pub account: Option<String>,
+    pub quantity: Decimal,
+    pub asset_class: AssetClass,
+    /// Sold short and bought back to cover; acquired on the covering date
+    pub short_sale: bool,
+    pub acquired: NaiveDate,
+    pub sold: NaiveDate,
+    pub proceeds: Decimal,
+    /// Cost of the lot plus commissions and any wash-sale losses carried into it
+    pub cost_basis: Decimal,
+    /// Part of `cost_basis` that is disallowed loss from earlier wash sales
+    pub carried_loss: Decimal,
+    /// Loss disallowed on this sale because replacement shares were bought (code W)
+    pub disallowed_loss: Decimal,
+    pub term: Term,
+}
+
+impl TaxLot {
+    /// Reportable gain or loss: proceeds less basis, plus any disallowed loss.
+    pub fn gain(&self) -> Decimal {
+        self.proceeds - self.cost_basis + self.disallowed_loss
+    }
+
+    pub fn is_wash_sale(&self) -> bool {
+        self.disallowed_loss > Decimal::ZERO
+    }
+
+    pub fn tax_year(&self) -> i32 {
+        self.sold.year()
+    }
+
+    /// Form 8949 column (a), e.g. "100 sh AAPL" or "2 MESH4".
+    pub fn description(&self) -> String {
+        match self.asset_class {
+            AssetClass::Stk => format!("{} sh {}", self.quantity.normalize(), self.symbol),
+            _ => format!("{} {}", self.quantity.normalize(), self.symbol),
+        }
+    }
+}
+
+/// Totals of one tax year.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaxTotals {
+    pub lots: usize,
+    pub short_term_gain: Decimal,
+    pub long_term_gain: Decimal,
+    pub wash_sales: usize,
+    pub disallowed_loss: Decimal,
+}
+
+/// Every closed lot, in the order they were sold.
+#[derive(Debug, Clone)]
+pub struct TaxReport {
+    pub lots: Vec<TaxLot>,
+    /// Method the lots were matched with
+    pub lot_method: LotMethod,
+}
+
+impl TaxReport {
+    /// Build the report from raw fills. Positions are always carried across
+    /// days; average cost is not a tax lot method, so it is replaced by FIFO.
+    pub fn build(trades: &[Trade], options: MatchOptions) -> Self {
+        let lot_method = match options.lot_method {
+            LotMethod::Average => LotMethod::Fifo,
+            method => method,
+        };
+        let options = MatchOptions { carry_positions: true, lot_method, ..options };
+        let exchange_tz = options.exchange_tz;
+
+        let mut matched = match_trades_with(trades, options);
+        matched.sort_by_key(|m| (m.exit_time, m.entry_time));
+
+        let mut replacements = replacement_buys(trades);
+        // Disallowed loss waiting to be added to lots opened by a replacement buy:
+        // (account, symbol, buy time) -> (shares, loss per share)
+        let mut carried: HashMap<LotKey, VecDeque<(Decimal, Decimal)>> = HashMap::new();
+        let mut lots = Vec::with_capacity(matched.len());
+
+        for mt in &matched {
+            let mut lot = tax_lot(mt, exchange_tz);
+            if lot.short_sale {
+                lots.push(lot);
+                continue;
+            }
+
+            lot.carried_loss = take_carried(&mut carried, mt);
+            lot.cost_basis += lot.carried_loss;
+
+            let loss = lot.cost_basis - lot.proceeds;
+            if loss > Decimal::ZERO {
+                let window_end = mt.exit_time + Duration::days(WASH_SALE_WINDOW_DAYS);
+                let mut unmatched = mt.quantity;
+                for buy in replacements.iter_mut().filter(|b| {
+                    b.symbol == mt.symbol && b.time > mt.exit_time && b.time <= window_end && b.available > Decimal::ZERO
+                }) {
+                    let shares = unmatched.min(buy.available);
+                    let disallowed = loss * shares / mt.quantity;
+                    buy.available -= shares;
+                    unmatched -= shares;
+                    lot.disallowed_loss += disallowed;
+                    carried.entry((buy.account.clone(), buy.symbol.clone(), buy.time))
+                        .or_default()
+                        .push_back((shares, disallowed / shares));
+                    if unmatched == Decimal::ZERO {
+                        break;
+                    }
+                }
+            }
+            lots.push(lot);
+        }
+
+        Self { lots, lot_method }
+    }
+
+    /// Years with at least one sale, in order.
+    pub fn tax_years(&self) -> Vec<i32> {
+        let years: BTreeSet<i32> = self.lots.iter().map(TaxLot::tax_year).collect();
+        years.into_iter().collect()
+    }
+
+    pub fn lots_in_year(&self, year: i32) -> impl Iterator<Item = &TaxLot> {
+        self.lots.iter().filter(move |lot| lot.tax_year() == year)
+    }
+
+    pub fn totals(&self, year: i32) -> TaxTotals {
+        let mut totals = TaxTotals::default();
+        for lot in self.lots_in_year(year) {
+            totals.lots += 1;
+            match lot.term {
+                Term::Short => totals.short_term_gain += lot.gain(),
+                Term::Long => totals.long_term_gain += lot.gain(),
+            }
+            if lot.is_wash_sale() {
+                totals.wash_sales += 1;
+                totals.disallowed_loss += lot.disallowed_loss;
+            }
+        }
+        totals
+    }
+
+    /// Write one year's lots as CSV laid out like Form 8949: Part I
+    /// (short-term) rows, then Part II (long-term), each followed by a totals row.
+    pub fn write_form_8949<W: Write>(&self, year: i32, writer: W) -> Result<()> {
+        let mut csv = csv::Writer::from_writer(writer);
+        csv.write_record([
+            "Part",
+            "(a) Description of property",
+            "(b) Date acquired",
+            "(c) Date sold or disposed of",
+            "(d) Proceeds",
+            "(e) Cost or other basis",
+            "(f) Code",
+            "(g) Amount of adjustment",
+            "(h) Gain or (loss)",
+        ])?;
+
+        for term in [Term::Short, Term::Long] {
+            let lots: Vec<&TaxLot> = self.lots_in_year(year).filter(|lot| lot.term == term).collect();
+            if lots.is_empty() {
+                continue;
+            }
+            for lot in &lots {
+                let (code, adjustment) = if lot.is_wash_sale() {
+                    ("W", money(lot.disallowed_loss))
+                } else {
+                    ("", String::new())
+                };
+                csv.write_record([
+                    term.form_part().to_string(),
+                    lot.description(),
+                    lot.acquired.format("%m/%d/%Y").to_string(),
+                    lot.sold.format("%m/%d/%Y").to_string(),
+                    money(lot.proceeds),
+                    money(lot.cost_basis),
+                    code.to_string(),
+                    adjustment,
+                    money(lot.gain()),
+                ])?;
+            }
+            let sum = |f: fn(&TaxLot) -> Decimal| lots.iter().map(|lot| f(lot)).sum::<Decimal>();
+            csv.write_record([
+                term.form_part().to_string(),
+                "Totals".to_string(),
+                String::new(),
+                String::new(),
+                money(sum(|lot| lot.proceeds)),
+                money(sum(|lot| lot.cost_basis)),
+                String::new(),
+                money(sum(|lot| lot.disallowed_loss)),
+                money(sum(TaxLot::gain)),
+            ])?;
+        }
+
+        csv.flush().context("Failed to write Form 8949 CSV")?;
+        Ok(())
+    }
+}
+
+/// A buy fill (or the part of one) that opened a long position, which can
+/// be the replacement purchase of a wash sale.
+struct ReplacementBuy {
+    account: Option<String>,
+    symbol: String,
+    time: DateTime<Utc>,
+    /// Shares not yet matched to an earlier loss
+    available: Decimal,
+}
+
+type LotKey = (Option<String>, String, DateTime<Utc>);
+
+/// Buy fills in time order with the shares each added to a long position;
+/// buys that only cover a short are left out.
+fn replacement_buys(trades: &[Trade]) -> Vec<ReplacementBuy> {
+    let mut fills: Vec<&Trade> = trades.iter().collect();
+    fills.sort_by_key(|t| t.time);
+
+    let mut positions: HashMap<(Option<&str>, &str), Decimal> = HashMap::new();
+    let mut buys = Vec::new();
+    for fill in fills {
+        let position = positions.entry((fill.account.as_deref(), fill.symbol.as_str())).or_default();
+        match fill.side {
+            Side::Buy => {
+                // Shares that cover an open short are not a purchase of replacement shares
+                let covered = (-*position).clamp(Decimal::ZERO, fill.quantity);
+                let opened = fill.quantity - covered;
+                *position += fill.quantity;
+                if opened > Decimal::ZERO {
+                    buys.push(ReplacementBuy {
+                        account: fill.account.clone(),
+                        symbol: fill.symbol.clone(),
+                        time: fill.time,
+                        available: opened,
+                    });
+                }
+            }
+            Side::Sell => *position -= fill.quantity,
+        }
+    }
+    buys
+}
+
+/// Disallowed loss carried into the shares `mt` closed, taken from the
+/// replacement buy that opened its lot.
+fn take_carried(carried: &mut HashMap<LotKey, VecDeque<(Decimal, Decimal)>>, mt: &MatchedTrade) -> Decimal {
+    let key = (mt.account.clone(), mt.symbol.clone(), mt.entry_time);
+    let Some(queue) = carried.get_mut(&key) else {
+        return Decimal::ZERO;
+    };
+
+    let mut remaining = mt.quantity;
+    let mut total = Decimal::ZERO;
+    while remaining > Decimal::ZERO {
+        let Some((shares, per_share)) = queue.front_mut() else {
+            break;
+        };
+        let taken = remaining.min(*shares);
+        total += taken * *per_share;
+        *shares -= taken;
+        remaining -= taken;
+        if *shares == Decimal::ZERO {
+            queue.pop_front();
+        }
+    }
+    total
+}
+
+fn tax_lot(mt: &MatchedTrade, exchange_tz: exchange_time::Tz) -> TaxLot {
+    let multiplier = mt.instrument.multiplier;
+    let sold = mt.exit_date(exchange_tz);
+    let short_sale = mt.side == "Short";
+
+    // A short sale is closed by the covering purchase, so it is acquired and sold on that date
+    let (acquired, proceeds, cost) = if short_sale {
+        (sold, mt.entry_price * mt.quantity * multiplier, mt.exit_price * mt.quantity * multiplier)
+    } else {
+        let acquired = exchange_time::trading_date(mt.entry_time, exchange_tz);
+        (acquired, mt.exit_price * mt.quantity * multiplier, mt.entry_price * mt.quantity * multiplier)
+    };
+
+    // Held more than one year: sold after the anniversary of the purchase
+    let term = match acquired.checked_add_months(Months::new(12)) {
+        Some(anniversary) if sold > anniversary => Term::Long,
+        _ => Term::Short,
+    };
+
+    TaxLot {
+        symbol: mt.symbol.clone(),
+        account: mt.account.clone(),
+        quantity: mt.quantity,
+        asset_class: mt.instrument.asset_class,
+        short_sale,
+        acquired,
+        sold,
+        proceeds,
+        // Both commissions are added to the basis, so the gain is the round trip's net P&L
+        cost_basis: cost + mt.commission,
+        carried_loss: Decimal::ZERO,
+        disallowed_loss: Decimal::ZERO,
+        term,
+    }
+}
+
+fn money(amount: Decimal) -> String {
+    format!("{:.2}", amount.round_dp(2))
+}
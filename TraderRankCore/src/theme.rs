@@ -0,0 +1,118 @@
+//! The dark and light colour palettes of the desktop app, for the CLI's HTML
+//! report and terminal UI. The desktop itself declares them as custom properties
+//! in `TraderRankDesktop/assets/main.css`; a test keeps the two in step.
+
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    pub bg_primary: &'static str,
+    pub bg_secondary: &'static str,
+    pub bg_card: &'static str,
+    pub bg_table_header: &'static str,
+    pub bg_table_row_alt: &'static str,
+    pub text_primary: &'static str,
+    pub text_heading: &'static str,
+    pub text_secondary: &'static str,
+    pub text_muted: &'static str,
+    pub accent_primary: &'static str,
+    pub accent_green: &'static str,
+    pub accent_green_dim: &'static str,
+    pub accent_red: &'static str,
+    pub accent_red_dim: &'static str,
+    pub accent_cyan: &'static str,
+    pub accent_orange: &'static str,
+    pub accent_yellow: &'static str,
+    pub border_color: &'static str,
+    pub border_card: &'static str,
+}
+
+impl Palette {
+    pub const DARK: Palette = Palette {
+        bg_primary: "#0c0d14",
+        bg_secondary: "#121318",
+        bg_card: "#181a23",
+        bg_table_header: "#14151d",
+        bg_table_row_alt: "rgba(255, 255, 255, 0.02)",
+        text_primary: "#f0f1f5",
+        text_heading: "#ffffff",
+        text_secondary: "#9294a5",
+        text_muted: "#5c5e72",
+        accent_primary: "#6366f1",
+        accent_green: "#00d4aa",
+        accent_green_dim: "rgba(0, 212, 170, 0.15)",
+        accent_red: "#ff4d6a",
+        accent_red_dim: "rgba(255, 77, 106, 0.15)",
+        accent_cyan: "#22d3ee",
+        accent_orange: "#fb923c",
+        accent_yellow: "#facc15",
+        border_color: "rgba(255, 255, 255, 0.06)",
+        border_card: "rgba(255, 255, 255, 0.07)",
+    };
+
+    pub const LIGHT: Palette = Palette {
+        bg_primary: "#f3f4f6",
+        bg_secondary: "#ffffff",
+        bg_card: "#ffffff",
+        bg_table_header: "#f7f8fa",
+        bg_table_row_alt: "rgba(0, 0, 0, 0.015)",
+        text_primary: "#111827",
+        text_heading: "#111827",
+        text_secondary: "#6b7280",
+        text_muted: "#9ca3af",
+        accent_primary: "#4f46e5",
+        accent_green: "#059669",
+        accent_green_dim: "rgba(5, 150, 105, 0.1)",
+        accent_red: "#dc2626",
+        accent_red_dim: "rgba(220, 38, 38, 0.1)",
+        accent_cyan: "#0891b2",
+        accent_orange: "#ea580c",
+        accent_yellow: "#ca8a04",
+        border_color: "rgba(15, 16, 41, 0.08)",
+        border_card: "rgba(15, 16, 41, 0.08)",
+    };
+
+    /// The palette as CSS custom property declarations, named as in `main.css`
+    /// (`--bg-primary: #0c0d14;` ...).
+    pub fn css_variables(&self) -> String {
+        let mut css = String::new();
+        for (name, value) in self.entries() {
+            let _ = writeln!(css, "--{}: {};", name, value);
+        }
+        css
+    }
+
+    /// Both palettes as `[data-theme="dark"]` and `[data-theme="light"]` rules,
+    /// dark also being the default.
+    pub fn theme_css() -> String {
+        format!(
+            ":root,\n[data-theme=\"dark\"] {{\n{}}}\n\n[data-theme=\"light\"] {{\n{}}}\n",
+            Palette::DARK.css_variables(),
+            Palette::LIGHT.css_variables(),
+        )
+    }
+
+    fn entries(&self) -> [(&'static str, &'static str); 19] {
+        [
+            ("bg-primary", self.bg_primary),
+            ("bg-secondary", self.bg_secondary),
+            ("bg-card", self.bg_card),
+            ("bg-table-header", self.bg_table_header),
+            ("bg-table-row-alt", self.bg_table_row_alt),
+            ("text-primary", self.text_primary),
+            ("text-heading", self.text_heading),
+            ("text-secondary", self.text_secondary),
+            ("text-muted", self.text_muted),
+            ("accent-primary", self.accent_primary),
+            ("accent-green", self.accent_green),
+            ("accent-green-dim", self.accent_green_dim),
+            ("accent-red", self.accent_red),
+            ("accent-red-dim", self.accent_red_dim),
+            ("accent-cyan", self.accent_cyan),
+            ("accent-orange", self.accent_orange),
+            ("accent-yellow", self.accent_yellow),
+            ("border-color", self.border_color),
+            ("border-card", self.border_card),
+        ]
+    }
+}
@@ -0,0 +1,358 @@
+use crate::models::{HoldingPeriod, MatchedTrade, Side, Trade};
+use crate::exchange_time::{Tz, DEFAULT_EXCHANGE_TZ};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+/// Which open lots a closing fill is matched against.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LotMethod {
+    /// Oldest lot first; the method used for US tax reporting by default
+    Fifo,
+    /// Newest lot first
+    Lifo,
+    /// All open fills pooled into one lot at their weighted-average price
+    #[default]
+    Average,
+    /// The lot named by the closing fill's `closes_lot`, then oldest first
+    SpecificId,
+}
+
+impl LotMethod {
+    pub const ALL: [LotMethod; 4] = [LotMethod::Fifo, LotMethod::Lifo, LotMethod::Average, LotMethod::SpecificId];
+
+    /// Parse "fifo", "lifo", "average" or "specific-id", ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().replace('_', "-").as_str() {
+            "fifo" => Some(LotMethod::Fifo),
+            "lifo" => Some(LotMethod::Lifo),
+            "average" | "avg" | "average-cost" => Some(LotMethod::Average),
+            "specific-id" | "specific" => Some(LotMethod::SpecificId),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LotMethod::Fifo => "fifo",
+            LotMethod::Lifo => "lifo",
+            LotMethod::Average => "average",
+            LotMethod::SpecificId => "specific-id",
+        }
+    }
+}
+
+impl fmt::Display for LotMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            LotMethod::Fifo => "FIFO",
+            LotMethod::Lifo => "LIFO",
+            LotMethod::Average => "Average cost",
+            LotMethod::SpecificId => "Specific ID",
+        };
+        f.write_str(label)
+    }
+}
+
+/// Part of an open position bought (or sold short) together.
+#[derive(Debug, Clone)]
+struct Lot {
+    quantity: Decimal,
+    price: Decimal,
+    /// Opening commission not yet charged to a closed round trip
+    commission: Decimal,
+    time: DateTime<Utc>,
+    fills: u32,
+    execution_id: Option<String>,
+}
+
+/// Chronological long/short position state for a single symbol in one account.
+///
+/// This is the one position engine shared by `match_trades` and
+/// `TradingAnalytics`, so daily summaries and matched round trips always agree:
+/// - Buy fills build a long position out of lots
+/// - Sell fills close long lots and produce MatchedTrades (side="Long")
+/// - Sell fills at position<=0 open/add to a short position
+/// - Buy fills close short lots and produce MatchedTrades (side="Short")
+/// - A fill larger than the open position closes it and flips into the other side
+///
+/// Under FIFO, LIFO and specific-ID every opening fill is its own lot and each
+/// lot a closing fill reaches is a separate round trip with that lot's entry time
+/// and price. Under average cost the open fills are pooled into one lot at their
+/// weighted-average price, entered at the first fill's time.
+///
+/// Prices are per unit; P&L is scaled by the contract multiplier of the closing fill.
+/// Each round trip is tagged intraday or swing by whether it opened and closed
+/// on the same trading day in `exchange_tz`.
+///
+/// Fills must be applied in time order.
+#[derive(Debug, Clone)]
+pub struct PositionTracker {
+    symbol: String,
+    exchange_tz: Tz,
+    lot_method: LotMethod,
+    // position > 0 = long, position < 0 = short, position == 0 = flat
+    position: Decimal,
+    // Open lots, oldest first
+    lots: VecDeque<Lot>,
+}
+
+impl PositionTracker {
+    pub fn new(symbol: &str, options: MatchOptions) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            exchange_tz: options.exchange_tz,
+            lot_method: options.lot_method,
+            position: Decimal::ZERO,
+            lots: VecDeque::new(),
+        }
+    }
+
+    /// Signed open quantity: positive when long, negative when short.
+    pub fn position(&self) -> Decimal {
+        self.position
+    }
+
+    /// Apply one fill. Returns the round trips it closed, one per lot.
+    /// A zero-quantity fill opens and closes nothing and is skipped.
+    pub fn apply(&mut self, trade: &Trade) -> Vec<MatchedTrade> {
+        // Its commission could not be split by quantity either
+        if trade.quantity.is_zero() {
+            return Vec::new();
+        }
+
+        let opens_long = trade.side == Side::Buy;
+        let is_closing = (opens_long && self.position < Decimal::ZERO)
+            || (!opens_long && self.position > Decimal::ZERO);
+
+        if !is_closing {
+            self.add_lot(trade, trade.quantity);
+            return Vec::new();
+        }
+
+        let side = if self.position > Decimal::ZERO { "Long" } else { "Short" };
+        let mut remaining = trade.quantity;
+        let mut matched = Vec::new();
+
+        while remaining > Decimal::ZERO {
+            let Some(idx) = self.next_lot(trade) else {
+                break;
+            };
+            let lot = &mut self.lots[idx];
+            let qty_to_close = remaining.min(lot.quantity);
+
+            // Long P&L = (exit - entry) * qty * multiplier, short P&L = (entry - exit) * qty * multiplier
+            let multiplier = trade.instrument.multiplier;
+            let gross_pnl = if side == "Long" {
+                (trade.fill_price - lot.price) * qty_to_close * multiplier
+            } else {
+                (lot.price - trade.fill_price) * qty_to_close * multiplier
+            };
+
+            let entry_comm = lot.commission * qty_to_close / lot.quantity;
+            let exit_comm = trade.commission * qty_to_close / trade.quantity;
+            let total_comm = entry_comm + exit_comm;
+
+            matched.push(MatchedTrade {
+                symbol: self.symbol.clone(),
+                entry_time: lot.time,
+                exit_time: trade.time,
+                side: side.to_string(),
+                quantity: qty_to_close,
+                entry_price: lot.price,
+                exit_price: trade.fill_price,
+                gross_pnl,
+                commission: total_comm,
+                net_pnl: gross_pnl - total_comm,
+                entry_fills: lot.fills,
+                exit_fills: 1,
+                instrument: trade.instrument.clone(),
+                account: trade.account.clone(),
+                holding: HoldingPeriod::between(lot.time, trade.time, self.exchange_tz),
+            });
+
+            // The rest of the lot keeps its share of the opening commission
+            lot.commission -= entry_comm;
+            lot.quantity -= qty_to_close;
+            if lot.quantity == Decimal::ZERO {
+                self.lots.remove(idx);
+            }
+            remaining -= qty_to_close;
+            if self.position > Decimal::ZERO {
+                self.position -= qty_to_close;
+            } else {
+                self.position += qty_to_close; // moves toward zero
+            }
+        }
+
+        if remaining > Decimal::ZERO {
+            // Filled more than the open position -- flip into the other side
+            self.add_lot(trade, remaining);
+        }
+
+        matched
+    }
+
+    /// Index of the open lot the closing `trade` is matched against next.
+    fn next_lot(&self, trade: &Trade) -> Option<usize> {
+        if self.lots.is_empty() {
+            return None;
+        }
+        let named = || {
+            let id = trade.closes_lot.as_deref()?;
+            self.lots.iter().position(|lot| lot.execution_id.as_deref() == Some(id))
+        };
+        Some(match self.lot_method {
+            LotMethod::Lifo => self.lots.len() - 1,
+            LotMethod::SpecificId => named().unwrap_or(0),
+            LotMethod::Fifo | LotMethod::Average => 0,
+        })
+    }
+
+    /// Open `quantity` of `trade` (all of it, or what is left after a flip).
+    fn add_lot(&mut self, trade: &Trade, quantity: Decimal) {
+        let commission = trade.commission * quantity / trade.quantity;
+        match trade.side {
+            Side::Buy => self.position += quantity,
+            Side::Sell => self.position -= quantity,
+        }
+
+        if self.lot_method == LotMethod::Average {
+            if let Some(pooled) = self.lots.front_mut() {
+                // Adding to the existing position -- weighted average cost basis
+                let total_cost = pooled.price * pooled.quantity + trade.fill_price * quantity;
+                pooled.quantity += quantity;
+                pooled.price = total_cost / pooled.quantity;
+                pooled.commission += commission;
+                pooled.fills += 1;
+                return;
+            }
+        }
+
+        self.lots.push_back(Lot {
+            quantity,
+            price: trade.fill_price,
+            commission,
+            time: trade.time,
+            fills: 1,
+            execution_id: trade.execution_id.clone(),
+        });
+    }
+}
+
+/// How `match_trades_with` pairs fills into round trips.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MatchOptions {
+    /// Timezone whose calendar defines trading days
+    pub exchange_tz: Tz,
+    /// Carry open positions across days, so overnight holds close as swing
+    /// trades. When false, every position starts flat each trading day.
+    pub carry_positions: bool,
+    /// Which open lots closing fills are matched against
+    pub lot_method: LotMethod,
+}
+
+impl MatchOptions {
+    /// Day-trading only: positions left open at end of day are skipped.
+    pub fn intraday(exchange_tz: Tz) -> Self {
+        Self { exchange_tz, carry_positions: false, lot_method: LotMethod::default() }
+    }
+
+    /// Positions are carried across days until they are closed.
+    pub fn swing(exchange_tz: Tz) -> Self {
+        Self { exchange_tz, carry_positions: true, lot_method: LotMethod::default() }
+    }
+
+    /// The same options with a different lot-matching method.
+    pub fn with_lot_method(self, lot_method: LotMethod) -> Self {
+        Self { lot_method, ..self }
+    }
+}
+
+impl Default for MatchOptions {
+    fn default() -> Self {
+        Self::intraday(DEFAULT_EXCHANGE_TZ)
+    }
+}
+
+/// A position still open after all fills were applied: at the end of a
+/// trading day in intraday mode, or at the last fill when carrying positions.
+#[derive(Debug, Clone)]
+pub struct OpenPosition {
+    pub symbol: String,
+    /// Signed quantity: positive when long, negative when short
+    pub quantity: Decimal,
+}
+
+impl std::fmt::Display for OpenPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let direction = if self.quantity > Decimal::ZERO { "long" } else { "short" };
+        write!(f, "{} - {} unclosed {} shares", self.symbol, self.quantity.abs(), direction)
+    }
+}
+
+/// Match raw trade executions into round-trip day trades with P&L.
+///
+/// Per day, per account and symbol, trades are fed chronologically through a `PositionTracker`,
+/// so positions in different accounts never offset each other.
+/// Days are trading days in `exchange_tz`. Positions left open at end of day are skipped;
+/// use `match_trades_with` and `MatchOptions::swing` to carry them overnight.
+///
+/// Returns matched trades sorted by exit_time descending (most recent first).
+pub fn match_trades(trades: &[Trade], exchange_tz: Tz) -> Vec<MatchedTrade> {
+    match_trades_with(trades, MatchOptions::intraday(exchange_tz))
+}
+
+/// Match raw trade executions into round-trip trades with P&L, per `options`.
+///
+/// Returns matched trades sorted by exit_time descending (most recent first).
+pub fn match_trades_with(trades: &[Trade], options: MatchOptions) -> Vec<MatchedTrade> {
+    let (mut all_matched, _) = match_positions(trades, options);
+
+    // Sort by exit_time descending (most recent first)
+    all_matched.sort_by_key(|m| std::cmp::Reverse(m.exit_time));
+
+    all_matched
+}
+
+/// Round trips, in no particular order, and the positions left open.
+/// Shared by `match_trades_with` and `TradingAnalytics`.
+pub(crate) fn match_positions(trades: &[Trade], options: MatchOptions) -> (Vec<MatchedTrade>, Vec<OpenPosition>) {
+    // In intraday mode each trading day is matched on its own
+    let mut periods: HashMap<Option<NaiveDate>, Vec<&Trade>> = HashMap::new();
+    for trade in trades {
+        let period = (!options.carry_positions).then(|| trade.trading_date(options.exchange_tz));
+        periods.entry(period).or_default().push(trade);
+    }
+
+    let mut all_matched = Vec::new();
+    let mut open = Vec::new();
+
+    for period_trades in periods.values() {
+        // Group by account and symbol within the period
+        let mut by_symbol: HashMap<(Option<&str>, &str), Vec<&Trade>> = HashMap::new();
+        for trade in period_trades {
+            by_symbol.entry((trade.account.as_deref(), trade.symbol.as_str())).or_default().push(trade);
+        }
+
+        for ((_, symbol), mut symbol_trades) in by_symbol {
+            // Sort by time for chronological processing (stable sort handles ties gracefully)
+            symbol_trades.sort_by_key(|t| t.time);
+
+            let mut tracker = PositionTracker::new(symbol, options);
+            all_matched.extend(symbol_trades.iter().flat_map(|t| tracker.apply(t)));
+
+            if tracker.position() != Decimal::ZERO {
+                open.push(OpenPosition {
+                    symbol: symbol.to_string(),
+                    quantity: tracker.position(),
+                });
+            }
+        }
+    }
+
+    (all_matched, open)
+}
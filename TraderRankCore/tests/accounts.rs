@@ -0,0 +1,56 @@
+use trader_rank_core::{match_trades, CsvParser, TradingAnalytics, DEFAULT_EXCHANGE_TZ};
+
+mod common;
+use common::{dec, fixture};
+
+#[test]
+fn parsers_keep_the_account() {
+    let parser = CsvParser::new();
+
+    let history = parser.parse_file(&fixture("ib_transaction_history_accounts.csv")).unwrap();
+    let accounts: Vec<_> = history.trades.iter().map(|t| t.account.as_deref()).collect();
+    assert_eq!(accounts, [Some("U1111111"), Some("U2222222"), Some("U1111111"), Some("U2222222")]);
+
+    let flex = parser.parse_file(&fixture("flex_activity_statement.xml")).unwrap();
+    assert!(flex.trades.iter().all(|t| t.in_account("U1234567")));
+
+    // Single-account Activity Statements name the account once, in Account Information
+    let activity = parser.parse_file(&fixture("ib_activity_statement.csv")).unwrap();
+    assert!(!activity.trades.is_empty());
+    assert!(activity.trades.iter().all(|t| t.in_account("U1234567")));
+
+    let generic = parser.parse_file(&fixture("trades_with_bad_rows.csv")).unwrap();
+    assert!(generic.trades.iter().all(|t| t.account.is_none()));
+}
+
+#[test]
+fn positions_in_different_accounts_do_not_offset() {
+    let report = CsvParser::new().parse_file(&fixture("ib_transaction_history_accounts.csv")).unwrap();
+
+    // Netted by symbol alone, the first two fills would close against each other
+    let matched = match_trades(&report.trades, DEFAULT_EXCHANGE_TZ);
+    assert_eq!(matched.len(), 2);
+    let long = matched.iter().find(|m| m.account.as_deref() == Some("U1111111")).unwrap();
+    assert_eq!(long.side, "Long");
+    assert_eq!(long.gross_pnl, dec("75"));
+    let short = matched.iter().find(|m| m.account.as_deref() == Some("U2222222")).unwrap();
+    assert_eq!(short.side, "Short");
+    assert_eq!(short.gross_pnl, dec("100"));
+}
+
+#[test]
+fn summarizes_per_account_and_combined() {
+    let report = CsvParser::new().parse_file(&fixture("ib_transaction_history_accounts.csv")).unwrap();
+
+    let combined = TradingAnalytics::analyze_trades(&report.trades, DEFAULT_EXCHANGE_TZ);
+    assert_eq!(combined.accounts, ["U1111111", "U2222222"]);
+    assert_eq!(combined.total_trades, 2);
+    assert_eq!(combined.total_pnl, dec("171"));
+
+    let by_account = TradingAnalytics::analyze_by_account(&report.trades, DEFAULT_EXCHANGE_TZ);
+    assert_eq!(by_account.len(), 2);
+    assert_eq!(by_account["U1111111"].total_pnl, dec("73"));
+    assert_eq!(by_account["U1111111"].accounts, ["U1111111"]);
+    assert_eq!(by_account["U2222222"].total_pnl, dec("98"));
+    assert_eq!(by_account["U2222222"].total_trades, 1);
+}
@@ -0,0 +1,75 @@
+use trader_rank_core::parser::csv_parser::FileFormat;
+use trader_rank_core::parser::DiagnosticKind;
+use trader_rank_core::{match_trades, CsvParser, DEFAULT_EXCHANGE_TZ};
+
+mod common;
+use common::{dec, fixture, temp_dir};
+
+/// Every layout holds the same AAPL round trip: 100 shares bought at 185.50, sold at 186.25.
+fn assert_round_trip(name: &str) {
+    let report = CsvParser::new().parse_file(&fixture(name)).unwrap();
+    assert_eq!(report.format, FileFormat::Trades, "{}", name);
+    assert!(report.diagnostics.is_empty(), "{}: {:?}", name, report.diagnostics);
+    assert_eq!(report.trades.len(), 2, "{}", name);
+    assert!(report.trades.iter().all(|t| t.symbol == "AAPL" && t.quantity == dec("100")), "{}", name);
+    assert_eq!(report.trades[0].fill_price, dec("185.50"), "{}", name);
+    assert_eq!(report.trades[1].commission, dec("1.00"), "{}", name);
+
+    let matched = match_trades(&report.trades, DEFAULT_EXCHANGE_TZ);
+    assert_eq!(matched.len(), 1, "{}", name);
+    assert_eq!(matched[0].gross_pnl, dec("75"), "{}", name);
+}
+
+#[test]
+fn columns_may_come_in_any_order() {
+    assert_round_trip("trades_reordered.csv");
+}
+
+#[test]
+fn extra_columns_are_ignored_and_sell_quantities_may_be_negative() {
+    assert_round_trip("trades_extra_columns.csv");
+}
+
+#[test]
+fn columns_are_found_by_their_aliases() {
+    assert_round_trip("trades_renamed.csv");
+}
+
+#[test]
+fn missing_required_columns_are_named() {
+    let report = CsvParser::new().parse_file(&fixture("trades_missing_columns.csv")).unwrap();
+
+    assert!(report.trades.is_empty());
+    assert_eq!(report.diagnostics.len(), 1);
+    let diagnostic = &report.diagnostics[0];
+    assert_eq!(diagnostic.kind, DiagnosticKind::MissingColumns);
+    assert_eq!(diagnostic.line, 1);
+    // Renamed columns that are present are not reported
+    assert!(diagnostic.message.contains("Time"), "{}", diagnostic.message);
+    assert!(!diagnostic.message.contains("Symbol"), "{}", diagnostic.message);
+    assert!(!diagnostic.message.contains("Fill Price"), "{}", diagnostic.message);
+}
+
+#[test]
+fn zero_quantities_are_dropped_with_a_diagnostic() {
+    let dir = temp_dir("columns_zero");
+    let generic = dir.join("zero.csv");
+    std::fs::write(&generic, "\
+Symbol,Side,Quantity,Price,Time
+AAPL,Buy,0,185.50,2024-01-02 09:35:00
+AAPL,Buy,100,185.50,2024-01-02 09:40:00
+").unwrap();
+    let history = dir.join("zero_history.csv");
+    std::fs::write(&history, "\
+Transaction History,Header,Date,Account,Description,Transaction Type,Symbol,Quantity,Price,Gross Amount,Commission,Net Amount
+Transaction History,Data,2024-01-02,U1111111,APPLE INC,Buy,AAPL,0,185.50,0,-1,-1
+").unwrap();
+
+    for path in [generic, history] {
+        let report = CsvParser::new().parse_file(&path).unwrap();
+        assert!(report.trades.iter().all(|t| !t.quantity.is_zero()), "{:?}", path);
+        assert_eq!(report.diagnostics.len(), 1, "{:?}", path);
+        assert_eq!(report.diagnostics[0].kind, DiagnosticKind::InvalidNumber);
+        assert_eq!(report.diagnostics[0].message, "Zero quantity");
+    }
+}
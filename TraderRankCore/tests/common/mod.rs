@@ -0,0 +1,49 @@
+//! Helpers shared by the integration tests. Each test crate uses only some of them.
+#![allow(dead_code)]
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::path::PathBuf;
+use std::str::FromStr;
+use trader_rank_core::{Instrument, Side, Trade, DEFAULT_EXCHANGE_TZ};
+
+pub fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+}
+
+pub fn dec(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+pub fn utc(s: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+}
+
+/// A fresh, empty directory under the system temp dir.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("trader_rank_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// A commission-free AAPL stock fill. `time` is RFC 3339 or a New York wall-clock
+/// "YYYY-MM-DD HH:MM:SS"; other fields are set with struct update syntax.
+pub fn fill(side: Side, quantity: u32, price: &str, time: &str) -> Trade {
+    let fill_price = dec(price);
+    Trade {
+        symbol: "AAPL".to_string(),
+        side,
+        quantity: Decimal::from(quantity),
+        fill_price,
+        time: Trade::parse_time(time, DEFAULT_EXCHANGE_TZ).unwrap(),
+        net_amount: fill_price * Decimal::from(quantity),
+        commission: Decimal::ZERO,
+        realized_pnl: None,
+        instrument: Instrument::stock(),
+        account: None,
+        execution_id: None,
+        closes_lot: None,
+        source: None,
+    }
+}
@@ -0,0 +1,77 @@
+use trader_rank_core::parser::csv_parser::FileFormat;
+use trader_rank_core::parser::DiagnosticKind;
+use trader_rank_core::{CsvParser, Side};
+
+mod common;
+use common::{dec, fixture};
+
+#[test]
+fn semicolon_files_read_european_numbers() {
+    let report = CsvParser::new().parse_file(&fixture("trades_semicolon_european.csv")).unwrap();
+
+    assert_eq!(report.format, FileFormat::Trades);
+    assert_eq!(report.trades.len(), 2);
+    assert_eq!(report.trades[0].fill_price, dec("1234.56"));
+    assert_eq!(report.trades[0].net_amount, dec("123456.00"));
+    assert_eq!(report.trades[0].commission, dec("1.00"));
+
+    // Three digits after the decimal comma could be a thousands group: not guessed
+    let found: Vec<(usize, DiagnosticKind)> = report.diagnostics.iter().map(|d| (d.line, d.kind)).collect();
+    assert_eq!(found, [(4, DiagnosticKind::InvalidNumber)]);
+}
+
+#[test]
+fn semicolon_files_read_a_lone_comma_as_decimal() {
+    let report = CsvParser::new().parse_file(&fixture("trades_semicolon_decimal_comma.csv")).unwrap();
+
+    // `1,250` would be 1250 in a comma-delimited file
+    assert!(report.diagnostics.is_empty(), "{:?}", report.diagnostics);
+    let prices: Vec<_> = report.trades.iter().map(|t| t.fill_price).collect();
+    assert_eq!(prices, [dec("1.250"), dec("1.30")]);
+    assert_eq!(report.trades[0].commission, dec("1.00"));
+    assert_eq!(report.trades[0].net_amount, dec("125.000"));
+}
+
+#[test]
+fn tab_files_with_a_bom_are_read() {
+    let report = CsvParser::new().parse_file(&fixture("trades_tab_bom.csv")).unwrap();
+
+    assert_eq!(report.format, FileFormat::Trades);
+    assert!(report.diagnostics.is_empty());
+    assert_eq!(report.trades.len(), 2);
+    assert_eq!(report.trades[0].symbol, "MSFT");
+    assert_eq!(report.trades[0].fill_price, dec("370.25"));
+}
+
+#[test]
+fn quoted_fields_keep_commas_quotes_and_newlines() {
+    let report = CsvParser::new().parse_file(&fixture("ib_transaction_history_quoted.csv")).unwrap();
+
+    assert!(report.diagnostics.is_empty(), "{:?}", report.diagnostics);
+    assert_eq!(report.trades.len(), 2);
+    let sides: Vec<&Side> = report.trades.iter().map(|t| &t.side).collect();
+    assert_eq!(sides, [&Side::Buy, &Side::Sell]);
+    assert_eq!(report.trades[0].fill_price, dec("185.50"));
+    assert_eq!(report.trades[1].fill_price, dec("186.25"));
+    assert_eq!(report.trades[1].net_amount.abs(), dec("18624.00"));
+}
+
+#[test]
+fn decimal_separators_are_read_by_position() {
+    let parse = |raw: &str| CsvParser::parse_decimal(raw).ok();
+
+    assert_eq!(parse("1,234.56"), Some(dec("1234.56")));
+    assert_eq!(parse("1.234,56"), Some(dec("1234.56")));
+    assert_eq!(parse("123.456,00"), Some(dec("123456.00")));
+    assert_eq!(parse("-1.234.567,8"), Some(dec("-1234567.8")));
+    assert_eq!(parse("12,50"), Some(dec("12.50")));
+    assert_eq!(parse("1,234"), Some(dec("1234")));
+    assert_eq!(parse("1,234,567"), Some(dec("1234567")));
+    assert_eq!(parse("($1,234.50)"), Some(dec("-1234.50")));
+
+    // Mixed or ambiguous separators are errors, not guesses
+    assert_eq!(parse("1.234,567"), None);
+    assert_eq!(parse("12,34.5"), None);
+    assert_eq!(parse("1,23,4"), None);
+    assert_eq!(parse("1.2.3,45"), None);
+}
@@ -0,0 +1,82 @@
+use chrono::Duration;
+use rust_decimal::Decimal;
+use trader_rank_core::dedup::{dedupe_sources, DuplicateReason};
+use trader_rank_core::{CsvParser, Side, Trade};
+
+mod common;
+use common::{fill, fixture};
+
+/// 100 AAPL bought at 185.50 with a $1 commission.
+fn buy(time: &str, execution_id: Option<&str>) -> Trade {
+    Trade { commission: Decimal::ONE, execution_id: execution_id.map(str::to_string), ..fill(Side::Buy, 100, "185.50", time) }
+}
+
+#[test]
+fn identical_partial_fills_in_one_source_are_kept() {
+    let a = buy("2024-01-02T14:35:12Z", None);
+    let outcome = dedupe_sources(vec![vec![a.clone(), a]]);
+    assert_eq!(outcome.trades.len(), 2);
+    assert!(outcome.duplicates.is_empty());
+}
+
+#[test]
+fn fills_without_ids_match_across_sources_within_tolerance() {
+    let csv = vec![buy("2024-01-02T14:35:12Z", None), buy("2024-01-02T14:35:12Z", None)];
+    // The second source stamps the same two fills a second later, plus one more fill
+    let other = vec![
+        buy("2024-01-02T14:35:13Z", None),
+        buy("2024-01-02T14:35:13Z", None),
+        buy("2024-01-02T14:35:13Z", None),
+    ];
+
+    let outcome = dedupe_sources(vec![csv, other]);
+    assert_eq!(outcome.trades.len(), 3);
+    assert_eq!(outcome.duplicates.len(), 2);
+    assert!(outcome.duplicates.iter().all(|d| d.reason == DuplicateReason::MatchingFields));
+    assert_eq!(outcome.fuzzy_duplicates().count(), 2);
+    assert_eq!(outcome.duplicates[0].kept_source, 0);
+    assert_eq!(outcome.duplicates[0].dropped_source, 1);
+
+    // Too far apart to be the same fill
+    let late = vec![buy("2024-01-02T14:35:20Z", None)];
+    let outcome = dedupe_sources(vec![vec![buy("2024-01-02T14:35:12Z", None)], late]);
+    assert_eq!(outcome.trades.len(), 2);
+}
+
+#[test]
+fn execution_ids_decide_when_both_fills_have_one() {
+    let first = buy("2024-01-02T14:35:12Z", Some("1001"));
+    let mut same_id_later = buy("2024-01-02T14:35:12Z", Some("1001"));
+    same_id_later.time += Duration::minutes(5);
+    let other_id = buy("2024-01-02T14:35:12Z", Some("1002"));
+
+    let outcome = dedupe_sources(vec![vec![first], vec![same_id_later, other_id]]);
+    assert_eq!(outcome.trades.len(), 2);
+    assert_eq!(outcome.duplicates.len(), 1);
+    assert_eq!(outcome.duplicates[0].reason, DuplicateReason::ExecutionId);
+    assert_eq!(outcome.fuzzy_duplicates().count(), 0);
+}
+
+#[test]
+fn keeps_the_copy_with_an_execution_id() {
+    let without = buy("2024-01-02T14:35:12Z", None);
+    let with = buy("2024-01-02T14:35:13Z", Some("1001"));
+
+    let outcome = dedupe_sources(vec![vec![without], vec![with]]);
+    assert_eq!(outcome.trades.len(), 1);
+    assert_eq!(outcome.trades[0].execution_id.as_deref(), Some("1001"));
+    assert_eq!(outcome.duplicates[0].dropped.execution_id, None);
+    assert_eq!(outcome.duplicates[0].dropped_source, 0);
+}
+
+#[test]
+fn flex_trades_carry_their_trade_id() {
+    let report = CsvParser::new().parse_file(&fixture("flex_activity_statement.xml")).unwrap();
+    let ids: Vec<_> = report.trades.iter().map(|t| t.execution_id.as_deref()).collect();
+    assert_eq!(ids, [Some("1001"), Some("1002"), Some("1004"), Some("1005")]);
+
+    // Importing the same statement twice adds nothing
+    let outcome = dedupe_sources(vec![report.trades.clone(), report.trades]);
+    assert_eq!(outcome.trades.len(), 4);
+    assert!(outcome.duplicates.iter().all(|d| d.reason == DuplicateReason::ExecutionId));
+}
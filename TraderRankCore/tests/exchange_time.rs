@@ -0,0 +1,117 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use trader_rank_core::exchange_time::{self, parse_tz, DateRange};
+use trader_rank_core::{match_trades, match_trades_with, MatchOptions, Side, TimePatternAnalyzer, Trade, TradingAnalytics, TradingSummary, DEFAULT_EXCHANGE_TZ};
+
+mod common;
+use common::{fill, utc};
+
+#[test]
+fn wall_clock_times_become_instants_across_dst() {
+    let winter = Trade::parse_time("2024-01-02 09:35:00", DEFAULT_EXCHANGE_TZ).unwrap();
+    let summer = Trade::parse_time("2024-07-02 09:35:00", DEFAULT_EXCHANGE_TZ).unwrap();
+    assert_eq!(winter, utc("2024-01-02T14:35:00Z"));
+    assert_eq!(summer, utc("2024-07-02T13:35:00Z"));
+
+    // Explicit offsets are taken as written
+    let offset = Trade::parse_time("2024-07-02T09:35:00-04:00", chrono_tz::UTC).unwrap();
+    assert_eq!(offset, summer);
+
+    // 02:30 on the spring-forward day never happens in New York
+    assert!(Trade::parse_time("2024-03-10 02:30:00", DEFAULT_EXCHANGE_TZ).is_err());
+}
+
+#[test]
+fn hours_and_days_are_exchange_local() {
+    let winter = fill(Side::Buy, 100, "100", "2024-01-02T14:35:00Z");
+    let summer = fill(Side::Buy, 100, "100", "2024-07-02T13:35:00Z");
+    assert_eq!(winter.hour_of_day(DEFAULT_EXCHANGE_TZ), 9);
+    assert_eq!(summer.hour_of_day(DEFAULT_EXCHANGE_TZ), 9);
+
+    // An after-hours fill at 20:30 New York time is already the next day in UTC
+    let late = fill(Side::Sell, 100, "101", "2024-01-03T01:30:00Z");
+    assert_eq!(late.trading_date(DEFAULT_EXCHANGE_TZ), NaiveDate::from_ymd_opt(2024, 1, 2).unwrap());
+    assert_eq!(late.trading_date(chrono_tz::UTC), NaiveDate::from_ymd_opt(2024, 1, 3).unwrap());
+}
+
+#[test]
+fn round_trips_and_summaries_bucket_by_exchange_day() {
+    // Opened at the bell, closed after hours: one New York trading day, two UTC days
+    let trades = vec![
+        fill(Side::Buy, 100, "100", "2024-01-02T14:35:00Z"),
+        fill(Side::Sell, 100, "101", "2024-01-03T01:30:00Z"),
+    ];
+
+    assert_eq!(match_trades(&trades, DEFAULT_EXCHANGE_TZ).len(), 1);
+    assert!(match_trades(&trades, chrono_tz::UTC).is_empty());
+
+    let summary = TradingAnalytics::analyze_trades(&trades, DEFAULT_EXCHANGE_TZ);
+    assert_eq!(summary.daily_summaries.len(), 1);
+    let day = &summary.daily_summaries[0];
+    assert_eq!(day.date, exchange_time::date_to_utc(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()));
+    let hours: Vec<u32> = day.time_slot_performance.iter().map(|s| s.hour).collect();
+    assert_eq!(hours, [9, 20]);
+}
+
+#[test]
+fn sessions_use_the_configured_exchange() {
+    // 15:05 UTC is 10:05 in New York (Morning) and 15:05 in London (Power Hour)
+    let trades = vec![
+        fill(Side::Buy, 100, "100", "2024-01-02T15:00:00Z"),
+        fill(Side::Sell, 100, "102", "2024-01-02T15:05:00Z"),
+    ];
+
+    let ny = TimePatternAnalyzer::identify_best_trading_periods(&trades, DEFAULT_EXCHANGE_TZ);
+    assert_eq!(ny[0].name, "Morning");
+
+    let london = TimePatternAnalyzer::identify_best_trading_periods(&trades, parse_tz("Europe/London").unwrap());
+    assert_eq!(london[0].name, "Power Hour");
+}
+
+#[test]
+fn date_ranges_select_exchange_days() {
+    let day = |d: u32| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+    let range = DateRange { from: Some(day(3)), to: Some(day(4)) };
+    assert!(!range.contains(day(2)) && range.contains(day(3)) && range.contains(day(4)) && !range.contains(day(5)));
+    assert!(DateRange::default().contains(day(1)) && DateRange::default().is_all());
+
+    assert_eq!(range.start(DEFAULT_EXCHANGE_TZ), Some(utc("2024-01-03T05:00:00Z")));
+    assert_eq!(range.end(DEFAULT_EXCHANGE_TZ), Some(utc("2024-01-05T05:00:00Z")));
+    assert_eq!(DateRange { from: None, to: Some(day(4)) }.start(DEFAULT_EXCHANGE_TZ), None);
+}
+
+#[test]
+fn analysis_in_a_range_keeps_earlier_entries() {
+    // A swing trade bought on the 2nd and sold on the 3rd, then a day trade on the 4th
+    let trades = vec![
+        fill(Side::Buy, 100, "100", "2024-01-02T15:00:00Z"),
+        fill(Side::Sell, 100, "103", "2024-01-03T15:00:00Z"),
+        fill(Side::Buy, 100, "100", "2024-01-04T15:00:00Z"),
+        fill(Side::Sell, 100, "99", "2024-01-04T16:00:00Z"),
+    ];
+    let options = MatchOptions { carry_positions: true, ..MatchOptions::default() };
+    let third = NaiveDate::from_ymd_opt(2024, 1, 3).unwrap();
+    let range = DateRange { from: Some(third), to: Some(third) };
+
+    let summary = TradingAnalytics::analyze_trades_in(&trades, options, range);
+    assert_eq!(summary.daily_summaries.len(), 1);
+    assert_eq!(summary.daily_summaries[0].realized_pnl, Decimal::from(300));
+
+    // Summarizing round trips matched once gives the same days
+    let matched = match_trades_with(&trades, options);
+    let summarized = TradingAnalytics::summarize_matched_in(&trades, matched, options.exchange_tz, range);
+    let days = |summary: &TradingSummary| -> Vec<_> {
+        summary.daily_summaries.iter().map(|d| (d.date, d.total_trades, d.realized_pnl, d.total_volume)).collect()
+    };
+    assert_eq!(days(&summarized), days(&summary));
+
+    let periods = TimePatternAnalyzer::identify_best_trading_periods_in(&trades, options, range);
+    let traded: u32 = periods.iter().map(|p| p.total_trades).sum();
+    assert_eq!(traded, 1);
+}
+
+#[test]
+fn unknown_timezone_names_are_rejected() {
+    assert!(parse_tz("America/Gotham").is_err());
+    assert_eq!(parse_tz(" America/Chicago ").unwrap(), chrono_tz::America::Chicago);
+}
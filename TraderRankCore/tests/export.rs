@@ -0,0 +1,56 @@
+use rust_decimal::Decimal;
+use trader_rank_core::export::{write_fills_csv, write_round_trips_csv};
+use trader_rank_core::{match_trades, AssetClass, CsvParser, Instrument, Side, Trade, DEFAULT_EXCHANGE_TZ};
+
+mod common;
+use common::{dec, fill, temp_dir};
+
+/// Two ES contracts on account U1, $2.25 commission.
+fn es(side: Side, price: &str, time: &str, instrument: Instrument) -> Trade {
+    Trade { symbol: "ES".to_string(), commission: dec("2.25"), instrument, account: Some("U1".to_string()), ..fill(side, 2, price, time) }
+}
+
+fn trades() -> Vec<Trade> {
+    let future = Instrument { multiplier: Decimal::from(50), ..Instrument::of_class(AssetClass::Fut) };
+    vec![
+        es(Side::Buy, "4750.25", "2024-01-08T14:35:00Z", future.clone()),
+        es(Side::Sell, "4755.5", "2024-01-08T15:10:12Z", future),
+    ]
+}
+
+#[test]
+fn exported_fills_import_as_the_same_trades() {
+    let trades = trades();
+    let mut csv = Vec::new();
+    write_fills_csv(&trades, DEFAULT_EXCHANGE_TZ, &mut csv).unwrap();
+    let text = String::from_utf8(csv).unwrap();
+    assert!(text.lines().nth(1).unwrap().contains("2024-01-08T09:35:00-05:00"));
+
+    let path = temp_dir("export").join("fills.csv");
+    std::fs::write(&path, &text).unwrap();
+    let report = CsvParser::new().with_timezone(chrono_tz::UTC).parse_file(&path).unwrap();
+    assert!(report.diagnostics.is_empty(), "{:?}", report.diagnostics);
+
+    assert_eq!(report.trades.len(), 2);
+    for (imported, original) in report.trades.iter().zip(&trades) {
+        assert_eq!(imported.time, original.time);
+        assert_eq!(imported.fill_price, original.fill_price);
+        assert_eq!(imported.commission, original.commission);
+        assert_eq!(imported.instrument, original.instrument);
+        assert_eq!(imported.account, original.account);
+    }
+}
+
+#[test]
+fn round_trips_are_one_row_each() {
+    let matched = match_trades(&trades(), DEFAULT_EXCHANGE_TZ);
+    let mut csv = Vec::new();
+    write_round_trips_csv(&matched, DEFAULT_EXCHANGE_TZ, &mut csv).unwrap();
+    let text = String::from_utf8(csv).unwrap();
+
+    let rows: Vec<&str> = text.lines().collect();
+    assert_eq!(rows.len(), 2);
+    assert!(rows[0].starts_with("Symbol,Side,Quantity,Entry Time,Exit Time"));
+    // (4755.5 - 4750.25) × 2 × 50 = 525, less 4.50 commission
+    assert!(rows[1].contains(",525.00,4.50,520.50,Intraday,"), "{}", rows[1]);
+}
@@ -0,0 +1,100 @@
+use trader_rank_core::parser::csv_parser::FileFormat;
+use trader_rank_core::parser::DiagnosticKind;
+use trader_rank_core::parser::flex_xml::parse_flex_trades;
+use trader_rank_core::{match_trades, CsvParser, Side, DEFAULT_EXCHANGE_TZ};
+
+mod common;
+use common::{dec, fixture};
+
+#[test]
+fn detects_flex_statements_by_content() {
+    let parser = CsvParser::new();
+    assert!(matches!(parser.detect_format(&fixture("flex_activity_statement.xml")).unwrap(), FileFormat::IbFlex));
+    assert!(matches!(parser.detect_format(&fixture("flex_trade_confirmations.xml")).unwrap(), FileFormat::IbFlex));
+    assert!(matches!(parser.detect_format(&fixture("not_flex.xml")).unwrap(), FileFormat::Unknown));
+}
+
+#[test]
+fn parses_activity_statement_executions() {
+    let report = CsvParser::new().parse_file(&fixture("flex_activity_statement.xml")).unwrap();
+    let trades = &report.trades;
+
+    // Summary rows and the CASH conversion are skipped
+    assert_eq!(trades.len(), 4);
+    assert!(trades.iter().all(|t| t.symbol == "AAPL" || t.symbol == "TSLA"));
+
+    let buy = &trades[0];
+    assert_eq!(buy.symbol, "AAPL");
+    assert_eq!(buy.side, Side::Buy);
+    assert_eq!(buy.quantity, dec("100"));
+    assert_eq!(buy.fill_price, dec("185.5"));
+    assert_eq!(buy.commission, dec("1"));
+    // Statement times are New York wall-clock times, stored as UTC instants
+    assert_eq!(buy.time.with_timezone(&DEFAULT_EXCHANGE_TZ).format("%Y-%m-%d %H:%M:%S").to_string(), "2024-01-02 09:35:12");
+    assert_eq!(buy.time.to_rfc3339(), "2024-01-02T14:35:12+00:00");
+
+    // Negative quantities and scientific-notation commissions are normalised
+    let short = &trades[2];
+    assert_eq!(short.side, Side::Sell);
+    assert_eq!(short.quantity, dec("50"));
+    assert_eq!(short.commission, dec("0.66"));
+
+    // Cancelled/corrected fills keep their side
+    assert_eq!(trades[3].side, Side::Buy);
+}
+
+#[test]
+fn parses_trade_confirmations_in_time_order() {
+    let xml = std::fs::read_to_string(fixture("flex_trade_confirmations.xml")).unwrap();
+    let (trades, diagnostics) = parse_flex_trades(&xml, DEFAULT_EXCHANGE_TZ).unwrap();
+
+    assert!(diagnostics.is_empty());
+    assert_eq!(trades.len(), 3);
+    let times: Vec<String> = trades.iter().map(|t| t.time.with_timezone(&DEFAULT_EXCHANGE_TZ).format("%H:%M:%S").to_string()).collect();
+    // Sorted by time; the NVDA fill has no time and sorts first at midnight, then gets a synthetic 09:30
+    assert_eq!(times, ["09:30:00", "10:00:00", "15:45:00"]);
+    assert_eq!(trades[0].symbol, "NVDA");
+    assert_eq!(trades[1].side, Side::Buy);
+    assert_eq!(trades[2].side, Side::Sell);
+}
+
+#[test]
+fn skipped_flex_elements_are_reported_with_their_line() {
+    let report = CsvParser::new().parse_file(&fixture("flex_activity_statement.xml")).unwrap();
+
+    assert_eq!(report.format, FileFormat::IbFlex);
+    assert_eq!(report.diagnostics.len(), 1);
+    let cash = &report.diagnostics[0];
+    assert_eq!(cash.kind, DiagnosticKind::UnsupportedAsset);
+    assert_eq!(cash.line, 8);
+    assert!(cash.raw.contains("EUR.USD"));
+}
+
+#[test]
+fn flex_trades_match_into_round_trips() {
+    let trades = CsvParser::new().parse_file(&fixture("flex_activity_statement.xml")).unwrap().trades;
+    let matched = match_trades(&trades, DEFAULT_EXCHANGE_TZ);
+
+    assert_eq!(matched.len(), 2);
+    let aapl = matched.iter().find(|m| m.symbol == "AAPL").unwrap();
+    assert_eq!(aapl.side, "Long");
+    assert_eq!(aapl.gross_pnl, dec("75"));
+    assert_eq!(aapl.net_pnl, dec("72.9875"));
+
+    let tsla = matched.iter().find(|m| m.symbol == "TSLA").unwrap();
+    assert_eq!(tsla.side, "Short");
+    assert_eq!(tsla.gross_pnl, dec("100"));
+}
+
+#[test]
+fn non_flex_xml_yields_no_trades() {
+    let report = CsvParser::new().parse_file(&fixture("not_flex.xml")).unwrap();
+    assert!(report.trades.is_empty());
+    assert!(report.is_skipped());
+    assert!(!report.format_reasons.is_empty());
+}
+
+#[test]
+fn malformed_flex_xml_is_an_error() {
+    assert!(parse_flex_trades("<FlexQueryResponse><Trades>", DEFAULT_EXCHANGE_TZ).is_err());
+}
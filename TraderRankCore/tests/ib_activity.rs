@@ -0,0 +1,65 @@
+use rust_decimal::Decimal;
+use trader_rank_core::parser::csv_parser::FileFormat;
+use trader_rank_core::parser::DiagnosticKind;
+use trader_rank_core::{match_trades, AssetClass, CsvParser, Side, DEFAULT_EXCHANGE_TZ};
+
+mod common;
+use common::{dec, fixture};
+
+#[test]
+fn detects_trades_section_past_leading_sections() {
+    let format = CsvParser::new().detect_format(&fixture("ib_activity_statement.csv")).unwrap();
+    assert!(matches!(format, FileFormat::IbActivityStatement));
+}
+
+#[test]
+fn parses_trades_section_with_real_timestamps() {
+    let trades = CsvParser::new().parse_file(&fixture("ib_activity_statement.csv")).unwrap().trades;
+
+    // SubTotal/Total rows, the Forex conversion and the malformed row are skipped
+    assert_eq!(trades.len(), 3);
+
+    let buy = &trades[0];
+    assert_eq!(buy.symbol, "AAPL");
+    assert_eq!(buy.side, Side::Buy);
+    assert_eq!(buy.quantity, dec("1000"));
+    assert_eq!(buy.fill_price, dec("185.5"));
+    assert_eq!(buy.commission, dec("5"));
+    // Statement times are New York wall-clock times, stored as UTC instants
+    assert_eq!(buy.time.with_timezone(&DEFAULT_EXCHANGE_TZ).format("%Y-%m-%d %H:%M:%S").to_string(), "2024-01-02 09:35:12");
+    assert_eq!(buy.time.to_rfc3339(), "2024-01-02T14:35:12+00:00");
+    assert_eq!(buy.instrument.asset_class, AssetClass::Stk);
+    assert_eq!(buy.instrument.multiplier, dec("1"));
+    assert_eq!(buy.instrument.currency, "USD");
+    assert_eq!(buy.realized_pnl, Some(dec("0")));
+
+    let sell = &trades[1];
+    assert_eq!(sell.side, Side::Sell);
+    assert_eq!(sell.quantity, dec("1000"));
+    assert_eq!(sell.realized_pnl, Some(dec("739.9875")));
+    assert_eq!(sell.net_amount, dec("186244.9875"));
+}
+
+#[test]
+fn matched_pnl_agrees_with_broker_realized_pnl() {
+    let trades = CsvParser::new().parse_file(&fixture("ib_activity_statement.csv")).unwrap().trades;
+    let matched = match_trades(&trades, DEFAULT_EXCHANGE_TZ);
+
+    assert_eq!(matched.len(), 1);
+    let broker_realized: Decimal = trades.iter().filter_map(|t| t.realized_pnl).sum();
+    assert_eq!(matched[0].net_pnl, broker_realized);
+}
+
+#[test]
+fn dropped_rows_are_reported_with_line_and_reason() {
+    let report = CsvParser::new().parse_file(&fixture("ib_activity_statement.csv")).unwrap();
+
+    let kinds: Vec<DiagnosticKind> = report.diagnostics.iter().map(|d| d.kind).collect();
+    assert_eq!(kinds, [DiagnosticKind::UnsupportedAsset, DiagnosticKind::InvalidTime]);
+
+    let bad = &report.diagnostics[1];
+    assert_eq!(bad.line, 20);
+    assert_eq!(bad.raw, "Trades,Data,Order,Stocks,USD,BAD,not a date,10,1,,,,,,,");
+    assert!(bad.message.contains("not a date"));
+    assert_eq!(report.summary(), "3 trades, 2 lines dropped (IB Activity Statement)");
+}
@@ -0,0 +1,91 @@
+use chrono::NaiveDate;
+use trader_rank_core::models::OptionRight;
+use trader_rank_core::parser::DiagnosticKind;
+use trader_rank_core::{match_trades, AssetClass, CsvParser, TradingAnalytics, DEFAULT_EXCHANGE_TZ};
+
+mod common;
+use common::{dec, fixture};
+
+#[test]
+fn reads_flex_contract_terms() {
+    let report = CsvParser::new().parse_file(&fixture("flex_options.xml")).unwrap();
+    assert_eq!(report.trades.len(), 3);
+
+    let option = &report.trades[0].instrument;
+    assert_eq!(option.asset_class, AssetClass::Opt);
+    assert_eq!(option.multiplier, dec("100"));
+    assert_eq!(option.underlying.as_deref(), Some("AAPL"));
+    assert_eq!(option.expiry, NaiveDate::from_ymd_opt(2024, 1, 19));
+    assert_eq!(option.strike, Some(dec("190")));
+    assert_eq!(option.right, Some(OptionRight::Call));
+
+    let future = &report.trades[2];
+    assert_eq!(future.instrument.asset_class, AssetClass::Fut);
+    assert_eq!(future.instrument.multiplier, dec("50"));
+    // No netCash or proceeds: the default cash value includes the multiplier
+    assert_eq!(future.net_amount, dec("237512.50"));
+
+    // Bonds are reported, not imported
+    assert_eq!(report.diagnostics.len(), 1);
+    assert_eq!(report.diagnostics[0].kind, DiagnosticKind::UnsupportedAsset);
+}
+
+#[test]
+fn option_pnl_and_volume_use_the_multiplier() {
+    let report = CsvParser::new().parse_file(&fixture("flex_options.xml")).unwrap();
+    let options: Vec<_> = report.trades.iter()
+        .filter(|t| t.instrument.asset_class == AssetClass::Opt)
+        .cloned()
+        .collect();
+
+    let matched = match_trades(&options, DEFAULT_EXCHANGE_TZ);
+    assert_eq!(matched.len(), 1);
+    // 2 contracts bought at 2.50 and sold at 2.00: 0.50 × 2 × 100 lost
+    assert_eq!(matched[0].gross_pnl, dec("-100"));
+    assert_eq!(matched[0].net_pnl, dec("-102.6"));
+    assert_eq!(matched[0].instrument.asset_class, AssetClass::Opt);
+
+    let summary = TradingAnalytics::analyze_trades(&options, DEFAULT_EXCHANGE_TZ);
+    assert_eq!(summary.total_volume, dec("900"));
+}
+
+#[test]
+fn activity_statement_decodes_option_symbols_and_instrument_info() {
+    let report = CsvParser::new().parse_file(&fixture("ib_activity_options.csv")).unwrap();
+    assert_eq!(report.trades.len(), 3, "{:?}", report.diagnostics);
+
+    let option = &report.trades[0].instrument;
+    assert_eq!(option.asset_class, AssetClass::Opt);
+    assert_eq!(option.multiplier, dec("100"));
+    assert_eq!(option.underlying.as_deref(), Some("AAPL"));
+    assert_eq!(option.expiry, NaiveDate::from_ymd_opt(2024, 1, 19));
+    assert_eq!(option.strike, Some(dec("190")));
+    assert_eq!(option.right, Some(OptionRight::Call));
+
+    // The futures multiplier comes from the Financial Instrument Information section
+    let future = &report.trades[2].instrument;
+    assert_eq!(future.asset_class, AssetClass::Fut);
+    assert_eq!(future.multiplier, dec("50"));
+    assert_eq!(future.expiry, NaiveDate::from_ymd_opt(2024, 3, 15));
+
+    let matched = match_trades(&report.trades, DEFAULT_EXCHANGE_TZ);
+    assert_eq!(matched[0].gross_pnl, dec("-100"));
+}
+
+#[test]
+fn generic_csv_reads_optional_instrument_columns() {
+    let report = CsvParser::new().parse_file(&fixture("trades_with_instruments.csv")).unwrap();
+    assert_eq!(report.trades.len(), 4);
+
+    assert_eq!(report.trades[0].instrument.asset_class, AssetClass::Stk);
+    assert_eq!(report.trades[0].instrument.multiplier, dec("1"));
+    // Options without a multiplier column value default to 100
+    assert_eq!(report.trades[1].instrument.multiplier, dec("100"));
+    assert_eq!(report.trades[1].net_amount, dec("320"));
+    assert_eq!(report.trades[3].instrument.multiplier, dec("5"));
+    assert_eq!(report.trades[3].instrument.currency, "USD");
+
+    assert_eq!(report.diagnostics.len(), 1);
+    assert_eq!(report.diagnostics[0].line, 6);
+    assert_eq!(report.diagnostics[0].kind, DiagnosticKind::UnsupportedAsset);
+}
@@ -0,0 +1,118 @@
+use chrono::NaiveTime;
+use rust_decimal::Decimal;
+use trader_rank_core::{match_trades_with, CsvParser, LotMethod, MatchOptions, MatchedTrade, Side, Trade, TradingAnalytics, DEFAULT_EXCHANGE_TZ};
+
+mod common;
+use common::{dec, fill, fixture};
+
+/// Three 100-share buys at 200, 210 and 190, then a 150-share sale at 205
+/// naming the 190 lot.
+fn lots_fixture() -> Vec<Trade> {
+    let report = CsvParser::new().parse_file(&fixture("trades_specific_lots.csv")).unwrap();
+    assert!(report.diagnostics.is_empty());
+    report.trades
+}
+
+/// Round trips in the order the closing fill consumed the lots.
+fn match_lots(trades: &[Trade], method: LotMethod) -> Vec<MatchedTrade> {
+    let options = MatchOptions::intraday(DEFAULT_EXCHANGE_TZ).with_lot_method(method);
+    match_trades_with(trades, options)
+}
+
+fn entry_prices(matched: &[MatchedTrade]) -> Vec<Decimal> {
+    let mut prices: Vec<Decimal> = matched.iter().map(|m| m.entry_price).collect();
+    prices.sort();
+    prices
+}
+
+fn gross(matched: &[MatchedTrade]) -> Decimal {
+    matched.iter().map(|m| m.gross_pnl).sum()
+}
+
+#[test]
+fn reads_the_lot_a_fill_closes() {
+    let trades = lots_fixture();
+    assert_eq!(trades[2].execution_id.as_deref(), Some("E3"));
+    assert_eq!(trades[3].closes_lot.as_deref(), Some("E3"));
+    assert!(trades[0].closes_lot.is_none());
+}
+
+#[test]
+fn each_method_closes_different_lots() {
+    let trades = lots_fixture();
+
+    let fifo = match_lots(&trades, LotMethod::Fifo);
+    assert_eq!(entry_prices(&fifo), [dec("200"), dec("210")]);
+    assert_eq!(gross(&fifo), dec("250"));
+
+    let lifo = match_lots(&trades, LotMethod::Lifo);
+    assert_eq!(entry_prices(&lifo), [dec("190"), dec("210")]);
+    assert_eq!(gross(&lifo), dec("1250"));
+
+    let specific = match_lots(&trades, LotMethod::SpecificId);
+    assert_eq!(entry_prices(&specific), [dec("190"), dec("200")]);
+    assert_eq!(gross(&specific), dec("1750"));
+
+    // Average cost pools the 300 shares at 200 into a single round trip
+    let average = match_lots(&trades, LotMethod::Average);
+    assert_eq!(average.len(), 1);
+    assert_eq!(average[0].entry_price, dec("200"));
+    assert_eq!(average[0].entry_fills, 3);
+    assert_eq!(gross(&average), dec("750"));
+}
+
+#[test]
+fn lots_keep_their_own_entry_time_and_commission() {
+    let trades = lots_fixture();
+    let fifo = match_lots(&trades, LotMethod::Fifo);
+
+    let first = fifo.iter().find(|m| m.entry_price == dec("200")).unwrap();
+    assert_eq!(first.quantity, dec("100"));
+    assert_eq!(first.entry_time, trades[0].time);
+    assert_eq!(first.entry_fills, 1);
+    // Whole opening commission plus 100/150 of the closing one
+    assert_eq!(first.commission, dec("2.00"));
+
+    let second = fifo.iter().find(|m| m.entry_price == dec("210")).unwrap();
+    assert_eq!(second.quantity, dec("50"));
+    assert_eq!(second.entry_time.with_timezone(&DEFAULT_EXCHANGE_TZ).time(), NaiveTime::from_hms_opt(10, 15, 0).unwrap());
+    assert_eq!(second.commission, dec("1.00"));
+}
+
+#[test]
+fn unknown_lot_ids_fall_back_to_fifo() {
+    let mut trades = lots_fixture();
+    trades[3].closes_lot = Some("E9".to_string());
+    let specific = match_lots(&trades, LotMethod::SpecificId);
+    assert_eq!(entry_prices(&specific), [dec("200"), dec("210")]);
+}
+
+#[test]
+fn zero_share_fills_are_skipped() {
+    // A 0-share fill with a commission, opening and then closing a position
+    let zero = |side, time| Trade { commission: Decimal::ONE, ..fill(side, 0, "100", time) };
+    let trades = vec![
+        zero(Side::Buy, "2024-01-02 09:30:00"),
+        fill(Side::Buy, 100, "100", "2024-01-02 09:35:00"),
+        zero(Side::Sell, "2024-01-02 09:40:00"),
+        fill(Side::Sell, 100, "101", "2024-01-02 09:45:00"),
+    ];
+
+    for method in [LotMethod::Fifo, LotMethod::Lifo, LotMethod::Average, LotMethod::SpecificId] {
+        let matched = match_lots(&trades, method);
+        assert_eq!(matched.len(), 1, "{}", method);
+        assert_eq!((matched[0].quantity, matched[0].gross_pnl, matched[0].commission), (dec("100"), dec("100"), Decimal::ZERO), "{}", method);
+    }
+    let summary = TradingAnalytics::analyze_trades_with(&trades, MatchOptions::default());
+    assert_eq!(summary.total_trades, 1);
+}
+
+#[test]
+fn lot_methods_parse_by_name() {
+    assert_eq!(LotMethod::from_name("FIFO"), Some(LotMethod::Fifo));
+    assert_eq!(LotMethod::from_name("specific_id"), Some(LotMethod::SpecificId));
+    assert_eq!(LotMethod::from_name("hifo"), None);
+    for method in LotMethod::ALL {
+        assert_eq!(LotMethod::from_name(method.name()), Some(method));
+    }
+}
@@ -0,0 +1,52 @@
+use trader_rank_core::parser::csv_parser::FileFormat;
+use trader_rank_core::parser::DiagnosticKind;
+use trader_rank_core::CsvParser;
+
+mod common;
+use common::{fixture, temp_dir};
+
+#[test]
+fn bad_rows_are_reported_instead_of_dropped_silently() {
+    let report = CsvParser::new().parse_file(&fixture("trades_with_bad_rows.csv")).unwrap();
+
+    assert_eq!(report.file_name, "trades_with_bad_rows.csv");
+    assert_eq!(report.format, FileFormat::Trades);
+    assert!(!report.format_reasons.is_empty());
+    assert_eq!(report.trades.len(), 2);
+
+    let found: Vec<(usize, DiagnosticKind)> = report.diagnostics.iter().map(|d| (d.line, d.kind)).collect();
+    assert_eq!(found, [
+        (3, DiagnosticKind::InvalidSide),
+        (4, DiagnosticKind::InvalidNumber),
+        (6, DiagnosticKind::MissingValue),
+        (7, DiagnosticKind::InvalidTime),
+    ]);
+
+    // Raw text is the line as written, quotes included
+    assert_eq!(report.diagnostics[1].raw, "\"TSLA, Inc\",Sell,abc,240,2024-01-02 11:00:00,12000,0.66");
+    assert_eq!(report.diagnostics[1].to_string(), "line 4: invalid number: Invalid quantity: abc");
+}
+
+#[test]
+fn missing_columns_are_one_diagnostic_at_the_header() {
+    let path = temp_dir("parse_report").join("no_time.csv");
+    std::fs::write(&path, "Symbol,Side,Quantity,Price\nAAPL,Buy,100,185.50\n").unwrap();
+
+    let report = CsvParser::new().parse_file(&path).unwrap();
+    assert!(report.trades.is_empty());
+    assert_eq!(report.diagnostics.len(), 1);
+    assert_eq!(report.diagnostics[0].kind, DiagnosticKind::MissingColumns);
+    assert_eq!(report.diagnostics[0].line, 1);
+    assert!(report.diagnostics[0].message.contains("Time"));
+}
+
+#[test]
+fn workbook_diagnostics_use_sheet_row_numbers() {
+    // The sheet's data starts on row 3
+    let report = CsvParser::new().parse_file(&fixture("trades_below_row_1.xlsx")).unwrap();
+
+    assert_eq!(report.format, FileFormat::Trades);
+    assert_eq!(report.trades.len(), 2);
+    let found: Vec<(usize, DiagnosticKind)> = report.diagnostics.iter().map(|d| (d.line, d.kind)).collect();
+    assert_eq!(found, [(5, DiagnosticKind::InvalidSide)]);
+}
@@ -0,0 +1,93 @@
+use trader_rank_core::exchange_time::parse_tz;
+use trader_rank_core::parser::csv_parser::FileFormat;
+use trader_rank_core::parser::report::Detection;
+use trader_rank_core::parser::DiagnosticKind;
+use trader_rank_core::{match_trades, CsvParser, ImportProfile, ParseReport, Side, DEFAULT_EXCHANGE_TZ};
+
+mod common;
+use common::{dec, fixture};
+
+fn parser() -> CsvParser {
+    CsvParser::with_profiles(ImportProfile::load_dir(&fixture("profiles")).unwrap())
+}
+
+#[test]
+fn profiles_load_from_toml_and_json() {
+    let profiles = ImportProfile::load_dir(&fixture("profiles")).unwrap();
+    let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["Preamble Broker", "Signed Broker"]);
+}
+
+#[test]
+fn header_is_found_below_a_preamble() {
+    let report = parser().parse_file(&fixture("profile_preamble.csv")).unwrap();
+
+    assert_eq!(report.format, FileFormat::Profile("Preamble Broker".to_string()));
+    assert!(report.format_reasons[0].starts_with("line 4 "), "{:?}", report.format_reasons);
+    assert_eq!(report.trades.len(), 2);
+    assert_eq!(report.trades[0].side, Side::Buy);
+    assert_eq!(report.trades[1].side, Side::Sell);
+    assert_eq!(report.trades[0].time.to_rfc3339(), "2024-03-08T14:45:00+00:00");
+    // Costs written as negative cash flows are positive commissions
+    assert_eq!(report.trades[0].commission, dec("1.00"));
+}
+
+#[test]
+fn times_in_the_spring_forward_gap_are_invalid() {
+    let report = parser().parse_file(&fixture("profile_preamble.csv")).unwrap();
+
+    assert_eq!(report.diagnostics.len(), 1);
+    let diagnostic = &report.diagnostics[0];
+    assert_eq!((diagnostic.line, diagnostic.kind), (7, DiagnosticKind::InvalidTime));
+    assert!(diagnostic.message.contains("does not exist in America/New_York"), "{}", diagnostic.message);
+}
+
+#[test]
+fn signed_quantities_give_the_side_without_a_side_column() {
+    let report = parser().parse_file(&fixture("profile_signed.csv")).unwrap();
+
+    assert_eq!(report.format, FileFormat::Profile("Signed Broker".to_string()));
+    assert!(report.diagnostics.is_empty(), "{:?}", report.diagnostics);
+    let sides: Vec<&Side> = report.trades.iter().map(|t| &t.side).collect();
+    assert_eq!(sides, [&Side::Sell, &Side::Buy]);
+    assert!(report.trades.iter().all(|t| t.quantity == dec("50")));
+    // A positive amount in a negative-cost column is a rebate
+    assert_eq!(report.trades[0].commission, dec("0.75"));
+    assert_eq!(report.trades[1].commission, dec("-0.25"));
+
+    let matched = match_trades(&report.trades, DEFAULT_EXCHANGE_TZ);
+    assert_eq!(matched.len(), 1);
+    assert_eq!(matched[0].side, "Short");
+    assert_eq!(matched[0].gross_pnl, dec("100"));
+    assert_eq!(matched[0].commission, dec("0.50"));
+}
+
+#[test]
+fn profiles_without_a_timezone_use_the_source_timezone() {
+    // 2024-03-11 10:00 in New York (EDT), not in UTC
+    let report = parser().parse_file(&fixture("profile_signed.csv")).unwrap();
+    assert_eq!(report.trades[0].time.to_rfc3339(), "2024-03-11T14:00:00+00:00");
+
+    let chicago = parser().with_timezone(parse_tz("America/Chicago").unwrap());
+    let report = chicago.parse_file(&fixture("profile_signed.csv")).unwrap();
+    assert_eq!(report.trades[0].time.to_rfc3339(), "2024-03-11T15:00:00+00:00");
+
+    // A declared timezone wins over the source timezone
+    let report = chicago.parse_file(&fixture("profile_preamble.csv")).unwrap();
+    assert_eq!(report.trades[0].time.to_rfc3339(), "2024-03-08T14:45:00+00:00");
+}
+
+#[test]
+fn rows_without_the_header_signature_are_reported() {
+    let profiles = ImportProfile::load_dir(&fixture("profiles")).unwrap();
+    let rows = CsvParser::read_csv_rows(&fixture("profile_signed.csv")).unwrap();
+    let detection = Detection { format: FileFormat::Profile(profiles[0].name.clone()), reasons: Vec::new() };
+    let mut report = ParseReport::new("profile_signed.csv", detection);
+
+    profiles[0].parse_rows(&rows, DEFAULT_EXCHANGE_TZ, &mut report);
+
+    assert!(report.trades.is_empty());
+    assert_eq!(report.diagnostics.len(), 1);
+    assert_eq!((report.diagnostics[0].line, report.diagnostics[0].kind), (1, DiagnosticKind::MissingColumns));
+    assert!(report.diagnostics[0].message.contains("'Preamble Broker' skipped"), "{}", report.diagnostics[0].message);
+}
@@ -0,0 +1,178 @@
+use chrono::Utc;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use trader_rank_core::storage::schema::{self, DocumentSchema, Migration, VERSION_FIELD};
+use trader_rank_core::storage::{JsonStore, ProcessedData, SqliteStore, Store};
+use trader_rank_core::{LotMethod, Side, Trade, TradingAnalytics, DEFAULT_EXCHANGE_TZ};
+
+mod common;
+use common::{fill, temp_dir};
+
+/// 100 shares with a $1 commission.
+fn stock(symbol: &str, side: Side, price: &str, time: &str) -> Trade {
+    Trade { symbol: symbol.to_string(), commission: Decimal::ONE, ..fill(side, 100, price, time) }
+}
+
+fn trades() -> Vec<Trade> {
+    vec![
+        stock("AAPL", Side::Buy, "190.10", "2024-01-08T14:35:00Z"),
+        stock("AAPL", Side::Sell, "191.25", "2024-01-08T15:10:00Z"),
+        stock("MSFT", Side::Buy, "400", "2024-02-09T14:40:00Z"),
+        stock("MSFT", Side::Sell, "398.5", "2024-02-09T16:00:00Z"),
+    ]
+}
+
+fn read(path: &Path) -> Value {
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+/// `processed_data.json` as the first releases wrote it: file names only, no
+/// monthly summaries, and none of the fields added since.
+fn legacy_processed_data() -> Value {
+    let mut summary = serde_json::to_value(TradingAnalytics::analyze_trades(&trades(), DEFAULT_EXCHANGE_TZ)).unwrap();
+    let fields = summary.as_object_mut().unwrap();
+    for added in ["monthly_summaries", "best_month", "worst_month", "accounts"] {
+        fields.remove(added);
+    }
+    json!({
+        "last_processed": "2024-03-01T12:00:00Z",
+        "processed_files": ["trades_2024.csv"],
+        "summary": summary,
+    })
+}
+
+#[test]
+fn legacy_processed_data_is_backed_up_and_upgraded() {
+    let dir = temp_dir("schema_processed");
+    let path = dir.join("processed_data.json");
+    fs::write(&path, serde_json::to_string(&legacy_processed_data()).unwrap()).unwrap();
+
+    let store = JsonStore::new(dir.clone()).unwrap();
+    let data = store.load_processed_data().unwrap().unwrap();
+    assert!(data.sources.is_empty());
+    assert_eq!(data.lot_method, LotMethod::Average);
+    assert!(!data.carry_positions);
+    assert_eq!(data.exchange_timezone, None);
+    assert_eq!(data.summary.monthly_summaries.len(), 2);
+    assert_eq!(data.summary.best_month.map(|(month, _)| month), Some((2024, 1)));
+    assert_eq!(data.summary.worst_month.map(|(month, _)| month), Some((2024, 2)));
+
+    let backup = dir.join("processed_data.json.v0.bak");
+    assert_eq!(read(&backup), legacy_processed_data());
+    let upgraded = read(&path);
+    assert_eq!(upgraded[VERSION_FIELD], 1);
+    assert!(upgraded.get("processed_files").is_none());
+
+    // Loading again does not migrate or back up a second time
+    fs::remove_file(&backup).unwrap();
+    store.load_processed_data().unwrap().unwrap();
+    assert!(!backup.exists());
+}
+
+#[test]
+fn bare_trade_lists_are_wrapped_in_a_versioned_document() {
+    let dir = temp_dir("schema_ledger");
+    fs::write(dir.join("trades.json"), serde_json::to_string(&trades()).unwrap()).unwrap();
+
+    let store = JsonStore::new(dir.clone()).unwrap();
+    assert_eq!(store.load_trades().unwrap().unwrap().len(), 4);
+    assert!(dir.join("trades.json.v0.bak").exists());
+
+    let upgraded = read(&dir.join("trades.json"));
+    assert_eq!(upgraded[VERSION_FIELD], 1);
+    assert_eq!(upgraded["trades"].as_array().unwrap().len(), 4);
+
+    store.save_matched_trades(&[]).unwrap();
+    assert_eq!(read(&dir.join("matched_trades.json")), json!({ "schema_version": 1, "matched_trades": [] }));
+}
+
+#[test]
+fn documents_from_a_newer_version_are_not_read_or_overwritten() {
+    let dir = temp_dir("schema_newer");
+    let path = dir.join("processed_data.json");
+    let newer = json!({ "schema_version": 99, "summary": "reshaped" });
+    fs::write(&path, newer.to_string()).unwrap();
+
+    let store = JsonStore::new(dir.clone()).unwrap();
+    let error = format!("{:#}", store.load_processed_data().unwrap_err());
+    assert!(error.contains("newer version of TraderRank"), "{}", error);
+    assert!(error.contains("schema version 99"), "{}", error);
+
+    let data = ProcessedData {
+        last_processed: Utc::now(),
+        sources: Vec::new(),
+        summary: TradingAnalytics::analyze_trades(&trades(), DEFAULT_EXCHANGE_TZ),
+        exchange_timezone: Some(DEFAULT_EXCHANGE_TZ),
+        account_summaries: Default::default(),
+        carry_positions: false,
+        lot_method: LotMethod::Average,
+    };
+    let error = format!("{:#}", store.save_processed_data(&data).unwrap_err());
+    assert!(error.contains("Refusing to overwrite"), "{}", error);
+    assert_eq!(read(&path), newer);
+    assert!(!schema::backup_path(&path, 99).exists());
+}
+
+/// A document whose version 1 adds a `theme`, kept in another directory before that.
+const MOVED: DocumentSchema = DocumentSchema {
+    name: "settings",
+    migrations: &[Migration {
+        to: 1,
+        description: "add a theme",
+        apply: |value| {
+            schema::object_mut(value)?.entry("theme").or_insert(json!("dark"));
+            Ok(())
+        },
+    }],
+};
+
+#[test]
+fn legacy_documents_are_upgraded_into_their_new_place() {
+    let dir = temp_dir("schema_moved");
+    let legacy = dir.join("Data/desktop_settings.json");
+    let path = dir.join("AppData/settings.json");
+    fs::create_dir_all(legacy.parent().unwrap()).unwrap();
+    fs::write(&legacy, json!({ "lot_method": "fifo" }).to_string()).unwrap();
+
+    let loaded: Value = MOVED.load_or_move(&path, Some(&legacy)).unwrap().unwrap();
+    assert_eq!(loaded, json!({ "lot_method": "fifo", "theme": "dark", "schema_version": 1 }));
+    assert_eq!(read(&path), loaded);
+    assert_eq!(read(&legacy), json!({ "lot_method": "fifo" }));
+
+    // Once moved, the new file is the one read
+    fs::write(&path, json!({ "schema_version": 1, "theme": "light" }).to_string()).unwrap();
+    let loaded: Value = MOVED.load_or_move(&path, Some(&legacy)).unwrap().unwrap();
+    assert_eq!(loaded["theme"], "light");
+
+    let missing = dir.join("none.json");
+    assert!(MOVED.load_or_move::<Value>(&missing, Some(&missing)).unwrap().is_none());
+}
+
+#[test]
+fn older_databases_are_backed_up_and_upgraded() {
+    let dir = temp_dir("schema_database");
+    let path = dir.join(SqliteStore::FILE_NAME);
+    {
+        // Version 0 recorded source files by name only
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE source_files (name TEXT PRIMARY KEY, processed_at TEXT NOT NULL);
+             INSERT INTO source_files VALUES ('trades_2024.csv', '2024-03-01T12:00:00Z');",
+        )
+        .unwrap();
+    }
+
+    let store = SqliteStore::open(&path).unwrap();
+    store.save_trades(&trades()).unwrap();
+    assert!(store.load_sources().unwrap().is_empty());
+    assert!(dir.join("traderrank.db.v0.bak").exists());
+    drop(store);
+
+    let conn = rusqlite::Connection::open(&path).unwrap();
+    conn.pragma_update(None, "user_version", 99).unwrap();
+    drop(conn);
+    let error = format!("{:#}", SqliteStore::open(&path).err().unwrap());
+    assert!(error.contains("newer version of TraderRank"), "{}", error);
+}
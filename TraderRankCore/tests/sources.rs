@@ -0,0 +1,134 @@
+use std::fs;
+use std::time::{Duration, SystemTime};
+use trader_rank_core::dedup::dedupe_sources;
+use trader_rank_core::parser::CsvParser;
+use trader_rank_core::storage::{scan_sources, SourceFile, SourceStatus, StoreKind};
+use trader_rank_core::{Trade, TradingAnalytics, DEFAULT_EXCHANGE_TZ};
+
+mod common;
+use common::temp_dir;
+
+const HEADER: &str = "Symbol,Side,Quantity,Fill Price,Time,Net Amount,Commission\n";
+const MONDAY: &str = "AAPL,Buy,100,185.50,2024-01-08 09:35:12,18550,1.00\n\
+                      AAPL,Sell,100,186.25,2024-01-08 10:15:00,18625,1.00\n";
+const TUESDAY: &str = "MSFT,Buy,50,400.00,2024-01-09 09:40:00,20000,1.00\n\
+                       MSFT,Sell,50,398.50,2024-01-09 11:00:00,19925,1.00\n";
+
+fn statuses(dir: &std::path::Path, known: &[SourceFile]) -> Vec<(String, SourceStatus)> {
+    scan_sources(dir, known)
+        .unwrap()
+        .into_iter()
+        .map(|scan| (scan.file.name, scan.status))
+        .collect()
+}
+
+/// The file's fills, attributed to it as the CLI does when importing.
+fn parse(path: &std::path::Path) -> Vec<Trade> {
+    let mut trades = CsvParser::new().parse_file(path).unwrap().trades;
+    for trade in &mut trades {
+        trade.source = Some(path.file_name().unwrap().to_string_lossy().to_string());
+    }
+    trades
+}
+
+#[test]
+fn files_are_recognised_by_content() {
+    let dir = temp_dir("sources_scan");
+    let path = dir.join("statement.csv");
+    fs::write(&path, format!("{}{}", HEADER, MONDAY)).unwrap();
+    fs::write(dir.join("notes.txt"), "not a trade file").unwrap();
+
+    assert_eq!(statuses(&dir, &[]), [("statement.csv".to_string(), SourceStatus::New)]);
+
+    let imported = SourceFile::read(&path).unwrap();
+    assert_eq!(imported.hash.len(), 64);
+    assert_eq!(statuses(&dir, std::slice::from_ref(&imported)), [("statement.csv".to_string(), SourceStatus::Unchanged)]);
+
+    // A renamed copy is recognised by its hash
+    fs::copy(&path, dir.join("statement (1).csv")).unwrap();
+    assert_eq!(
+        statuses(&dir, std::slice::from_ref(&imported)),
+        [
+            ("statement (1).csv".to_string(), SourceStatus::Copy("statement.csv".to_string())),
+            ("statement.csv".to_string(), SourceStatus::Unchanged),
+        ]
+    );
+    fs::remove_file(dir.join("statement (1).csv")).unwrap();
+
+    // Touching the file makes it be hashed again, but it is still unchanged
+    let file = fs::File::options().write(true).open(&path).unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(60)).unwrap();
+    drop(file);
+    assert_eq!(statuses(&dir, std::slice::from_ref(&imported)), [("statement.csv".to_string(), SourceStatus::Unchanged)]);
+
+    // A re-downloaded statement with more rows
+    fs::write(&path, format!("{}{}{}", HEADER, MONDAY, TUESDAY)).unwrap();
+    let scan = scan_sources(&dir, std::slice::from_ref(&imported)).unwrap();
+    assert_eq!(scan[0].status, SourceStatus::Changed);
+    assert!(scan[0].needs_import());
+    assert_ne!(scan[0].file.hash, imported.hash);
+
+    // ...and a copy of it saved alongside, which sorts first but is still the copy
+    fs::copy(&path, dir.join("statement (1).csv")).unwrap();
+    assert_eq!(
+        statuses(&dir, std::slice::from_ref(&imported)),
+        [
+            ("statement (1).csv".to_string(), SourceStatus::Copy("statement.csv".to_string())),
+            ("statement.csv".to_string(), SourceStatus::Changed),
+        ]
+    );
+}
+
+#[test]
+fn a_changed_file_merges_only_its_new_fills() {
+    let dir = temp_dir("sources_delta");
+    let path = dir.join("statement.csv");
+    fs::write(&path, format!("{}{}", HEADER, MONDAY)).unwrap();
+    let ledger = dedupe_sources(vec![Vec::new(), parse(&path)]).trades;
+    assert_eq!(ledger.len(), 2);
+
+    fs::write(&path, format!("{}{}{}", HEADER, MONDAY, TUESDAY)).unwrap();
+    let merged = dedupe_sources(vec![ledger, parse(&path)]);
+
+    assert_eq!(merged.trades.len(), 4);
+    assert_eq!(merged.duplicates.len(), 2);
+    assert!(merged.duplicates.iter().all(|d| d.kept_source == 0));
+    assert!(merged.trades.iter().all(|t| t.source.as_deref() == Some("statement.csv")));
+}
+
+#[test]
+fn forgetting_a_source_drops_its_fills() {
+    let dir = temp_dir("sources_forget");
+    let source_dir = dir.join("Source");
+    fs::create_dir_all(&source_dir).unwrap();
+    fs::write(source_dir.join("monday.csv"), format!("{}{}", HEADER, MONDAY)).unwrap();
+    fs::write(source_dir.join("tuesday.csv"), format!("{}{}", HEADER, TUESDAY)).unwrap();
+
+    for kind in StoreKind::ALL {
+        let store = kind.open(&dir.join(kind.name())).unwrap();
+
+        // Without a ledger every file is new
+        assert!(store.scan_sources(&source_dir).unwrap().iter().all(|scan| scan.status == SourceStatus::New));
+
+        let files: Vec<_> = store.scan_sources(&source_dir).unwrap();
+        let trades = dedupe_sources(files.iter().map(|scan| parse(&scan.path)).collect()).trades;
+        let mut records: Vec<_> = files.iter().map(|scan| SourceFile { trades: 2, ..scan.file.clone() }).collect();
+        // A renamed copy of monday.csv, recorded but not imported
+        records.push(SourceFile { name: "monday (1).csv".to_string(), trades: 0, ..files[0].file.clone() });
+        let summary = TradingAnalytics::analyze_trades(&trades, DEFAULT_EXCHANGE_TZ);
+        store.save_trades(&trades).unwrap();
+        store.mark_files_processed(records, summary, Default::default(), Default::default()).unwrap();
+        assert!(store.scan_sources(&source_dir).unwrap().iter().all(|scan| !scan.needs_import()));
+
+        assert!(store.forget_source("friday.csv").unwrap().is_none());
+        let forgotten = store.forget_source("monday.csv").unwrap().unwrap();
+        assert_eq!(forgotten.trades, 2);
+
+        let ledger = store.load_trades().unwrap().unwrap();
+        assert_eq!(ledger.iter().map(|t| t.symbol.as_str()).collect::<Vec<_>>(), ["MSFT", "MSFT"]);
+        assert_eq!(store.load_sources().unwrap().iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), ["tuesday.csv"]);
+
+        let statuses: Vec<_> = store.scan_sources(&source_dir).unwrap().into_iter().map(|scan| scan.status).collect();
+        assert_eq!(statuses, [SourceStatus::New, SourceStatus::Unchanged]);
+    }
+}
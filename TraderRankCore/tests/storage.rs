@@ -0,0 +1,155 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use trader_rank_core::storage::{Annotations, RConfig, SourceFile, Store, StoreKind, TradeFilter};
+use trader_rank_core::{match_trades, Side, SqliteStore, Trade, TradingAnalytics, DEFAULT_EXCHANGE_TZ};
+
+mod common;
+use common::{fill, temp_dir};
+
+/// 100 shares with a $1 commission.
+fn stock(symbol: &str, side: Side, price: &str, time: &str, account: &str) -> Trade {
+    Trade { symbol: symbol.to_string(), commission: Decimal::ONE, account: Some(account.to_string()), ..fill(side, 100, price, time) }
+}
+
+fn ledger() -> Vec<Trade> {
+    vec![
+        stock("AAPL", Side::Buy, "190.10", "2024-01-08T14:35:00Z", "U1"),
+        stock("AAPL", Side::Sell, "191.25", "2024-01-08T15:10:00.250Z", "U1"),
+        stock("MSFT", Side::Buy, "400", "2024-01-09T14:40:00Z", "U2"),
+        stock("MSFT", Side::Sell, "398.5", "2024-01-09T16:00:00Z", "U2"),
+    ]
+}
+
+fn source(name: &str, hash: &str, trades: usize) -> SourceFile {
+    SourceFile {
+        name: name.to_string(),
+        hash: hash.to_string(),
+        size: 1024,
+        modified: Some("2024-01-10T08:00:00.5Z".parse().unwrap()),
+        processed_at: "2024-01-10T09:00:00Z".parse().unwrap(),
+        trades,
+    }
+}
+
+fn stores(name: &str) -> Vec<Box<dyn Store>> {
+    StoreKind::ALL
+        .iter()
+        .map(|kind| kind.open(&temp_dir(&format!("{}_{}", name, kind.name()))).unwrap())
+        .collect()
+}
+
+#[test]
+fn trade_ledger_round_trips() {
+    for store in stores("ledger") {
+        assert!(store.load_trades().unwrap().is_none());
+        assert!(!store.has_trade_ledger().unwrap());
+
+        store.save_trades(&[]).unwrap();
+        assert_eq!(store.load_trades().unwrap().map(|t| t.len()), Some(0));
+
+        let trades = ledger();
+        store.save_trades(&trades).unwrap();
+        let loaded = store.load_trades().unwrap().unwrap();
+        assert_eq!(loaded.len(), 4);
+        assert_eq!(loaded[1].time, trades[1].time);
+        assert_eq!(loaded[3].fill_price.to_string(), "398.5");
+        assert_eq!(loaded[2].account.as_deref(), Some("U2"));
+    }
+}
+
+#[test]
+fn trades_are_queried_by_time_symbol_and_account() {
+    for store in stores("query") {
+        store.save_trades(&ledger()).unwrap();
+
+        let tuesday = TradeFilter {
+            from: Some("2024-01-09T00:00:00Z".parse().unwrap()),
+            until: Some("2024-01-10T00:00:00Z".parse().unwrap()),
+            ..TradeFilter::default()
+        };
+        let found = store.query_trades(&tuesday).unwrap();
+        assert_eq!(found.iter().map(|t| t.symbol.as_str()).collect::<Vec<_>>(), ["MSFT", "MSFT"]);
+
+        let aapl = TradeFilter { symbol: Some("AAPL".to_string()), ..TradeFilter::default() };
+        assert_eq!(store.query_trades(&aapl).unwrap().len(), 2);
+
+        // The upper bound is exclusive
+        let until_sell = TradeFilter { until: Some("2024-01-08T15:10:00.250Z".parse().unwrap()), ..TradeFilter::default() };
+        assert_eq!(store.query_trades(&until_sell).unwrap().len(), 1);
+
+        let other_account = TradeFilter { symbol: Some("AAPL".to_string()), account: Some("U2".to_string()), ..TradeFilter::default() };
+        assert!(store.query_trades(&other_account).unwrap().is_empty());
+    }
+}
+
+#[test]
+fn processed_files_matched_trades_and_annotations_round_trip() {
+    let trades = ledger();
+    let summary = TradingAnalytics::analyze_trades(&trades, DEFAULT_EXCHANGE_TZ);
+    let matched = match_trades(&trades, DEFAULT_EXCHANGE_TZ);
+
+    for store in stores("processed") {
+        assert!(store.load_processed_data().unwrap().is_none());
+        let options = trader_rank_core::MatchOptions::default();
+        store.mark_files_processed(vec![source("a.csv", "aa", 2)], summary.clone(), Default::default(), options).unwrap();
+        store.mark_files_processed(vec![source("b.xml", "bb", 2), source("a.csv", "ab", 3)], summary.clone(), Default::default(), options).unwrap();
+
+        let processed = store.load_processed_data().unwrap().unwrap();
+        assert_eq!(processed.sources.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), ["a.csv", "b.xml"]);
+        assert_eq!(processed.sources[0], source("a.csv", "ab", 3));
+        assert_eq!(store.load_sources().unwrap(), processed.sources);
+        assert_eq!(processed.summary.total_trades, summary.total_trades);
+        assert_eq!(processed.exchange_timezone, Some(DEFAULT_EXCHANGE_TZ));
+
+        store.save_matched_trades(&matched).unwrap();
+        let loaded = store.load_matched_trades().unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[1].net_pnl, matched[1].net_pnl);
+
+        assert_eq!(store.load_annotations().unwrap(), Annotations::default());
+        let mut annotations = Annotations::default();
+        annotations.exclusions.insert("day:2024-01-08".to_string(), "FOMC".to_string());
+        annotations.r_configs.push(RConfig { week_start: NaiveDate::from_ymd_opt(2024, 1, 8).unwrap(), r_value: Decimal::from(250) });
+        store.save_annotations(&annotations).unwrap();
+        assert_eq!(store.load_annotations().unwrap(), annotations);
+    }
+}
+
+#[test]
+fn sqlite_is_detected_once_a_database_exists() {
+    let dir = temp_dir("detect");
+    assert_eq!(StoreKind::detect(&dir), StoreKind::Json);
+
+    let store = SqliteStore::open(&dir.join(SqliteStore::FILE_NAME)).unwrap();
+    store.save_trades(&ledger()).unwrap();
+    drop(store);
+    assert_eq!(StoreKind::detect(&dir), StoreKind::Sqlite);
+
+    let reopened = StoreKind::detect(&dir).open(&dir).unwrap();
+    assert_eq!(reopened.load_trades().unwrap().unwrap().len(), 4);
+}
+
+#[test]
+fn clear_keeps_annotations() {
+    let trades = ledger();
+    let summary = TradingAnalytics::analyze_trades(&trades, DEFAULT_EXCHANGE_TZ);
+
+    for store in stores("clear") {
+        store.save_trades(&trades).unwrap();
+        store.mark_files_processed(vec![source("a.csv", "aa", 4)], summary.clone(), Default::default(), Default::default()).unwrap();
+        store.save_matched_trades(&match_trades(&trades, DEFAULT_EXCHANGE_TZ)).unwrap();
+        let mut annotations = Annotations::default();
+        annotations.exclusions.insert("day:2024-01-08".to_string(), "FOMC".to_string());
+        store.save_annotations(&annotations).unwrap();
+
+        store.clear().unwrap();
+        assert!(store.load_trades().unwrap().is_none());
+        assert!(store.load_processed_data().unwrap().is_none());
+        assert!(store.load_sources().unwrap().is_empty());
+        assert!(store.load_matched_trades().unwrap().is_empty());
+        assert_eq!(store.load_annotations().unwrap(), annotations);
+
+        // Clearing an empty store is not an error
+        store.clear().unwrap();
+    }
+}
@@ -0,0 +1,27 @@
+use trader_rank_core::models::DayStreaks;
+use trader_rank_core::{MatchOptions, Side, Trade, TradingAnalytics};
+
+mod common;
+use common::fill;
+
+/// One day trade per day, closed at `exit` after buying at 100.
+fn days(exits: &[&str]) -> Vec<Trade> {
+    exits.iter().enumerate()
+        .flat_map(|(i, exit)| {
+            let day = format!("2024-01-{:02}", i + 2);
+            [
+                fill(Side::Buy, 100, "100", &format!("{}T15:00:00Z", day)),
+                fill(Side::Sell, 100, exit, &format!("{}T16:00:00Z", day)),
+            ]
+        })
+        .collect()
+}
+
+#[test]
+fn day_streaks_follow_daily_pnl() {
+    let summary = TradingAnalytics::analyze_trades_with(&days(&["101", "102", "101", "99", "98", "103"]), MatchOptions::default());
+    assert_eq!(summary.day_streaks(), DayStreaks { current: 1, longest_win: 3, longest_loss: 2 });
+
+    let summary = TradingAnalytics::analyze_trades_with(&days(&["101", "99", "99"]), MatchOptions::default());
+    assert_eq!(summary.day_streaks(), DayStreaks { current: -2, longest_win: 1, longest_loss: 2 });
+}
@@ -0,0 +1,107 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use trader_rank_core::{
+    match_trades, match_trades_with, DateRange, HoldingPeriod, MatchOptions, Side, Trade, TradingAnalytics,
+    DEFAULT_EXCHANGE_TZ,
+};
+
+mod common;
+use common::fill;
+
+/// MSFT with a $1 commission.
+fn msft(side: Side, quantity: u32, price: &str, time: &str) -> Trade {
+    Trade { symbol: "MSFT".to_string(), commission: Decimal::ONE, ..fill(side, quantity, price, time) }
+}
+
+/// Bought Monday, half sold Tuesday, a Tuesday day trade, the rest sold Wednesday.
+fn overnight_hold() -> Vec<Trade> {
+    vec![
+        msft(Side::Buy, 100, "400", "2024-01-08T15:00:00Z"),
+        msft(Side::Sell, 50, "410", "2024-01-09T15:00:00Z"),
+        msft(Side::Buy, 10, "412", "2024-01-09T16:00:00Z"),
+        msft(Side::Sell, 10, "406", "2024-01-09T17:00:00Z"),
+        msft(Side::Sell, 60, "390", "2024-01-10T15:00:00Z"),
+    ]
+}
+
+#[test]
+fn overnight_holds_only_match_when_carried() {
+    let trades = overnight_hold();
+
+    // Day by day, Tuesday's sale is unmatched and Wednesday's sale opens a short
+    let intraday = match_trades(&trades, DEFAULT_EXCHANGE_TZ);
+    assert_eq!(intraday.len(), 1);
+    assert_eq!(intraday[0].holding, HoldingPeriod::Intraday);
+
+    let carried = match_trades_with(&trades, MatchOptions::swing(DEFAULT_EXCHANGE_TZ));
+    assert_eq!(carried.len(), 3);
+    assert_eq!(carried.iter().map(|m| m.quantity).sum::<Decimal>(), Decimal::from(110));
+
+    // Every close is part of the position opened on Monday
+    let monday = NaiveDate::from_ymd_opt(2024, 1, 8).unwrap();
+    assert!(carried.iter().all(|m| m.is_swing()));
+    assert!(carried.iter().all(|m| exchange_date(m.entry_time) == monday));
+    assert!(carried.iter().all(|m| m.exit_date(DEFAULT_EXCHANGE_TZ) > monday));
+}
+
+#[test]
+fn open_positions_are_returned_instead_of_printed() {
+    let trades = overnight_hold();
+    let range = DateRange::default();
+
+    // Monday's 100 shares and Wednesday's 60-share short are left at the close
+    let (summary, open) = TradingAnalytics::analyze_positions_in(&trades, MatchOptions::default(), range);
+    assert_eq!(summary.total_trades, 1);
+    let mut left: Vec<String> = open.iter().map(|p| p.to_string()).collect();
+    left.sort();
+    assert_eq!(left, ["MSFT - 100 unclosed long shares", "MSFT - 50 unclosed short shares", "MSFT - 60 unclosed short shares"]);
+
+    let (_, open) = TradingAnalytics::analyze_positions_in(&trades, MatchOptions::swing(DEFAULT_EXCHANGE_TZ), range);
+    assert_eq!(open.iter().map(|p| p.quantity).collect::<Vec<_>>(), [Decimal::from(-10)]);
+}
+
+#[test]
+fn added_lots_carry_into_the_swing_cost_basis() {
+    let trades = overnight_hold();
+    let carried = match_trades_with(&trades, MatchOptions::swing(DEFAULT_EXCHANGE_TZ));
+
+    // The Tuesday buy adds to the overnight long, so the "day trade" closes part of it
+    let tuesday_close = carried.iter().find(|m| m.exit_time == trades[3].time).unwrap();
+    assert_eq!(tuesday_close.holding, HoldingPeriod::Swing);
+    assert_eq!(tuesday_close.entry_price, Decimal::from(402));
+
+    // Wednesday closes the remaining 50 at a loss
+    let last = &carried[0];
+    assert_eq!(last.quantity, Decimal::from(50));
+    assert_eq!(last.gross_pnl, Decimal::from(-600));
+}
+
+#[test]
+fn daily_summaries_split_swing_trades_on_the_exit_day() {
+    let trades = overnight_hold();
+
+    let intraday = TradingAnalytics::analyze_trades(&trades, DEFAULT_EXCHANGE_TZ);
+    assert_eq!(intraday.total_trades, 1);
+    assert!(intraday.daily_summaries.iter().all(|d| d.swing_trades == 0));
+
+    let swing = TradingAnalytics::analyze_trades_with(&trades, MatchOptions::swing(DEFAULT_EXCHANGE_TZ));
+    assert_eq!(swing.total_trades, 3);
+    // Monday has no closes, so only Tuesday and Wednesday are summarized
+    let days: Vec<_> = swing.daily_summaries.iter().map(|d| d.date.date_naive()).collect();
+    assert_eq!(days, [NaiveDate::from_ymd_opt(2024, 1, 9).unwrap(), NaiveDate::from_ymd_opt(2024, 1, 10).unwrap()]);
+
+    let tuesday = &swing.daily_summaries[0];
+    assert_eq!(tuesday.swing_trades, 2);
+    assert_eq!(tuesday.swing_pnl, tuesday.realized_pnl);
+    // Volume counts Tuesday's fills only
+    assert_eq!(tuesday.total_volume, Decimal::from(20500 + 4120 + 4060));
+
+    let wednesday = &swing.daily_summaries[1];
+    assert_eq!(wednesday.swing_trades, 1);
+    assert_eq!(wednesday.swing_pnl, wednesday.realized_pnl);
+    assert!(wednesday.swing_pnl < Decimal::ZERO);
+}
+
+fn exchange_date(time: DateTime<Utc>) -> NaiveDate {
+    trader_rank_core::exchange_time::trading_date(time, DEFAULT_EXCHANGE_TZ)
+}
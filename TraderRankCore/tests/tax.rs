@@ -0,0 +1,107 @@
+use rust_decimal::Decimal;
+use trader_rank_core::tax::{TaxReport, Term};
+use trader_rank_core::{LotMethod, MatchOptions, Side, Trade};
+
+mod common;
+use common::{dec, fill};
+
+/// XYZ shares filled at 10:00 New York time on `date`.
+fn xyz(side: Side, quantity: u32, price: &str, date: &str) -> Trade {
+    Trade { symbol: "XYZ".to_string(), ..fill(side, quantity, price, &format!("{} 10:00:00", date)) }
+}
+
+fn report(trades: &[Trade]) -> TaxReport {
+    TaxReport::build(trades, MatchOptions::default())
+}
+
+#[test]
+fn lots_held_over_a_year_are_long_term() {
+    let trades = vec![
+        xyz(Side::Buy, 100, "10", "2023-01-03"),
+        xyz(Side::Buy, 100, "20", "2023-06-01"),
+        // Exactly one year after the first purchase is still short-term
+        xyz(Side::Sell, 100, "30", "2024-01-03"),
+        xyz(Side::Sell, 100, "30", "2024-06-03"),
+    ];
+    let report = report(&trades);
+
+    // Average cost is not a tax method, so lots are matched first in, first out
+    assert_eq!(report.lot_method, LotMethod::Fifo);
+    assert_eq!(report.lots.len(), 2);
+    assert_eq!(report.lots[0].term, Term::Short);
+    assert_eq!(report.lots[0].gain(), dec("2000"));
+    assert_eq!(report.lots[1].term, Term::Long);
+    assert_eq!(report.lots[1].acquired.to_string(), "2023-06-01");
+
+    let totals = report.totals(2024);
+    assert_eq!(totals.short_term_gain, dec("2000"));
+    assert_eq!(totals.long_term_gain, dec("1000"));
+    assert_eq!(report.tax_years(), [2024]);
+}
+
+#[test]
+fn wash_sale_loss_moves_into_the_replacement_basis() {
+    let trades = vec![
+        xyz(Side::Buy, 100, "50", "2024-03-01"),
+        xyz(Side::Sell, 100, "40", "2024-03-05"),
+        // Bought back within 30 days, and held across days before selling
+        xyz(Side::Buy, 100, "42", "2024-03-20"),
+        xyz(Side::Sell, 100, "45", "2024-04-10"),
+    ];
+    let report = report(&trades);
+    assert_eq!(report.lots.len(), 2);
+
+    let loss = &report.lots[0];
+    assert!(loss.is_wash_sale());
+    assert_eq!(loss.disallowed_loss, dec("1000"));
+    assert_eq!(loss.gain(), Decimal::ZERO);
+
+    let replacement = &report.lots[1];
+    assert_eq!(replacement.carried_loss, dec("1000"));
+    assert_eq!(replacement.cost_basis, dec("5200"));
+    assert_eq!(replacement.gain(), dec("-700"));
+
+    // Nothing is lost overall: the reported total equals the economic result
+    let totals = report.totals(2024);
+    assert_eq!(totals.short_term_gain, dec("-700"));
+    assert_eq!(totals.wash_sales, 1);
+}
+
+#[test]
+fn only_replacement_shares_within_the_window_disallow_loss() {
+    let trades = vec![
+        xyz(Side::Buy, 100, "50", "2024-03-01"),
+        xyz(Side::Sell, 100, "40", "2024-03-05"),
+        xyz(Side::Buy, 40, "41", "2024-03-25"),
+        xyz(Side::Buy, 60, "41", "2024-04-15"),
+    ];
+    let report = report(&trades);
+    assert_eq!(report.lots.len(), 1);
+    assert_eq!(report.lots[0].disallowed_loss, dec("400"));
+    assert_eq!(report.lots[0].gain(), dec("-600"));
+}
+
+#[test]
+fn writes_form_8949_rows_for_one_year() {
+    let trades = vec![
+        xyz(Side::Buy, 100, "50", "2023-12-01"),
+        xyz(Side::Sell, 100, "40", "2023-12-15"),
+        xyz(Side::Buy, 100, "42", "2023-12-20"),
+        xyz(Side::Sell, 100, "45", "2024-01-10"),
+    ];
+    let report = report(&trades);
+
+    let mut csv = Vec::new();
+    report.write_form_8949(2023, &mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert!(lines[0].starts_with("Part,(a) Description of property,(b) Date acquired"));
+    assert_eq!(lines[1], "I,100 sh XYZ,12/01/2023,12/15/2023,4000.00,5000.00,W,1000.00,0.00");
+    assert_eq!(lines[2], "I,Totals,,,4000.00,5000.00,,1000.00,0.00");
+    assert_eq!(lines.len(), 3);
+
+    let mut csv = Vec::new();
+    report.write_form_8949(2024, &mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    assert!(csv.contains("I,100 sh XYZ,12/20/2023,01/10/2024,4500.00,5200.00,,,-700.00"));
+}
@@ -0,0 +1,37 @@
+use trader_rank_core::theme::Palette;
+
+/// The custom properties of one `[data-theme="..."]` block in the desktop stylesheet.
+fn css_block(css: &str, theme: &str) -> Vec<(String, String)> {
+    let selector = format!("[data-theme=\"{}\"] {{", theme);
+    let start = css.find(&selector).unwrap() + selector.len();
+    let end = start + css[start..].find('}').unwrap();
+    css[start..end]
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("--"))
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().trim_end_matches(';').to_string()))
+        .collect()
+}
+
+#[test]
+fn palettes_match_the_desktop_stylesheet() {
+    let css = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/../TraderRankDesktop/assets/main.css")).unwrap();
+
+    for (theme, palette) in [("dark", Palette::DARK), ("light", Palette::LIGHT)] {
+        let stylesheet = css_block(&css, theme);
+        let variables = palette.css_variables();
+        for line in variables.lines() {
+            let (name, value) = line.trim_start_matches("--").split_once(": ").unwrap();
+            let value = value.trim_end_matches(';');
+            let declared = stylesheet.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str());
+            assert_eq!(declared, Some(value), "--{} in the {} theme", name, theme);
+        }
+    }
+}
+
+#[test]
+fn theme_css_defaults_to_dark() {
+    let css = Palette::theme_css();
+    assert!(css.starts_with(":root,\n[data-theme=\"dark\"] {\n--bg-primary: #0c0d14;"));
+    assert!(css.contains("[data-theme=\"light\"] {\n--bg-primary: #f3f4f6;"));
+}
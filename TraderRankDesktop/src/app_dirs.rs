@@ -13,6 +13,21 @@ pub fn imports_dir() -> Option<PathBuf> {
     app_data_dir().map(|d| d.join("imports"))
 }
 
+/// Directory for user-defined import profiles: %LOCALAPPDATA%\TraderRank\profiles\
+pub fn profiles_dir() -> Option<PathBuf> {
+    app_data_dir().map(|d| d.join("profiles"))
+}
+
+/// Directory for exported reports such as Form 8949 CSVs: %LOCALAPPDATA%\TraderRank\exports\
+pub fn exports_dir() -> Option<PathBuf> {
+    app_data_dir().map(|d| d.join("exports"))
+}
+
+/// Path for the SQLite store, when it is selected: %LOCALAPPDATA%\TraderRank\traderrank.db
+pub fn database_path() -> Option<PathBuf> {
+    app_data_dir().map(|d| d.join(trader_rank_core::SqliteStore::FILE_NAME))
+}
+
 /// Path for app settings: %LOCALAPPDATA%\TraderRank\settings.json
 pub fn settings_path() -> Option<PathBuf> {
     app_data_dir().map(|d| d.join("settings.json"))
@@ -1,15 +1,24 @@
-use crate::models::{MatchedTrade, Trade, TradingSummary};
-use crate::parser::CsvParser;
+use trader_rank_core::models::{MatchedTrade, Trade, TradingSummary};
+use trader_rank_core::parser::{self, CsvParser, ImportProfile, ParseReport};
+use trader_rank_core::parser::csv_parser::FileFormat;
+use trader_rank_core::parser::report::Detection;
+use trader_rank_core::exchange_time::Tz;
+use trader_rank_core::analytics::TradingAnalytics;
+use trader_rank_core::dedup::{dedupe_sources, DuplicateFill, DuplicateReason};
+use trader_rank_core::storage::Store;
 use crate::state::{AppState, WeeklyRConfig, SymbolStats, HourlyStats};
 use chrono::Datelike;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use crate::settings_store;
 
-/// Collect CSV files from a directory, if it exists.
-fn collect_csv_files(dir: &PathBuf) -> Vec<PathBuf> {
+/// Dropped lines listed per file in the Activity Log; the rest are counted.
+const MAX_DIAGNOSTICS_LOGGED: usize = 10;
+
+/// Collect CSV, spreadsheet and Flex XML files from a directory, if it exists.
+fn collect_trade_files(dir: &PathBuf) -> Vec<PathBuf> {
     if !dir.exists() {
         return Vec::new();
     }
@@ -17,12 +26,7 @@ fn collect_csv_files(dir: &PathBuf) -> Vec<PathBuf> {
         Ok(entries) => entries
             .filter_map(|e| e.ok())
             .map(|e| e.path())
-            .filter(|p| {
-                p.extension()
-                    .and_then(|ext| ext.to_str())
-                    .map(|ext| ext.eq_ignore_ascii_case("csv"))
-                    .unwrap_or(false)
-            })
+            .filter(|p| parser::is_trade_file(p))
             .collect(),
         Err(e) => {
             eprintln!("Failed to read directory {:?}: {}", dir, e);
@@ -31,55 +35,123 @@ fn collect_csv_files(dir: &PathBuf) -> Vec<PathBuf> {
     }
 }
 
-/// Load trades from %LOCALAPPDATA%\TraderRank\imports\ (IB Flex imports).
-fn load_trades_from_imports() -> Vec<Trade> {
+/// Load user-defined import profiles from %LOCALAPPDATA%\TraderRank\profiles\.
+/// An invalid profile is reported and no profiles are used, rather than failing the load.
+fn load_import_profiles() -> Vec<ImportProfile> {
+    let Some(dir) = crate::app_dirs::profiles_dir() else {
+        return Vec::new();
+    };
+
+    match ImportProfile::load_dir(&dir) {
+        Ok(profiles) => {
+            if !profiles.is_empty() {
+                eprintln!("Loaded {} import profile(s) from {:?}", profiles.len(), dir);
+            }
+            profiles
+        }
+        Err(e) => {
+            eprintln!("Error loading import profiles: {:#}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Load trades from %LOCALAPPDATA%\TraderRank\imports\ (IB Flex imports),
+/// with the parse report of every file read and the duplicate fills dropped.
+/// Duplicate sources index into the reports.
+fn load_trades_from_imports(exchange_tz: Tz) -> (Vec<Trade>, Vec<ParseReport>, Vec<DuplicateFill>) {
     let Some(imports) = crate::app_dirs::imports_dir() else {
         eprintln!("Could not determine imports directory.");
-        return Vec::new();
+        return (Vec::new(), Vec::new(), Vec::new());
     };
 
-    let csv_files = collect_csv_files(&imports);
-    if csv_files.is_empty() {
-        eprintln!("No CSV files in {:?}. Use the refresh button or Settings to fetch trades from IB.", imports);
-        return Vec::new();
+    let trade_files = collect_trade_files(&imports);
+    if trade_files.is_empty() {
+        eprintln!("No trade files in {:?}. Use the refresh button or Settings to fetch trades from IB.", imports);
+        return (Vec::new(), Vec::new(), Vec::new());
     }
 
-    eprintln!("Found {} CSV files in {:?}", csv_files.len(), imports);
+    eprintln!("Found {} trade files in {:?}", trade_files.len(), imports);
 
-    let mut all_trades: HashSet<Trade> = HashSet::new();
+    let parser = CsvParser::with_profiles(load_import_profiles()).with_timezone(exchange_tz);
+
+    let mut file_trades = Vec::new();
+    let mut reports = Vec::new();
     let mut total_parsed = 0usize;
 
-    for csv_path in &csv_files {
-        match CsvParser::parse_file(csv_path) {
-            Ok(trades) => {
-                let count = trades.len();
-                for trade in trades {
-                    all_trades.insert(trade);
-                }
-                total_parsed += count;
+    for csv_path in &trade_files {
+        let file_name = csv_path.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown");
+        match parser.parse_file(csv_path) {
+            Ok(mut report) => {
+                total_parsed += report.trades.len();
+                file_trades.push(std::mem::take(&mut report.trades));
+                reports.push(report);
             }
             Err(e) => {
-                let file_name = csv_path.file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("unknown");
                 eprintln!("Error parsing {}: {}", file_name, e);
+                // Unreadable files are reported as skipped so they still show up in the Activity Log
+                let detection = Detection {
+                    format: FileFormat::Unknown,
+                    reasons: vec![format!("could not be read: {:#}", e)],
+                };
+                reports.push(ParseReport::new(file_name, detection));
+                file_trades.push(Vec::new());
             }
         }
     }
 
-    let mut trades: Vec<Trade> = all_trades.into_iter().collect();
-    trades.sort_by(|a, b| a.time.cmp(&b.time));
+    let dedup = dedupe_sources(file_trades);
+
+    eprintln!("Loaded {} unique trades from {} files ({} total before dedup)",
+        dedup.trades.len(), trade_files.len(), total_parsed);
 
-    eprintln!("Loaded {} unique trades from {} CSV files ({} total before dedup)",
-        trades.len(), csv_files.len(), total_parsed);
+    (dedup.trades, reports, dedup.duplicates)
+}
 
-    trades
+/// Activity Log lines for the parse reports: one summary per file, then the
+/// first few dropped lines of each, prefixed "WARN:".
+pub fn parse_report_log_lines(reports: &[ParseReport]) -> Vec<String> {
+    let mut lines = Vec::new();
+    for report in reports {
+        lines.push(format!("{}: {}", report.file_name, report.summary()));
+        for diagnostic in report.diagnostics.iter().take(MAX_DIAGNOSTICS_LOGGED) {
+            lines.push(format!("WARN: {} {} | {}", report.file_name, diagnostic, diagnostic.raw.trim()));
+        }
+        if report.diagnostics.len() > MAX_DIAGNOSTICS_LOGGED {
+            lines.push(format!("WARN: {} ... and {} more dropped lines",
+                report.file_name, report.diagnostics.len() - MAX_DIAGNOSTICS_LOGGED));
+        }
+    }
+    lines
+}
+
+/// Activity Log lines for duplicate fills: a count, then the first few that
+/// were matched on their fields alone (no execution ID), prefixed "WARN:".
+pub fn duplicate_log_lines(duplicates: &[DuplicateFill], reports: &[ParseReport]) -> Vec<String> {
+    if duplicates.is_empty() {
+        return Vec::new();
+    }
+
+    let file_name = |source: usize| reports.get(source).map(|r| r.file_name.as_str()).unwrap_or("?");
+    let fuzzy: Vec<_> = duplicates.iter().filter(|d| d.reason == DuplicateReason::MatchingFields).collect();
+    let mut lines = vec![format!("Dropped {} duplicate fills ({} without an execution ID)", duplicates.len(), fuzzy.len())];
+    for duplicate in fuzzy.iter().take(MAX_DIAGNOSTICS_LOGGED) {
+        lines.push(format!("WARN: {} in {} duplicates {}",
+            duplicate, file_name(duplicate.dropped_source), file_name(duplicate.kept_source)));
+    }
+    if fuzzy.len() > MAX_DIAGNOSTICS_LOGGED {
+        lines.push(format!("WARN: ... and {} more duplicates", fuzzy.len() - MAX_DIAGNOSTICS_LOGGED));
+    }
+    lines
 }
 
 /// Convert CLI's TradingSummary into the desktop's AppState.
 /// When `matched_trades` is provided, symbol stats are computed accurately from round-trip data.
 /// Otherwise falls back to an approximation from daily summaries.
-pub fn trading_summary_to_app_state(summary: TradingSummary, matched_trades: &[MatchedTrade]) -> AppState {
+/// `exchange_tz` is the timezone the summary was bucketed in.
+pub fn trading_summary_to_app_state(summary: TradingSummary, matched_trades: &[MatchedTrade], exchange_tz: Tz) -> AppState {
     let daily_summaries = summary.daily_summaries;
     let weekly_summaries = summary.weekly_summaries;
     let monthly_summaries = summary.monthly_summaries;
@@ -234,7 +306,7 @@ pub fn trading_summary_to_app_state(summary: TradingSummary, matched_trades: &[M
             })
             .collect()
     };
-    symbol_stats.sort_by(|a, b| b.total_pnl.cmp(&a.total_pnl));
+    symbol_stats.sort_by_key(|s| std::cmp::Reverse(s.total_pnl));
 
     // Hourly stats — track total wins and losses per hour across all days,
     // then compute win_rate = total_wins / (total_wins + total_losses) * 100.0
@@ -293,7 +365,7 @@ pub fn trading_summary_to_app_state(summary: TradingSummary, matched_trades: &[M
         })
         .collect();
 
-    let exclusions = settings_store::load_raw()
+    let exclusions = settings_store::load_with_annotations()
         .map(|s| s.exclusions)
         .unwrap_or_default();
 
@@ -325,23 +397,63 @@ pub fn trading_summary_to_app_state(summary: TradingSummary, matched_trades: &[M
         daily_pnls,
         r_configs,
         exclusions,
+        exchange_tz,
+        carry_positions: false,
+        lot_method: Default::default(),
+        parse_reports: Vec::new(),
+        accounts: Vec::new(),
+        selected_account: None,
+        duplicate_fills: Vec::new(),
     }
 }
 
 /// Load trade data from IB Flex imports, fall back to sample data.
+/// Every view sees only the selected account's trades; a selection that no
+/// longer matches any imported account falls back to all accounts.
 pub fn load_app_state() -> AppState {
-    let trades = load_trades_from_imports();
+    let match_options = settings_store::match_options();
+    let exchange_tz = match_options.exchange_tz;
+    let (trades, parse_reports, duplicate_fills) = load_trades_from_imports(exchange_tz);
     if !trades.is_empty() {
-        eprintln!("Processing {} trades through analytics engine...", trades.len());
-        let summary = crate::analytics::TradingAnalytics::analyze_trades(&trades);
-        let matched = crate::trade_matcher::match_trades(&trades);
+        if let Some(store) = settings_store::open_database() {
+            if let Err(e) = store.save_trades(&trades) {
+                eprintln!("Failed to save trades to the database: {:#}", e);
+            }
+        }
+        let accounts = TradingAnalytics::accounts(&trades);
+        let selected_account = settings_store::selected_account().filter(|a| accounts.contains(a));
+        let trades: Vec<Trade> = match &selected_account {
+            Some(account) => {
+                eprintln!("Showing account {} only", account);
+                trades.into_iter().filter(|t| t.in_account(account)).collect()
+            }
+            None => trades,
+        };
+
+        let mode = if match_options.carry_positions { "carrying positions overnight" } else { "day trades only" };
+        eprintln!("Processing {} trades through analytics engine ({}, {}, {} lots)...", trades.len(), exchange_tz, mode, match_options.lot_method);
+        let summary = TradingAnalytics::analyze_trades_with(&trades, match_options);
+        let matched = trader_rank_core::trade_matcher::match_trades_with(&trades, match_options);
         eprintln!("Matched {} round-trip trades", matched.len());
-        let mut state = trading_summary_to_app_state(summary, &matched);
+        if let Some(store) = settings_store::open_database() {
+            if let Err(e) = store.save_matched_trades(&matched) {
+                eprintln!("Failed to save matched trades to the database: {:#}", e);
+            }
+        }
+        let mut state = trading_summary_to_app_state(summary, &matched, exchange_tz);
+        state.carry_positions = match_options.carry_positions;
+        state.lot_method = match_options.lot_method;
         state.trades = trades;
         state.matched_trades = matched;
+        state.parse_reports = parse_reports;
+        state.accounts = accounts;
+        state.selected_account = selected_account;
+        state.duplicate_fills = duplicate_fills;
         return state;
     }
 
     eprintln!("No trade data found. Use the refresh button to fetch trades from IB.");
-    crate::sample_data::generate_sample_data()
+    let mut state = crate::sample_data::generate_sample_data();
+    state.parse_reports = parse_reports;
+    state
 }
@@ -1,26 +1,14 @@
 use anyhow::{Context, Result, bail};
-use chrono::{NaiveDate, NaiveDateTime, NaiveTime, DateTime, Utc};
-use rust_decimal::Decimal;
 use std::path::PathBuf;
-use std::str::FromStr;
+use trader_rank_core::models::{Instrument, Trade};
+use trader_rank_core::exchange_time::Tz;
+use trader_rank_core::parser::flex_xml::parse_flex_trades;
 
 const SEND_URL: &str = "https://ndcdyn.interactivebrokers.com/AccountManagement/FlexWebService/SendRequest";
 const GET_URL: &str = "https://ndcdyn.interactivebrokers.com/AccountManagement/FlexWebService/GetStatement";
 const MAX_RETRIES: u32 = 5;
 const RETRY_DELAY_MS: u64 = 5000;
 
-/// A single trade execution parsed from the Flex XML response.
-struct FlexTrade {
-    symbol: String,
-    side: String,
-    quantity: Decimal,
-    price: Decimal,
-    date: NaiveDate,
-    time: Option<NaiveTime>,
-    commission: Decimal,
-    net_amount: Decimal,
-}
-
 /// Fetch trades from IB Flex Web Service and save as CSV to Data/Source/.
 /// Returns the number of trades fetched.
 pub async fn fetch_and_save(token: &str, query_id: &str) -> Result<usize> {
@@ -75,7 +63,12 @@ pub async fn fetch_and_save(token: &str, query_id: &str) -> Result<usize> {
     }
 
     // Step 3: Parse trades from XML
-    let trades = parse_flex_trades(&statement_xml)?;
+    // Flex times are in the query's reporting timezone, taken to be the exchange's
+    let exchange_tz = crate::settings_store::exchange_timezone();
+    let (trades, diagnostics) = parse_flex_trades(&statement_xml, exchange_tz)?;
+    for diagnostic in &diagnostics {
+        eprintln!("Skipped Flex trade: {}", diagnostic);
+    }
     if trades.is_empty() {
         bail!("No trades found in the Flex response. Check your Flex Query configuration — it must include the Trades section.");
     }
@@ -83,7 +76,7 @@ pub async fn fetch_and_save(token: &str, query_id: &str) -> Result<usize> {
     // Step 4: Save as CSV to %LOCALAPPDATA%\TraderRank\imports\
     let output_path = imports_dir()?;
     let csv_path = output_path.join("ib_flex_import.csv");
-    write_trades_csv(&csv_path, &trades)?;
+    write_trades_csv(&csv_path, &trades, exchange_tz)?;
 
     Ok(trades.len())
 }
@@ -102,138 +95,6 @@ fn parse_reference_code(xml: &str) -> Result<String> {
         .context("No ReferenceCode found in SendRequest response. Verify your token and query ID.")
 }
 
-/// Parse trade executions from the Flex statement XML.
-/// Handles both Activity Statement (<Trade>) and Trade Confirmation (<TradeConfirm>) formats.
-fn parse_flex_trades(xml: &str) -> Result<Vec<FlexTrade>> {
-    let doc = roxmltree::Document::parse(xml)
-        .context("Failed to parse Flex XML response")?;
-
-    let mut trades = Vec::new();
-
-    // Look for <Trade> elements (Activity Statement) and <TradeConfirm> elements
-    for node in doc.descendants() {
-        let tag = node.tag_name().name();
-        if tag != "Trade" && tag != "TradeConfirm" {
-            continue;
-        }
-
-        // Skip summary/header rows — only want EXECUTION level detail
-        if let Some(level) = node.attribute("levelOfDetail") {
-            if level != "EXECUTION" && level != "ORDER" {
-                continue;
-            }
-        }
-
-        // Only process stock and option trades (skip forex conversions, dividends, etc.)
-        if let Some(asset) = node.attribute("assetCategory") {
-            if asset == "CASH" || asset == "BOND" {
-                continue;
-            }
-        }
-
-        let symbol = match node.attribute("symbol") {
-            Some(s) if !s.is_empty() => s.to_string(),
-            _ => continue,
-        };
-
-        let buy_sell = match node.attribute("buySell") {
-            Some(s) => s.to_string(),
-            None => continue,
-        };
-
-        let side = match buy_sell.to_uppercase().as_str() {
-            "BUY" => "Buy",
-            "SELL" => "Sell",
-            "BUY (Ca.)" => "Buy",   // cancelled/corrected
-            "SELL (Ca.)" => "Sell",
-            _ => continue,
-        }.to_string();
-
-        let quantity = parse_attr_decimal(node, "quantity")?.abs();
-        if quantity == Decimal::ZERO {
-            continue;
-        }
-        let price = parse_attr_decimal(node, "tradePrice")?;
-        let commission = parse_attr_decimal(node, "ibCommission")?.abs();
-        let net_amount = parse_attr_decimal(node, "netCash")
-            .or_else(|_| parse_attr_decimal(node, "proceeds"))
-            .unwrap_or(quantity * price);
-
-        // Parse date and time
-        // IB Flex uses multiple formats:
-        //   - Combined: dateTime="YYYYMMDD;HHMMSS" (Activity Statements)
-        //   - Separate: tradeDate="YYYYMMDD" + tradeTime="HHMMSS"
-        //   - orderTime="YYYYMMDD;HHMMSS" as fallback
-        let (date, time) = parse_trade_datetime(&node)?;
-
-        trades.push(FlexTrade {
-            symbol,
-            side,
-            quantity,
-            price,
-            date,
-            time,
-            commission,
-            net_amount,
-        });
-    }
-
-    // Sort by date+time so FIFO matching works correctly
-    trades.sort_by(|a, b| {
-        let dt_a = NaiveDateTime::new(a.date, a.time.unwrap_or(NaiveTime::from_hms_opt(0, 0, 0).unwrap()));
-        let dt_b = NaiveDateTime::new(b.date, b.time.unwrap_or(NaiveTime::from_hms_opt(0, 0, 0).unwrap()));
-        dt_a.cmp(&dt_b)
-    });
-
-    Ok(trades)
-}
-
-/// Parse date and time from an IB Flex XML trade node.
-/// Tries multiple attribute names and formats.
-fn parse_trade_datetime(node: &roxmltree::Node) -> Result<(NaiveDate, Option<NaiveTime>)> {
-    // Try combined dateTime first (most common in Activity Statements): "YYYYMMDD;HHMMSS"
-    if let Some(dt_str) = node.attribute("dateTime").or_else(|| node.attribute("orderTime")) {
-        if let Some((date_part, time_part)) = dt_str.split_once(';') {
-            if let Ok(date) = NaiveDate::parse_from_str(date_part, "%Y%m%d") {
-                let time = NaiveTime::parse_from_str(time_part, "%H%M%S")
-                    .or_else(|_| NaiveTime::parse_from_str(time_part, "%H:%M:%S"))
-                    .ok();
-                return Ok((date, time));
-            }
-        }
-        // Try as date-only
-        if let Ok(date) = NaiveDate::parse_from_str(dt_str, "%Y%m%d") {
-            return Ok((date, None));
-        }
-    }
-
-    // Try separate tradeDate + tradeTime
-    let date_str = node.attribute("tradeDate")
-        .or_else(|| node.attribute("reportDate"))
-        .unwrap_or("");
-    let date = NaiveDate::parse_from_str(date_str, "%Y%m%d")
-        .or_else(|_| NaiveDate::parse_from_str(date_str, "%Y-%m-%d"))
-        .with_context(|| format!("Invalid trade date: '{}' (no dateTime or tradeDate found)", date_str))?;
-
-    let time = node.attribute("tradeTime").and_then(|t| {
-        NaiveTime::parse_from_str(t, "%H%M%S")
-            .or_else(|_| NaiveTime::parse_from_str(t, "%H:%M:%S"))
-            .ok()
-    });
-
-    Ok((date, time))
-}
-
-/// Parse a Decimal from an XML attribute, returning ZERO for empty/missing.
-fn parse_attr_decimal(node: roxmltree::Node, attr: &str) -> Result<Decimal> {
-    let val = node.attribute(attr).unwrap_or("0");
-    if val.is_empty() || val == "--" || val == "-" {
-        return Ok(Decimal::ZERO);
-    }
-    Decimal::from_str(val)
-        .with_context(|| format!("Invalid decimal in {}: {}", attr, val))
-}
-
 /// Extract text content between <Tag>...</Tag> using simple string search.
 /// Used for the simple SendRequest response (not the full statement).
 fn extract_xml_text(xml: &str, tag: &str) -> Option<String> {
@@ -253,33 +114,42 @@ fn imports_dir() -> Result<PathBuf> {
     Ok(dir)
 }
 
-/// Format a FlexTrade as a CSV line.
-fn trade_to_csv_line(t: &FlexTrade, index: usize) -> String {
-    let time = if let Some(tm) = t.time {
-        NaiveDateTime::new(t.date, tm)
+/// Format a trade as a CSV line, with its time as wall-clock time in `tz`
+/// (read back by the generic Trades parser in the same timezone).
+fn trade_to_csv_line(t: &Trade, tz: Tz) -> String {
+    let mut fields = vec![
+        t.symbol.clone(),
+        t.side.to_string(),
+        t.quantity.to_string(),
+        t.fill_price.to_string(),
+        t.time.with_timezone(&tz).format("%Y-%m-%d %H:%M:%S").to_string(),
+        t.net_amount.to_string(),
+        t.commission.to_string(),
+    ];
+    // USD stocks leave the instrument columns blank
+    if t.instrument == Instrument::stock() {
+        fields.extend([String::new(), String::new(), String::new()]);
     } else {
-        let secs = (index as u32 / 1000) % 30;
-        let millis = index as u32 % 1000;
-        let tm = NaiveTime::from_hms_milli_opt(9, 30 + secs, 0, millis).unwrap();
-        NaiveDateTime::new(t.date, tm)
-    };
-    let dt: DateTime<Utc> = DateTime::from_naive_utc_and_offset(time, Utc);
+        fields.extend([
+            t.instrument.asset_class.to_string(),
+            t.instrument.multiplier.to_string(),
+            t.instrument.currency.clone(),
+        ]);
+    }
+    fields.push(t.account.clone().unwrap_or_default());
+    fields.push(t.execution_id.clone().unwrap_or_default());
 
-    format!(
-        "{},{},{},{},{},{},{}",
-        t.symbol,
-        t.side,
-        t.quantity,
-        t.price,
-        dt.format("%Y-%m-%d %H:%M:%S"),
-        t.net_amount,
-        t.commission,
-    )
+    // Trailing blank columns are omitted so lines match those written before
+    // the columns existed, keeping deduplication intact
+    while fields.last().is_some_and(|f| f.is_empty()) {
+        fields.pop();
+    }
+    fields.join(",")
 }
 
 /// Write trades as CSV, merging with any existing data to avoid losing
 /// previously fetched trades. Deduplicates by the full CSV line.
-fn write_trades_csv(path: &PathBuf, trades: &[FlexTrade]) -> Result<()> {
+fn write_trades_csv(path: &PathBuf, trades: &[Trade], tz: Tz) -> Result<()> {
     use std::collections::HashSet;
 
     // Read existing lines (skip header) if the file already exists
@@ -299,9 +169,13 @@ fn write_trades_csv(path: &PathBuf, trades: &[FlexTrade]) -> Result<()> {
 
     // Add new trades, deduplicating
     let mut new_count = 0usize;
-    for (i, t) in trades.iter().enumerate() {
-        let line = trade_to_csv_line(t, i);
-        if existing_lines.insert(line) {
+    for t in trades {
+        let line = trade_to_csv_line(t, tz);
+        // A line saved before accounts and execution IDs were recorded is replaced, not duplicated
+        let legacy = Trade { account: None, execution_id: None, ..t.clone() };
+        let legacy_line = trade_to_csv_line(&legacy, tz);
+        let upgraded = legacy_line != line && existing_lines.remove(&legacy_line);
+        if existing_lines.insert(line) && !upgraded {
             new_count += 1;
         }
     }
@@ -315,7 +189,7 @@ fn write_trades_csv(path: &PathBuf, trades: &[FlexTrade]) -> Result<()> {
     });
 
     let mut output = Vec::with_capacity(all_lines.len() + 1);
-    output.push("Symbol,Side,Qty,Fill Price,Time,Net Amount,Commission".to_string());
+    output.push("Symbol,Side,Qty,Fill Price,Time,Net Amount,Commission,Asset Class,Multiplier,Currency,Account,Execution ID".to_string());
     output.extend(all_lines);
 
     std::fs::write(path, output.join("\n"))
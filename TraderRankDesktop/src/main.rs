@@ -1,12 +1,8 @@
 #![allow(non_snake_case)]
 
 mod theme;
-mod models;
-mod analytics;
-mod parser;
 mod sample_data;
 mod data_loader;
-mod trade_matcher;
 mod flex_fetcher;
 mod app_dirs;
 mod state;
@@ -57,8 +53,6 @@ fn App() -> Element {
 
     let _theme = use_context_provider(|| Signal::new(initial_theme));
 
-    let _log = use_context_provider(|| Signal::new(Vec::<(String, String)>::new())); // (timestamp, message)
-
     let _state = use_context_provider(|| {
         let mut app_state = data_loader::load_app_state();
         // Apply saved R-configs if available
@@ -74,6 +68,12 @@ fn App() -> Element {
         Signal::new(app_state)
     });
 
+    let _log = use_context_provider(|| {
+        let mut log = Signal::new(Vec::<(String, String)>::new()); // (timestamp, message)
+        log_import_results(&mut log, &_state.read());
+        log
+    });
+
     rsx! {
         Router::<Route> {}
     }
@@ -83,6 +83,7 @@ fn App() -> Element {
 fn AppLayout() -> Element {
     let theme = use_context::<Signal<Theme>>();
     let theme_str = theme.read().as_str();
+    let settings_error = settings_store::settings_error();
 
     rsx! {
         style { "{CSS}" }
@@ -105,11 +106,17 @@ fn AppLayout() -> Element {
                     Link { class: "nav-tab", to: Route::Settings {}, "Settings" }
                 }
                 div { class: "nav-right",
+                    AccountSelector {}
                     RefreshButton {}
                     ThemeToggle {}
                 }
             }
 
+            // Settings that could not be loaded are not saved over, so say why
+            if let Some(error) = settings_error {
+                div { class: "settings-error", "\u{26A0}\u{FE0F} {error}" }
+            }
+
             // Main content
             div { class: "main-content",
                 Outlet::<Route> {}
@@ -142,6 +149,38 @@ fn ThemeToggle() -> Element {
     }
 }
 
+/// Filters every view to one account; shown only when the imports hold several
+#[component]
+fn AccountSelector() -> Element {
+    let mut state = use_context::<Signal<state::AppState>>();
+    let mut app_log = use_context::<Signal<Vec<(String, String)>>>();
+    let accounts = state.read().accounts.clone();
+    let current = state.read().selected_account.clone().unwrap_or_default();
+
+    if accounts.len() < 2 {
+        return rsx! {};
+    }
+
+    rsx! {
+        select {
+            class: "account-select",
+            title: "Account",
+            value: "{current}",
+            onchange: move |e: Event<FormData>| {
+                let account = e.value().to_string();
+                settings_store::update(|s| s.selected_account = account.clone());
+                let label = if account.is_empty() { "all accounts".to_string() } else { format!("account {}", account) };
+                log_message(&mut app_log, &format!("Showing {}. Reloading...", label));
+                reload_app_state(&mut state, &mut app_log);
+            },
+            option { value: "", selected: current.is_empty(), "All accounts" }
+            for account in accounts.iter() {
+                option { value: "{account}", selected: *account == current, "{account}" }
+            }
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq)]
 enum RefreshState {
     Idle,
@@ -150,9 +189,10 @@ enum RefreshState {
     Error,
 }
 
-/// Helper: reload AppState preserving user R-configs
-fn reload_app_state(state: &mut Signal<state::AppState>) {
+/// Helper: reload AppState preserving user R-configs, logging how each file was read
+fn reload_app_state(state: &mut Signal<state::AppState>, log: &mut Signal<Vec<(String, String)>>) {
     let mut new_state = data_loader::load_app_state();
+    log_import_results(log, &new_state);
     let old_configs = state.read().r_configs.clone();
     for saved_r in &old_configs {
         if let Some(existing) = new_state.r_configs.iter_mut().find(|c| c.week_start == saved_r.week_start) {
@@ -162,6 +202,16 @@ fn reload_app_state(state: &mut Signal<state::AppState>) {
     state.set(new_state);
 }
 
+/// Push the per-file parse outcomes, dropped lines and duplicate fills to the app log
+fn log_import_results(log: &mut Signal<Vec<(String, String)>>, state: &state::AppState) {
+    let lines = data_loader::parse_report_log_lines(&state.parse_reports)
+        .into_iter()
+        .chain(data_loader::duplicate_log_lines(&state.duplicate_fills, &state.parse_reports));
+    for line in lines {
+        log_message(log, &line);
+    }
+}
+
 /// Push a timestamped message to the app log (visible in Settings)
 pub fn log_message(log: &mut Signal<Vec<(String, String)>>, msg: &str) {
     let ts = chrono::Local::now().format("%H:%M:%S").to_string();
@@ -205,7 +255,7 @@ fn RefreshButton() -> Element {
 
                 if token.is_empty() || qid.is_empty() {
                     log_message(&mut app_log, "Reloading local trade data...");
-                    reload_app_state(&mut state);
+                    reload_app_state(&mut state, &mut app_log);
                     log_message(&mut app_log, "Data reloaded from local imports.");
                     status.set(RefreshState::Done);
                     spawn(async move {
@@ -221,7 +271,7 @@ fn RefreshButton() -> Element {
                     match flex_fetcher::fetch_and_save(&token, &qid).await {
                         Ok(count) => {
                             log_message(&mut app_log, &format!("Fetched {} trades from IB. Reloading...", count));
-                            reload_app_state(&mut state);
+                            reload_app_state(&mut state, &mut app_log);
                             log_message(&mut app_log, "Data reloaded successfully.");
                             status.set(RefreshState::Done);
                         }
@@ -1,4 +1,4 @@
-use crate::models::*;
+use trader_rank_core::models::*;
 use crate::state::{AppState, WeeklyRConfig, SymbolStats, HourlyStats};
 use chrono::{Datelike, NaiveDate, TimeZone, Utc};
 use rust_decimal::Decimal;
@@ -14,22 +14,25 @@ fn make_date(y: i32, m: u32, d: u32) -> chrono::DateTime<Utc> {
     )
 }
 
+/// A New York wall-clock time, as the instant it refers to.
 fn make_datetime(y: i32, m: u32, d: u32, h: u32, min: u32, s: u32) -> chrono::DateTime<Utc> {
-    Utc.from_utc_datetime(
-        &NaiveDate::from_ymd_opt(y, m, d)
-            .unwrap()
-            .and_hms_opt(h, min, s)
-            .unwrap(),
-    )
+    let local = NaiveDate::from_ymd_opt(y, m, d)
+        .unwrap()
+        .and_hms_opt(h, min, s)
+        .unwrap();
+    trader_rank_core::exchange_time::local_to_utc(local, trader_rank_core::DEFAULT_EXCHANGE_TZ).unwrap()
 }
 
+/// (month, day, trades, wins, pnl, gross_pnl, commission, volume, symbols)
+type SampleDay = (u32, u32, u32, u32, i64, i64, i64, i64, Vec<&'static str>);
+
 pub fn generate_sample_data() -> AppState {
     // Generate 8 weeks of daily summaries (about 40 trading days)
     let mut daily_summaries = Vec::new();
     let mut all_trades = Vec::new();
 
     // Structured daily data: (month, day, trades, wins, pnl, gross_pnl, commission, volume, symbols)
-    let daily_data: Vec<(u32, u32, u32, u32, i64, i64, i64, i64, Vec<&str>)> = vec![
+    let daily_data: Vec<SampleDay> = vec![
         // Week 1 - Jan 27-31
         (1, 27, 24, 15, 31200, 34500, 3300, 485000, vec!["AAPL", "NVDA", "SPY"]),
         (1, 28, 18, 10, -12800, -9200, 3600, 362000, vec!["TSLA", "AMD", "SPY"]),
@@ -174,6 +177,12 @@ pub fn generate_sample_data() -> AppState {
                     time,
                     net_amount: trade_pnl,
                     commission: trade_comm,
+                    realized_pnl: None,
+                    instrument: Instrument::stock(),
+                    account: None,
+                    execution_id: None,
+                    closes_lot: None,
+                    source: None,
                 });
             }
         }
@@ -194,6 +203,8 @@ pub fn generate_sample_data() -> AppState {
             largest_loss,
             symbols_traded: syms.iter().map(|s| s.to_string()).collect(),
             time_slot_performance: time_slots,
+            swing_trades: 0,
+            swing_pnl: Decimal::ZERO,
         });
     }
 
@@ -360,7 +371,7 @@ pub fn generate_sample_data() -> AppState {
             }
         })
         .collect();
-    symbol_stats.sort_by(|a, b| b.total_pnl.cmp(&a.total_pnl));
+    symbol_stats.sort_by_key(|s| std::cmp::Reverse(s.total_pnl));
 
     // Hourly stats
     let mut hourly_map: HashMap<u32, (Decimal, u32, f64)> = HashMap::new();
@@ -434,6 +445,13 @@ pub fn generate_sample_data() -> AppState {
         daily_pnls,
         r_configs,
         exclusions: std::collections::HashMap::new(),
+        exchange_tz: trader_rank_core::DEFAULT_EXCHANGE_TZ,
+        carry_positions: false,
+        lot_method: Default::default(),
+        parse_reports: Vec::new(),
+        accounts: Vec::new(),
+        selected_account: None,
+        duplicate_fills: Vec::new(),
     }
 }
 
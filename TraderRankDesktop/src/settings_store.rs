@@ -1,10 +1,34 @@
 use crate::state::WeeklyRConfig;
 use crate::theme::Theme;
+use trader_rank_core::exchange_time::{self, Tz, DEFAULT_EXCHANGE_TZ};
+use trader_rank_core::trade_matcher::{LotMethod, MatchOptions};
+use trader_rank_core::storage::schema::{self, DocumentSchema, Migration};
+use trader_rank_core::storage::{Annotations, RConfig, SqliteStore, Store, StoreKind};
 use chrono::NaiveDate;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// settings.json, upgraded on load when it is older than this build. Version 0
+/// files were kept in `Data/desktop_settings.json` and move on their upgrade.
+const SETTINGS: DocumentSchema = DocumentSchema {
+    name: "settings",
+    migrations: &[Migration {
+        to: 1,
+        description: "fill in settings older files left out",
+        apply: settings_v1,
+    }],
+};
+
+/// Why settings.json could not be loaded (e.g. it is from a newer TraderRank).
+/// While it is set, settings are not saved over the file.
+static LOAD_ERROR: Mutex<Option<String>> = Mutex::new(None);
+
+/// Why settings.json could not be saved, until a save succeeds.
+static SAVE_ERROR: Mutex<Option<String>> = Mutex::new(None);
 
 /// All persisted UI state — the entire app session
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -59,10 +83,32 @@ pub struct PersistedSettings {
     #[serde(default)]
     pub flex_query_id: String,
 
+    // Exchange timezone (IANA name): trading days and hours are bucketed in it,
+    // and built-in import formats' timestamps are read in it
+    #[serde(default = "default_exchange_timezone")]
+    pub exchange_timezone: String,
+
+    // Carry positions across days so overnight holds are matched as swing trades
+    #[serde(default)]
+    pub carry_positions: bool,
+
+    // Lot-matching method ("fifo", "lifo", "average", "specific-id"); empty for average cost
+    #[serde(default)]
+    pub lot_method: String,
+
+    // Account every view is filtered to; empty for all accounts combined
+    #[serde(default)]
+    pub selected_account: String,
+
     // Exclusions: key -> reason (optional)
     // Keys: "day:YYYY-MM-DD" for day exclusions, "trade:SYMBOL:EXIT_TIME_RFC3339" for trade exclusions
     #[serde(default)]
     pub exclusions: HashMap<String, String>,
+
+    // Storage backend ("json" or "sqlite"); empty for settings.json only.
+    // With SQLite, exclusions and R configs are also kept in the database with the trades
+    #[serde(default)]
+    pub storage_backend: String,
 }
 
 fn default_dashboard_range() -> String { "1M".to_string() }
@@ -73,6 +119,7 @@ fn default_sort_col_time() -> String { "time".to_string() }
 fn default_analytics_range() -> String { "All".to_string() }
 fn default_zoom() -> f64 { 1.0 }
 fn default_one() -> f64 { 1.0 }
+fn default_exchange_timezone() -> String { DEFAULT_EXCHANGE_TZ.name().to_string() }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PersistedRConfig {
@@ -82,30 +129,8 @@ pub struct PersistedRConfig {
 
 fn settings_path() -> Option<PathBuf> {
     // Prefer standard user directory: %LOCALAPPDATA%\TraderRank\settings.json
-    if let Some(user_path) = crate::app_dirs::settings_path() {
-        // If the user-dir settings file already exists, use it
-        if user_path.exists() {
-            return Some(user_path);
-        }
-        // If no user-dir file yet, check for legacy file and migrate
-        if let Some(legacy) = legacy_settings_path() {
-            if legacy.exists() {
-                // Migrate: copy old settings to user dir, then use user dir
-                if let Some(parent) = user_path.parent() {
-                    let _ = std::fs::create_dir_all(parent);
-                }
-                let _ = std::fs::copy(&legacy, &user_path);
-                return Some(user_path);
-            }
-        }
-        // No legacy file either — use user dir (will be created on first save)
-        if let Some(parent) = user_path.parent() {
-            let _ = std::fs::create_dir_all(parent);
-        }
-        return Some(user_path);
-    }
     // Fallback: legacy paths (no LOCALAPPDATA available)
-    legacy_settings_path()
+    crate::app_dirs::settings_path().or_else(legacy_settings_path)
 }
 
 /// Old settings path (relative to project Data/ dir). Kept for migration.
@@ -138,10 +163,39 @@ fn legacy_settings_path() -> Option<PathBuf> {
     None
 }
 
+/// Version 0 files (no `schema_version`, in the legacy `Data/` directory) have only
+/// the settings that existed when they were saved; the rest get their defaults written out.
+fn settings_v1(value: &mut Value) -> anyhow::Result<()> {
+    let Value::Object(defaults) = serde_json::to_value(serde_json::from_str::<PersistedSettings>("{}")?)? else {
+        return Ok(());
+    };
+    let settings = schema::object_mut(value)?;
+    for (key, default) in defaults {
+        settings.entry(key).or_insert(default);
+    }
+    Ok(())
+}
+
 pub fn save_all(settings: &PersistedSettings) {
+    if let Some(error) = LOAD_ERROR.lock().unwrap().clone() {
+        eprintln!("Settings not saved: {}", error);
+        return;
+    }
     let Some(path) = settings_path() else { return };
-    if let Ok(json) = serde_json::to_string_pretty(settings) {
-        let _ = std::fs::write(&path, json);
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Err(e) = SETTINGS.save(&path, settings) {
+        let message = format!("Failed to save settings: {:#}", e);
+        eprintln!("{}", message);
+        *SAVE_ERROR.lock().unwrap() = Some(message);
+        return;
+    }
+    *SAVE_ERROR.lock().unwrap() = None;
+    if let Some(store) = open_database_for(settings) {
+        if let Err(e) = store.save_annotations(&annotations(settings)) {
+            eprintln!("Failed to save annotations to the database: {:#}", e);
+        }
     }
 }
 
@@ -161,12 +215,93 @@ pub fn save_settings(theme: &Theme, r_configs: &[WeeklyRConfig]) {
 
 pub fn load_raw() -> Option<PersistedSettings> {
     let path = settings_path()?;
-    let json = std::fs::read_to_string(&path).ok()?;
-    serde_json::from_str(&json).ok()
+    // Without a settings.json yet, a legacy desktop_settings.json is upgraded into place
+    let legacy = legacy_settings_path().filter(|legacy| *legacy != path);
+    let loaded = SETTINGS.load_or_move(&path, legacy.as_deref());
+    let mut load_error = LOAD_ERROR.lock().unwrap();
+    match loaded {
+        Ok(settings) => {
+            *load_error = None;
+            settings
+        }
+        Err(e) => {
+            let message = format!("Failed to load settings: {:#}. Changes are not saved until this is fixed.", e);
+            if load_error.as_ref() != Some(&message) {
+                eprintln!("{}", message);
+            }
+            *load_error = Some(message);
+            None
+        }
+    }
+}
+
+/// The error that kept settings from loading or saving, for the UI to show.
+pub fn settings_error() -> Option<String> {
+    let load_error = LOAD_ERROR.lock().unwrap().clone();
+    load_error.or_else(|| SAVE_ERROR.lock().unwrap().clone())
+}
+
+/// Settings with exclusions and R configs read from the database when SQLite
+/// storage is selected. Used when the app state is built, not on every render.
+pub fn load_with_annotations() -> Option<PersistedSettings> {
+    let mut settings = load_raw()?;
+    let Some(store) = open_database_for(&settings) else {
+        return Some(settings);
+    };
+    match store.load_annotations() {
+        Ok(saved) if saved != Annotations::default() => {
+            settings.exclusions = saved.exclusions.into_iter().collect();
+            settings.r_configs = saved.r_configs
+                .iter()
+                .map(|c| PersistedRConfig {
+                    week_start: c.week_start.to_string(),
+                    r_value: c.r_value.to_string(),
+                })
+                .collect();
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("Failed to read annotations from the database: {:#}", e),
+    }
+    Some(settings)
+}
+
+/// Configured storage backend; JSON (settings.json only) when unset.
+pub fn storage_kind() -> StoreKind {
+    load_raw()
+        .and_then(|s| StoreKind::from_name(&s.storage_backend))
+        .unwrap_or(StoreKind::Json)
+}
+
+/// The SQLite store, when it is the configured backend.
+pub fn open_database() -> Option<SqliteStore> {
+    open_database_for(&load_raw()?)
+}
+
+fn open_database_for(settings: &PersistedSettings) -> Option<SqliteStore> {
+    if StoreKind::from_name(&settings.storage_backend) != Some(StoreKind::Sqlite) {
+        return None;
+    }
+    let path = crate::app_dirs::database_path()?;
+    SqliteStore::open(&path)
+        .map_err(|e| eprintln!("{:#}", e))
+        .ok()
+}
+
+fn annotations(settings: &PersistedSettings) -> Annotations {
+    Annotations {
+        exclusions: settings.exclusions.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+        r_configs: settings.r_configs
+            .iter()
+            .filter_map(|c| Some(RConfig {
+                week_start: c.week_start.parse().ok()?,
+                r_value: c.r_value.parse().ok()?,
+            }))
+            .collect(),
+    }
 }
 
 pub fn load_settings() -> Option<(Theme, Vec<WeeklyRConfig>)> {
-    let settings = load_raw()?;
+    let settings = load_with_annotations()?;
 
     let theme = match settings.theme.as_str() {
         "light" => Theme::Light,
@@ -186,6 +321,36 @@ pub fn load_settings() -> Option<(Theme, Vec<WeeklyRConfig>)> {
     Some((theme, r_configs))
 }
 
+/// Configured exchange timezone; America/New_York when unset or not a valid IANA name.
+pub fn exchange_timezone() -> Tz {
+    let Some(settings) = load_raw() else {
+        return DEFAULT_EXCHANGE_TZ;
+    };
+    if settings.exchange_timezone.trim().is_empty() {
+        return DEFAULT_EXCHANGE_TZ;
+    }
+    exchange_time::parse_tz(&settings.exchange_timezone).unwrap_or_else(|e| {
+        eprintln!("{}; using {}", e, DEFAULT_EXCHANGE_TZ);
+        DEFAULT_EXCHANGE_TZ
+    })
+}
+
+/// How fills are matched into round trips: in the configured exchange
+/// timezone, carrying positions across days when swing trades are enabled,
+/// with the configured lot method (average cost when unset or unknown).
+pub fn match_options() -> MatchOptions {
+    let settings = load_raw().unwrap_or_default();
+    let lot_method = LotMethod::from_name(&settings.lot_method).unwrap_or_default();
+    MatchOptions { exchange_tz: exchange_timezone(), carry_positions: settings.carry_positions, lot_method }
+}
+
+/// Account the views are filtered to, or `None` for all accounts.
+pub fn selected_account() -> Option<String> {
+    load_raw()
+        .map(|s| s.selected_account.trim().to_string())
+        .filter(|a| !a.is_empty())
+}
+
 /// Update a single field and persist. Loads current settings, applies the
 /// mutation closure, then saves.
 pub fn update<F: FnOnce(&mut PersistedSettings)>(f: F) {
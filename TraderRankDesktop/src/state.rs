@@ -1,4 +1,8 @@
-use crate::models::*;
+use trader_rank_core::models::*;
+use trader_rank_core::parser::ParseReport;
+use trader_rank_core::dedup::DuplicateFill;
+use trader_rank_core::exchange_time::Tz;
+use trader_rank_core::trade_matcher::LotMethod;
 use chrono::NaiveDate;
 use rust_decimal::Decimal;
 use std::collections::HashMap;
@@ -66,6 +70,25 @@ pub struct AppState {
 
     // Exclusions: key -> reason
     pub exclusions: HashMap<String, String>,
+
+    // Timezone trading days and hours are shown in
+    pub exchange_tz: Tz,
+
+    // Whether positions are carried overnight, so swing trades are included
+    pub carry_positions: bool,
+
+    // Which open lots closing fills are matched against
+    pub lot_method: LotMethod,
+
+    // How each imported file was read, shown in the Activity Log
+    pub parse_reports: Vec<ParseReport>,
+
+    // Accounts found in the imports, and the one every view is filtered to (None = all)
+    pub accounts: Vec<String>,
+    pub selected_account: Option<String>,
+
+    // Fills dropped as duplicates of another import, shown in the Activity Log
+    pub duplicate_fills: Vec<DuplicateFill>,
 }
 
 impl AppState {
@@ -90,9 +113,9 @@ impl AppState {
         format!("day:{}", date_str)
     }
 
-    /// Generate exclusion key for a matched trade
-    pub fn trade_exclusion_key(mt: &MatchedTrade) -> String {
-        format!("trade:{}:{}", mt.symbol, mt.exit_time.format("%Y-%m-%dT%H:%M:%S"))
+    /// Generate exclusion key for a matched trade (exit time in exchange-local time)
+    pub fn trade_exclusion_key(&self, mt: &MatchedTrade) -> String {
+        format!("trade:{}:{}", mt.symbol, mt.exit_time.with_timezone(&self.exchange_tz).format("%Y-%m-%dT%H:%M:%S"))
     }
 
     /// Check if a day is excluded
@@ -102,8 +125,8 @@ impl AppState {
 
     /// Check if a matched trade is excluded (directly or via its day)
     pub fn is_trade_excluded(&self, mt: &MatchedTrade) -> bool {
-        let day_str = mt.exit_time.date_naive().to_string();
-        self.exclusions.contains_key(&Self::trade_exclusion_key(mt))
+        let day_str = mt.exit_date(self.exchange_tz).to_string();
+        self.exclusions.contains_key(&self.trade_exclusion_key(mt))
             || self.is_day_excluded(&day_str)
     }
 
@@ -119,7 +142,7 @@ impl AppState {
     #[allow(dead_code)]
     pub fn trade_exclusion_reason(&self, mt: &MatchedTrade) -> String {
         self.exclusions
-            .get(&Self::trade_exclusion_key(mt))
+            .get(&self.trade_exclusion_key(mt))
             .cloned()
             .unwrap_or_default()
     }
@@ -3,6 +3,7 @@ use chrono::Datelike;
 use crate::components::*;
 use crate::state::AppState;
 use crate::settings_store;
+use crate::views::tax_report::TaxReportPanel;
 use rust_decimal::Decimal;
 use std::collections::HashMap;
 
@@ -14,6 +15,7 @@ enum AnalyticsTab {
     Symbols,
     TradeQuality,
     Progression,
+    Tax,
 }
 
 impl AnalyticsTab {
@@ -25,6 +27,7 @@ impl AnalyticsTab {
             AnalyticsTab::Symbols => "Symbols",
             AnalyticsTab::TradeQuality => "TradeQuality",
             AnalyticsTab::Progression => "Progression",
+            AnalyticsTab::Tax => "Tax",
         }
     }
 
@@ -35,6 +38,7 @@ impl AnalyticsTab {
             "Symbols" => AnalyticsTab::Symbols,
             "TradeQuality" => AnalyticsTab::TradeQuality,
             "Progression" => AnalyticsTab::Progression,
+            "Tax" => AnalyticsTab::Tax,
             _ => AnalyticsTab::Overview,
         }
     }
@@ -129,9 +133,9 @@ pub fn Analytics() -> Element {
     let filtered_days = &non_excluded_days[skip..];
 
     // Filter matched trades by the same date cutoff and exclusions
-    let cutoff_date = filtered_days.first().map(|d| d.date);
+    let cutoff_date = filtered_days.first().map(|d| d.date.date_naive());
     let filtered_matched: Vec<_> = data.matched_trades.iter()
-        .filter(|mt| cutoff_date.map_or(true, |c| mt.exit_time >= c))
+        .filter(|mt| cutoff_date.is_none_or(|c| mt.exit_date(data.exchange_tz) >= c))
         .filter(|mt| !data.is_trade_excluded(mt))
         .collect();
 
@@ -193,6 +197,14 @@ pub fn Analytics() -> Element {
                         },
                         "Progression"
                     }
+                    button {
+                        class: if current_tab == AnalyticsTab::Tax { "tab active" } else { "tab" },
+                        onclick: move |_| {
+                            active_tab.set(AnalyticsTab::Tax);
+                            settings_store::update(|s| s.analytics_tab = AnalyticsTab::Tax.as_str().to_string());
+                        },
+                        "Tax"
+                    }
                 }
                 div { class: "window-controls",
                     span { class: "window-info", "Range:" }
@@ -252,7 +264,7 @@ pub fn Analytics() -> Element {
             filtered_hourly_stats.sort_by_key(|h| h.hour);
 
             // Recompute monthly summaries from filtered daily data
-            let filtered_monthly = crate::analytics::TradingAnalytics::calculate_monthly_from_daily(
+            let filtered_monthly = trader_rank_core::analytics::TradingAnalytics::calculate_monthly_from_daily(
                 &filtered_days.iter().cloned().cloned().collect::<Vec<_>>()
             );
 
@@ -867,7 +879,7 @@ pub fn Analytics() -> Element {
                         total_pnl: Decimal,
                     }
 
-                    let mut buckets = vec![
+                    let mut buckets = [
                         PnlBucket { label: "< -$50", count: 0, wins: 0, total_pnl: Decimal::ZERO },
                         PnlBucket { label: "-$50 to $0", count: 0, wins: 0, total_pnl: Decimal::ZERO },
                         PnlBucket { label: "$0 to $50", count: 0, wins: 0, total_pnl: Decimal::ZERO },
@@ -988,6 +1000,10 @@ pub fn Analytics() -> Element {
                     }
                 }
 
+                AnalyticsTab::Tax => rsx! {
+                    TaxReportPanel {}
+                },
+
                 AnalyticsTab::Progression => {
                     // ── Progression ──────────────────────────────────────────
                     struct ProgressionRow {
@@ -79,6 +79,19 @@ pub fn Dashboard() -> Element {
     let total_losses: u32 = visible_summaries.iter().map(|d| d.losing_trades).sum();
     let total_commission: Decimal = visible_summaries.iter().map(|d| d.total_commission).sum();
 
+    // Intraday/swing split, when positions are carried overnight
+    let swing_split = data.carry_positions.then(|| {
+        let swing_trades: u32 = visible_summaries.iter().map(|d| d.swing_trades).sum();
+        let swing_pnl: Decimal = visible_summaries.iter().map(|d| d.swing_pnl).sum();
+        format!(
+            "Intraday: {} trades, {} \u{00B7} Swing: {} trades, {}",
+            total_trades - swing_trades,
+            format_pnl(total_pnl - swing_pnl),
+            swing_trades,
+            format_pnl(swing_pnl),
+        )
+    });
+
     let win_rate = if total_trades > 0 {
         (total_wins as f64) / (total_trades as f64) * 100.0
     } else {
@@ -172,7 +185,7 @@ pub fn Dashboard() -> Element {
     let scale = if max_abs > Decimal::ZERO { max_abs } else { Decimal::ONE };
 
     // Current week — recompute from filtered daily summaries
-    let filtered_weekly = crate::analytics::TradingAnalytics::calculate_weekly_from_daily(
+    let filtered_weekly = trader_rank_core::analytics::TradingAnalytics::calculate_weekly_from_daily(
         &all_visible.iter().cloned().cloned().collect::<Vec<_>>()
     );
     let current_week_r = data.r_configs.last().map(|c| c.r_value).unwrap_or(Decimal::new(100, 0));
@@ -195,6 +208,9 @@ pub fn Dashboard() -> Element {
             div { class: "dashboard-filter-bar",
                 span { class: "filter-label", "Showing: {range_label}" }
                 span { class: "filter-detail", "{visible_summaries.len()} trading days \u{00B7} {total_trades} trades" }
+                if let Some(split) = swing_split {
+                    span { class: "filter-detail", "{split}" }
+                }
                 div { class: "chart-range-tabs",
                     for r in ranges.iter() {
                         {
@@ -3,4 +3,5 @@ pub mod timeline;
 pub mod visual_timeline;
 pub mod trades;
 pub mod analytics;
+pub mod tax_report;
 pub mod settings;
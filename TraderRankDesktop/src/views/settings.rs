@@ -3,6 +3,7 @@ use chrono::NaiveDate;
 use crate::theme::Theme;
 use crate::state::AppState;
 use crate::settings_store;
+use trader_rank_core::{LotMethod, StoreKind};
 use rust_decimal::Decimal;
 
 fn persist(theme: &Signal<Theme>, state: &Signal<AppState>) {
@@ -11,6 +12,55 @@ fn persist(theme: &Signal<Theme>, state: &Signal<AppState>) {
     settings_store::save_settings(&t, &s.r_configs);
 }
 
+/// Persist the overnight-positions choice and re-run the analytics with it.
+fn set_carry_positions(carry: bool, state: &mut Signal<AppState>, app_log: &mut Signal<Vec<(String, String)>>) {
+    if state.read().carry_positions == carry {
+        return;
+    }
+    settings_store::update(|s| s.carry_positions = carry);
+    let message = if carry {
+        "Carrying positions overnight; swing trades included. Reloading..."
+    } else {
+        "Analyzing day trades only. Reloading..."
+    };
+    crate::log_message(app_log, message);
+    crate::reload_app_state(state, app_log);
+}
+
+/// Persist the lot-matching method and re-run the analytics with it.
+fn set_lot_method(method: LotMethod, state: &mut Signal<AppState>, app_log: &mut Signal<Vec<(String, String)>>) {
+    if state.read().lot_method == method {
+        return;
+    }
+    settings_store::update(|s| s.lot_method = method.name().to_string());
+    crate::log_message(app_log, &format!("Lot matching set to {}. Reloading...", method));
+    crate::reload_app_state(state, app_log);
+}
+
+/// Persist the storage backend. Switching to SQLite copies exclusions and R
+/// configs into the database, and the reload saves the trades there.
+fn set_storage_kind(kind: StoreKind, state: &mut Signal<AppState>, app_log: &mut Signal<Vec<(String, String)>>) {
+    if settings_store::storage_kind() == kind {
+        return;
+    }
+    settings_store::update(|s| s.storage_backend = kind.name().to_string());
+    crate::log_message(app_log, &format!("Storage set to {}. Reloading...", kind));
+    crate::reload_app_state(state, app_log);
+}
+
+/// Exchange timezones offered in Settings: (IANA name, label)
+const EXCHANGE_TIMEZONES: &[(&str, &str)] = &[
+    ("America/New_York", "New York (NYSE, Nasdaq)"),
+    ("America/Chicago", "Chicago (CME, Cboe)"),
+    ("America/Toronto", "Toronto (TSX)"),
+    ("Europe/London", "London (LSE)"),
+    ("Europe/Berlin", "Frankfurt (Xetra)"),
+    ("Asia/Tokyo", "Tokyo (TSE)"),
+    ("Asia/Hong_Kong", "Hong Kong (HKEX)"),
+    ("Australia/Sydney", "Sydney (ASX)"),
+    ("UTC", "UTC"),
+];
+
 #[derive(Clone, PartialEq)]
 enum FetchStatus {
     Idle,
@@ -37,6 +87,17 @@ pub fn Settings() -> Element {
     let mut app_log = use_context::<Signal<Vec<(String, String)>>>();
 
     let current_theme = *theme.read();
+    let current_tz = state.read().exchange_tz.name().to_string();
+    let carry_positions = state.read().carry_positions;
+    let lot_method = state.read().lot_method;
+    let storage_kind = settings_store::storage_kind();
+    let mut tz_options: Vec<(String, String)> = EXCHANGE_TIMEZONES
+        .iter()
+        .map(|(name, label)| (name.to_string(), label.to_string()))
+        .collect();
+    if !tz_options.iter().any(|(name, _)| *name == current_tz) {
+        tz_options.push((current_tz.clone(), current_tz.clone()));
+    }
 
     let saved_settings = settings_store::load_raw();
     let mut flex_token = use_signal(|| saved_settings.as_ref().map(|s| s.flex_token.clone()).unwrap_or_default());
@@ -55,7 +116,7 @@ pub fn Settings() -> Element {
         .filter(|d| !data.is_day_excluded(&d.date.date_naive().to_string()))
         .cloned()
         .collect();
-    let filtered_weekly = crate::analytics::TradingAnalytics::calculate_weekly_from_daily(&filtered_daily);
+    let filtered_weekly = trader_rank_core::analytics::TradingAnalytics::calculate_weekly_from_daily(&filtered_daily);
     let mut rows: Vec<RConfigRow> = filtered_weekly.iter().map(|w| {
         let week_start_date = w.start_date.date_naive();
         let r_val = data.r_value_for_week(week_start_date);
@@ -114,6 +175,89 @@ pub fn Settings() -> Element {
                 }
             }
 
+            // Exchange timezone — compact single-row card
+            div { class: "card",
+                div { class: "setting-row",
+                    span { class: "setting-label", "Exchange Timezone" }
+                    select {
+                        class: "flex-input tz-select",
+                        value: "{current_tz}",
+                        onchange: move |e: Event<FormData>| {
+                            let name = e.value().to_string();
+                            settings_store::update(|s| s.exchange_timezone = name.clone());
+                            crate::log_message(&mut app_log, &format!("Exchange timezone set to {}. Reloading...", name));
+                            crate::reload_app_state(&mut state, &mut app_log);
+                        },
+                        for (name, label) in tz_options.iter() {
+                            option { value: "{name}", selected: *name == current_tz, "{label}" }
+                        }
+                    }
+                }
+                p { class: "setting-desc muted",
+                    "Trading days, hours and sessions are bucketed in this timezone, and IB statements and Trades CSVs are read as local times in it. Import profiles declare their own timezone."
+                }
+            }
+
+            // Position carrying — compact single-row card
+            div { class: "card",
+                div { class: "setting-row",
+                    span { class: "setting-label", "Overnight Positions" }
+                    div { class: "toggle-group",
+                        button {
+                            class: if !carry_positions { "toggle-btn active" } else { "toggle-btn" },
+                            onclick: move |_| set_carry_positions(false, &mut state, &mut app_log),
+                            "Day trades only"
+                        }
+                        button {
+                            class: if carry_positions { "toggle-btn active" } else { "toggle-btn" },
+                            onclick: move |_| set_carry_positions(true, &mut state, &mut app_log),
+                            "Carry overnight"
+                        }
+                    }
+                }
+                p { class: "setting-desc muted",
+                    "Carrying positions overnight matches holds closed on a later day as swing trades, counted on the day they close. Day trades only starts each day flat and skips positions left open."
+                }
+            }
+
+            // Lot matching — compact single-row card
+            div { class: "card",
+                div { class: "setting-row",
+                    span { class: "setting-label", "Lot Matching" }
+                    div { class: "toggle-group",
+                        for method in LotMethod::ALL {
+                            button {
+                                class: if method == lot_method { "toggle-btn active" } else { "toggle-btn" },
+                                onclick: move |_| set_lot_method(method, &mut state, &mut app_log),
+                                "{method}"
+                            }
+                        }
+                    }
+                }
+                p { class: "setting-desc muted",
+                    "Which open lots a closing fill is matched against. FIFO, LIFO and Specific ID keep each opening fill as its own lot, so every round trip has its exact entry time and price; Specific ID uses a Trades CSV's Closes Lot column. Average cost pools open fills at their average price."
+                }
+            }
+
+            // Storage backend — compact single-row card
+            div { class: "card",
+                div { class: "setting-row",
+                    span { class: "setting-label", "Storage" }
+                    div { class: "toggle-group",
+                        for kind in StoreKind::ALL {
+                            button {
+                                class: if kind == storage_kind { "toggle-btn active" } else { "toggle-btn" },
+                                onclick: move |_| set_storage_kind(kind, &mut state, &mut app_log),
+                                "{kind}"
+                            }
+                        }
+                    }
+                }
+                p { class: "setting-desc muted",
+                    "SQLite keeps imported trades, matched trades, exclusions and R configs in one database (traderrank.db in the app data folder) that other tools can query. JSON files keeps everything in settings.json."
+                }
+            }
+
             // R-Unit Configuration
             div { class: "card",
                 h3 { class: "card-title", "Risk Unit (R) Configuration" }
@@ -125,7 +269,7 @@ pub fn Settings() -> Element {
                         thead {
                             tr {
                                 {
-                                    let cols = vec![
+                                    let cols = [
                                         ("week", "Week", true),
                                         ("r_value", "R Value ($)", false),
                                         ("pnl", "Week P&L", false),
@@ -301,7 +445,7 @@ pub fn Settings() -> Element {
                                     match crate::flex_fetcher::fetch_and_save(&token, &qid).await {
                                         Ok(count) => {
                                             crate::log_message(&mut app_log, &format!("Fetched {} trades from IB. Reloading...", count));
-                                            crate::reload_app_state(&mut state);
+                                            crate::reload_app_state(&mut state, &mut app_log);
                                             let msg = format!("Imported {} trades. Data reloaded.", count);
                                             crate::log_message(&mut app_log, &msg);
                                             fetch_status.set(FetchStatus::Success(msg));
@@ -358,8 +502,13 @@ pub fn Settings() -> Element {
                     }
                     for (ts, msg) in app_log.read().iter().rev() {
                         {
-                            let is_error = msg.starts_with("ERROR:");
-                            let line_class = if is_error { "log-line error" } else { "log-line" };
+                            let line_class = if msg.starts_with("ERROR:") {
+                                "log-line error"
+                            } else if msg.starts_with("WARN:") {
+                                "log-line warn"
+                            } else {
+                                "log-line"
+                            };
                             let ts = ts.clone();
                             let msg = msg.clone();
                             rsx! {
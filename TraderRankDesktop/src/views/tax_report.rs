@@ -0,0 +1,141 @@
+use dioxus::prelude::*;
+use crate::components::*;
+use crate::state::AppState;
+use crate::settings_store;
+use rust_decimal::Decimal;
+use trader_rank_core::tax::TaxReport;
+
+/// Tax lots of the loaded trades for one year, with a Form 8949 CSV export.
+///
+/// Lots are matched across days whatever the Overnight Positions setting, since
+/// a lot sold in one session can be bought in another.
+#[component]
+pub fn TaxReportPanel() -> Element {
+    let state = use_context::<Signal<AppState>>();
+    let mut app_log = use_context::<Signal<Vec<(String, String)>>>();
+    let data = state.read();
+
+    let report = TaxReport::build(&data.trades, settings_store::match_options());
+    let years = report.tax_years();
+    let mut selected_year = use_signal(|| None::<i32>);
+
+    let Some(&latest) = years.last() else {
+        return rsx! {
+            div { class: "card",
+                h3 { class: "card-title", "Tax Lots" }
+                p { "No closed lots available. Load CSV source files to build the tax lot report." }
+            }
+        };
+    };
+    let year = selected_year.read().filter(|y| years.contains(y)).unwrap_or(latest);
+    let totals = report.totals(year);
+    let lots: Vec<_> = report.lots_in_year(year).collect();
+
+    rsx! {
+        div { class: "window-controls",
+            span { class: "window-info", "Tax year:" }
+            for y in years.iter() {
+                {
+                    let y_val = *y;
+                    rsx! {
+                        button {
+                            class: if y_val == year { "range-tab active" } else { "range-tab" },
+                            onclick: move |_| selected_year.set(Some(y_val)),
+                            "{y_val}"
+                        }
+                    }
+                }
+            }
+            span { class: "window-info", "Lots matched by {report.lot_method}" }
+            button {
+                class: "fetch-btn",
+                onclick: move |_| {
+                    let report = TaxReport::build(&state.read().trades, settings_store::match_options());
+                    match export_form_8949(&report, year) {
+                        Ok(path) => crate::log_message(&mut app_log, &format!("Form 8949 rows for {} written to {}", year, path.display())),
+                        Err(e) => crate::log_message(&mut app_log, &format!("ERROR: {}", e)),
+                    }
+                },
+                "Export Form 8949 CSV"
+            }
+        }
+
+        div { class: "kpi-grid kpi-grid-4",
+            MetricCard {
+                label: "Short-Term".to_string(),
+                value: format_pnl(totals.short_term_gain),
+                subtitle: Some("Held one year or less".to_string()),
+                positive: Some(totals.short_term_gain >= Decimal::ZERO),
+            }
+            MetricCard {
+                label: "Long-Term".to_string(),
+                value: format_pnl(totals.long_term_gain),
+                subtitle: Some("Held over one year".to_string()),
+                positive: Some(totals.long_term_gain >= Decimal::ZERO),
+            }
+            MetricCard {
+                label: "Wash Sales".to_string(),
+                value: format!("{}", totals.wash_sales),
+                subtitle: Some(format!("{} disallowed", format_decimal(totals.disallowed_loss))),
+                positive: None,
+            }
+            MetricCard {
+                label: "Closed Lots".to_string(),
+                value: format!("{}", totals.lots),
+                subtitle: Some(format!("Sold in {}", year)),
+                positive: None,
+            }
+        }
+
+        div { class: "card",
+            h3 { class: "card-title", "Closed Lots" }
+            div { class: "timeline-table-wrap",
+                table { class: "timeline-table",
+                    thead {
+                        tr {
+                            th { "Description" }
+                            th { "Acquired" }
+                            th { "Sold" }
+                            th { "Term" }
+                            th { "Proceeds" }
+                            th { "Basis" }
+                            th { "Wash Sale" }
+                            th { "Gain/Loss" }
+                        }
+                    }
+                    tbody {
+                        for lot in lots.iter() {
+                            {
+                                let gain = lot.gain();
+                                let row_class = if gain >= Decimal::ZERO { "timeline-row positive" } else { "timeline-row negative" };
+                                let wash = if lot.is_wash_sale() { format_decimal(lot.disallowed_loss) } else { String::new() };
+                                rsx! {
+                                    tr { class: "{row_class}",
+                                        td { "{lot.description()}" }
+                                        td { "{lot.acquired}" }
+                                        td { "{lot.sold}" }
+                                        td { "{lot.term}" }
+                                        td { "{format_decimal(lot.proceeds)}" }
+                                        td { "{format_decimal(lot.cost_basis)}" }
+                                        td { "{wash}" }
+                                        td { class: "pnl", "{format_pnl(gain)}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Write the year's Form 8949 rows to the exports directory.
+fn export_form_8949(report: &TaxReport, year: i32) -> anyhow::Result<std::path::PathBuf> {
+    let dir = crate::app_dirs::exports_dir()
+        .ok_or_else(|| anyhow::anyhow!("LOCALAPPDATA is not set; nowhere to export to"))?;
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("form_8949_{}.csv", year));
+    report.write_form_8949(year, std::fs::File::create(&path)?)?;
+    Ok(path)
+}
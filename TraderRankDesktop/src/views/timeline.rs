@@ -99,7 +99,7 @@ pub fn Timeline() -> Element {
                 .filter(|d| !data.is_day_excluded(&d.date.date_naive().to_string()))
                 .cloned()
                 .collect();
-            let weekly = crate::analytics::TradingAnalytics::calculate_weekly_from_daily(&filtered_daily);
+            let weekly = trader_rank_core::analytics::TradingAnalytics::calculate_weekly_from_daily(&filtered_daily);
             weekly.iter().map(|w| {
                 let period = format!(
                     "Wk {} ({}  -  {})",
@@ -130,12 +130,10 @@ pub fn Timeline() -> Element {
                 .filter(|d| !data.is_day_excluded(&d.date.date_naive().to_string()))
                 .cloned()
                 .collect();
-            let monthly = crate::analytics::TradingAnalytics::calculate_monthly_from_daily(&filtered_daily);
+            let monthly = trader_rank_core::analytics::TradingAnalytics::calculate_monthly_from_daily(&filtered_daily);
             monthly.iter().filter_map(|m| {
                 let period = format!("{} {}", m.month_name, m.year);
-                let Some(first_of_month) = chrono::NaiveDate::from_ymd_opt(m.year, m.month, 1) else {
-                    return None;
-                };
+                let first_of_month = chrono::NaiveDate::from_ymd_opt(m.year, m.month, 1)?;
                 let days_from_monday = first_of_month.weekday().num_days_from_monday();
                 let monday_of_first_week = first_of_month - chrono::Duration::days(days_from_monday as i64);
                 let r_val = data.r_value_for_week(monday_of_first_week);
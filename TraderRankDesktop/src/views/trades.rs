@@ -16,6 +16,7 @@ pub fn Trades() -> Element {
     let data = state.read();
     let matched = &data.matched_trades;
     let hiding = *hide_excluded.read();
+    let exchange_tz = data.exchange_tz;
 
     // Summary stats: only non-excluded trades
     let active_trades: Vec<_> = matched.iter().filter(|t| !data.is_trade_excluded(t)).collect();
@@ -33,7 +34,7 @@ pub fn Trades() -> Element {
 
     // If "hide excluded" is on, only show non-excluded trades
     let mut sorted_trades: Vec<_> = if hiding {
-        active_trades.iter().map(|t| *t).collect()
+        active_trades.to_vec()
     } else {
         matched.iter().collect()
     };
@@ -82,7 +83,7 @@ pub fn Trades() -> Element {
     let mut day_pnls: std::collections::HashMap<String, Decimal> = std::collections::HashMap::new();
     for t in visible.iter() {
         if !data.is_trade_excluded(t) {
-            let day_key = t.exit_time.date_naive().to_string();
+            let day_key = t.exit_date(exchange_tz).to_string();
             *day_pnls.entry(day_key).or_insert(Decimal::ZERO) += t.net_pnl;
         }
     }
@@ -95,8 +96,8 @@ pub fn Trades() -> Element {
         trade_reason: String,
     }
     let excl_infos: Vec<TradeExclInfo> = visible.iter().map(|t| {
-        let day_str = t.exit_time.date_naive().to_string();
-        let trade_key = AppState::trade_exclusion_key(t);
+        let day_str = t.exit_date(exchange_tz).to_string();
+        let trade_key = data.trade_exclusion_key(t);
         let trade_excluded = data.exclusions.contains_key(&trade_key);
         let day_excluded = data.is_day_excluded(&day_str);
         let trade_reason = data.exclusions.get(&trade_key).cloned().unwrap_or_default();
@@ -106,14 +107,14 @@ pub fn Trades() -> Element {
     // Collect unique day dates for day separator exclusion info
     let mut day_excl_map: std::collections::HashMap<String, (String, bool, String)> = std::collections::HashMap::new();
     for t in visible.iter() {
-        let date_naive = t.exit_time.date_naive().to_string();
-        let day_display = t.exit_time.date_naive().to_string();
-        if !day_excl_map.contains_key(&day_display) {
+        let date_naive = t.exit_date(exchange_tz).to_string();
+        let day_display = t.exit_date(exchange_tz).to_string();
+        day_excl_map.entry(day_display).or_insert_with(|| {
             let day_key = AppState::day_exclusion_key(&date_naive);
             let is_excl = data.is_day_excluded(&date_naive);
             let reason = data.day_exclusion_reason(&date_naive);
-            day_excl_map.insert(day_display, (day_key, is_excl, reason));
-        }
+            (day_key, is_excl, reason)
+        });
     }
 
     rsx! {
@@ -245,7 +246,7 @@ pub fn Trades() -> Element {
                                         let idx = row_index;
                                         row_index += 1;
 
-                                        let day_key = trade.exit_time.date_naive().to_string();
+                                        let day_key = trade.exit_date(exchange_tz).to_string();
                                         let show_separator = day_key != last_day;
                                         last_day = day_key.clone();
 
@@ -256,12 +257,19 @@ pub fn Trades() -> Element {
                                         let is_pos = trade.net_pnl >= Decimal::ZERO;
                                         let pnl_class = if is_pos { "pnl positive" } else { "pnl negative" };
                                         let fills_str = format!("{}\u{2192}{}", trade.entry_fills, trade.exit_fills);
-                                        let time_str = trade.exit_time.format("%m/%d %H:%M").to_string();
+                                        let exit_local = trade.exit_time.with_timezone(&exchange_tz);
+                                        let time_str = exit_local.format("%m/%d %H:%M").to_string();
                                         let entry_str = format!("${:.2}", trade.entry_price);
                                         let exit_str = format!("${:.2}", trade.exit_price);
                                         let pnl_str = format_pnl(trade.net_pnl);
                                         let qty_str = trade.quantity.to_string();
                                         let symbol = trade.symbol.clone();
+                                        let asset_tag = trade.instrument.is_derivative()
+                                            .then(|| trade.instrument.asset_class.to_string());
+                                        // Swing trades show when they were opened
+                                        let swing_title = trade.is_swing().then(|| {
+                                            format!("Opened {}", trade.entry_time.with_timezone(&exchange_tz).format("%m/%d %H:%M"))
+                                        });
                                         let side = trade.side.clone();
                                         let side_class = if side == "Long" { "side buy" } else { "side sell" };
 
@@ -271,7 +279,7 @@ pub fn Trades() -> Element {
                                         let trade_reason = info.trade_reason.clone();
 
                                         // Pre-compute separator data (used inside rsx conditionally)
-                                        let day_label = trade.exit_time.format("%A, %b %d").to_string();
+                                        let day_label = exit_local.format("%A, %b %d").to_string();
                                         let day_total = day_pnls.get(&day_key).copied().unwrap_or(Decimal::ZERO);
                                         let day_pnl_str = format_pnl(day_total);
                                         let day_pnl_class = if day_total >= Decimal::ZERO { "positive" } else { "negative" };
@@ -340,7 +348,15 @@ pub fn Trades() -> Element {
                                             // Trade row (always rendered)
                                             tr { class: "{row_class_base}",
                                                 td { "{time_str}" }
-                                                td { class: "symbol", "{symbol}" }
+                                                td { class: "symbol",
+                                                    "{symbol}"
+                                                    if let Some(tag) = asset_tag {
+                                                        span { class: "asset-tag", "{tag}" }
+                                                    }
+                                                    if let Some(title) = swing_title {
+                                                        span { class: "asset-tag swing-tag", title: "{title}", "Swing" }
+                                                    }
+                                                }
                                                 td { class: "{side_class}", "{side}" }
                                                 td { "{qty_str}" }
                                                 td { "{entry_str}" }